@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use erl_parse::cst::Form;
+use erl_tokenize::PositionRange;
+use serde::{Deserialize, Serialize};
+use crate::diagnostic::Diagnostic;
+use crate::error::{IroncladError, IroncladResult};
+use crate::project::ErlProjectImpl;
+use crate::syntax;
+
+/// Default baseline file name, relative to the working directory
+pub const DEFAULT_BASELINE_FILE: &str = "ironclad-baseline.toml";
+
+/// A finding accepted into the baseline. Line numbers are not stored, so that unrelated edits which move
+/// the code around do not make the finding reappear; the fingerprint identifies the code instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub rule: String,
+    /// Module name, or the file name for findings in headers
+    pub module: String,
+    /// Enclosing function as `name/arity`, empty outside of functions
+    #[serde(default)]
+    pub function: String,
+    /// Hash of the source line with all whitespace removed
+    pub fingerprint: String,
+    /// For the humans reading the file, not compared
+    #[serde(default)]
+    pub message: String,
+}
+
+impl BaselineEntry {
+    fn key(&self) -> (String, String, String, String) {
+        (self.rule.clone(), self.module.clone(), self.function.clone(), self.fingerprint.clone())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BaselineFile {
+    #[serde(default)]
+    pub finding: Vec<BaselineEntry>,
+}
+
+impl BaselineFile {
+    pub fn load(path: &Path) -> IroncladResult<Self> {
+        let contents = std::fs::read_to_string(path).map_err(IroncladError::from)?;
+        Ok(toml::from_str(contents.as_str())?)
+    }
+
+    pub fn save(&self, path: &Path) -> IroncladResult<()> {
+        let contents = toml::to_string(self)?;
+        std::fs::write(path, contents).map_err(IroncladError::from)
+    }
+}
+
+/// Result of checking the current findings against a baseline
+#[derive(Debug, Default)]
+pub struct BaselineComparison {
+    /// Findings which are not in the baseline
+    pub new: Vec<Diagnostic>,
+    /// Baseline entries which no longer match any finding
+    pub fixed: Vec<BaselineEntry>,
+}
+
+/// Builds baseline entries for diagnostics, caching file contents and function locations
+pub struct Fingerprinter {
+    /// Module file path to module name and function line ranges
+    modules: HashMap<PathBuf, (String, Vec<(usize, usize, String)>)>,
+    file_lines: HashMap<PathBuf, Vec<String>>,
+}
+
+impl Fingerprinter {
+    pub fn new(project: &ErlProjectImpl) -> Self {
+        let mut modules = HashMap::new();
+        for unit in project.modules.read().unwrap().values() {
+            let functions = unit.forms.iter()
+                .filter_map(|form| match form {
+                    Form::FunDecl(decl) => syntax::fun_decl_name(decl).map(|name| {
+                        (form.start_position().line(), form.end_position().line(), name.to_string())
+                    }),
+                    _ => None,
+                })
+                .collect();
+            modules.insert(unit.path.clone(), (unit.name.clone(), functions));
+        }
+        Self { modules, file_lines: HashMap::new() }
+    }
+
+    pub fn entry(&mut self, diagnostic: &Diagnostic) -> BaselineEntry {
+        let span = &diagnostic.span;
+        let (module, function) = match self.modules.get(&span.file) {
+            Some((name, functions)) => {
+                let function = functions.iter()
+                    .find(|(first, last, _)| (*first..=*last).contains(&span.start_line))
+                    .map(|(_, _, name)| name.clone())
+                    .unwrap_or_default();
+                (name.clone(), function)
+            }
+            None => (span.file.file_name().unwrap_or_default().to_string_lossy().to_string(), String::new()),
+        };
+        let line = self.source_line(&span.file, span.start_line);
+        let normalized: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+        BaselineEntry {
+            rule: diagnostic.code.clone(),
+            module,
+            function,
+            fingerprint: format!("{:016x}", fnv1a_hash(normalized.as_bytes())),
+            message: diagnostic.message.clone(),
+        }
+    }
+
+    fn source_line(&mut self, file: &Path, line: usize) -> String {
+        let lines = self.file_lines.entry(file.to_path_buf()).or_insert_with(|| {
+            std::fs::read_to_string(file)
+                .map(|text| text.lines().map(|l| l.to_string()).collect())
+                .unwrap_or_default()
+        });
+        lines.get(line.saturating_sub(1)).cloned().unwrap_or_default()
+    }
+}
+
+/// Record all current findings as the new baseline
+pub fn write_baseline(project: &ErlProjectImpl, diagnostics: &[Diagnostic], path: &Path) -> IroncladResult<usize> {
+    let mut fingerprinter = Fingerprinter::new(project);
+    let baseline = BaselineFile {
+        finding: diagnostics.iter().map(|d| fingerprinter.entry(d)).collect(),
+    };
+    baseline.save(path)?;
+    Ok(baseline.finding.len())
+}
+
+/// Split findings into new ones and baseline entries which were fixed. Identical entries are counted, so
+/// a second copy of an accepted finding in the same function is reported as new.
+pub fn compare(project: &ErlProjectImpl, diagnostics: Vec<Diagnostic>, baseline: &BaselineFile) -> BaselineComparison {
+    let mut remaining: HashMap<(String, String, String, String), Vec<&BaselineEntry>> = HashMap::new();
+    for entry in baseline.finding.iter() {
+        remaining.entry(entry.key()).or_default().push(entry);
+    }
+
+    let mut fingerprinter = Fingerprinter::new(project);
+    let mut comparison = BaselineComparison::default();
+    for diagnostic in diagnostics {
+        let entry = fingerprinter.entry(&diagnostic);
+        let matched = remaining.get_mut(&entry.key()).and_then(|entries| entries.pop());
+        if matched.is_none() {
+            comparison.new.push(diagnostic);
+        }
+    }
+    comparison.fixed = remaining.into_values().flatten().cloned().collect();
+    comparison.fixed.sort_by_key(|entry| entry.key());
+    comparison
+}
+
+/// FNV-1a, stable between runs and Rust versions unlike the std hasher
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
@@ -0,0 +1,172 @@
+use std::collections::{HashMap, HashSet};
+use erl_parse::cst::Form;
+use crate::diagnostic::{Diagnostic, Severity, SourceSpan};
+use crate::project::ErlProjectImpl;
+use crate::project::compile_unit::CompileUnit;
+use crate::syntax;
+use crate::syntax::mfa::FunArity;
+
+pub mod otp_callbacks;
+
+/// A behaviour callback, implemented by exporting the name with any one of `arities`
+#[derive(Debug, Clone)]
+pub struct Callback {
+    pub name: String,
+    pub arities: Vec<usize>,
+    pub optional: bool,
+}
+
+impl Callback {
+    fn is_exported(&self, exported: &HashSet<FunArity>) -> bool {
+        exported.iter().any(|f| f.name == self.name && self.arities.contains(&f.arity))
+    }
+
+    /// `name/arity`, or the alternatives joined with "or"
+    fn describe(&self) -> String {
+        let funs: Vec<String> = self.arities.iter()
+            .map(|arity| FunArity::new(&self.name, *arity).to_string())
+            .collect();
+        funs.join(" or ")
+    }
+}
+
+/// Callbacks a behaviour module declares with `-callback` and `-optional_callbacks`. Optional callbacks with
+/// the same name are alternatives, like `format_status/1` and `format_status/2` of gen_server.
+pub fn declared_callbacks(unit: &CompileUnit) -> Vec<Callback> {
+    let optional: HashSet<FunArity> = syntax::wild_attributes(&unit.forms, "optional_callbacks")
+        .flat_map(syntax::attribute_values)
+        .filter_map(syntax::expr_fun_arity)
+        .collect();
+    let mut callbacks: Vec<Callback> = Vec::new();
+    for form in unit.forms.iter() {
+        let Form::CallbackSpec(spec) = form else { continue };
+        let Some(clause) = spec.clauses.iter().next() else { continue };
+        let fun = FunArity::new(spec.callback_name.value(), clause.args.iter().count());
+        let is_optional = optional.contains(&fun);
+        match callbacks.iter_mut().find(|c| is_optional && c.optional && c.name == fun.name) {
+            Some(callback) => callback.arities.push(fun.arity),
+            None => callbacks.push(Callback { name: fun.name, arities: vec![fun.arity], optional: is_optional }),
+        }
+    }
+    callbacks
+}
+
+/// Callbacks of a behaviour, from a project module with `-callback` attributes or from the built-in OTP table
+fn behaviour_callbacks(behaviour: &str, project_behaviours: &HashMap<String, Vec<Callback>>) -> Option<Vec<Callback>> {
+    if let Some(callbacks) = project_behaviours.get(behaviour) {
+        return Some(callbacks.clone());
+    }
+    otp_callbacks::otp_behaviour_callbacks(behaviour).map(|table| {
+        table.iter()
+            .map(|(name, arities, optional)| Callback {
+                name: name.to_string(),
+                arities: arities.to_vec(),
+                optional: *optional,
+            })
+            .collect()
+    })
+}
+
+/// Check every module with `-behaviour(X)` against the callbacks of X
+pub fn analyze(project: &ErlProjectImpl) -> Vec<Diagnostic> {
+    let modules = project.modules.read().unwrap();
+    let project_behaviours: HashMap<String, Vec<Callback>> = modules.values()
+        .map(|unit| (unit.name.clone(), declared_callbacks(unit)))
+        .filter(|(_, callbacks)| !callbacks.is_empty())
+        .collect();
+    let mut diagnostics = Vec::new();
+
+    for unit in modules.values() {
+        let exported = if syntax::has_export_all(&unit.forms) {
+            syntax::defined_functions(&unit.forms)
+        } else {
+            syntax::exported_functions(&unit.forms)
+        };
+        for attr_value in syntax::behaviour_attributes(&unit.forms) {
+            let Some(behaviour) = syntax::expr_atom(attr_value) else { continue };
+            let span = SourceSpan::from_range(attr_value, &unit.path);
+            match behaviour_callbacks(behaviour, &project_behaviours) {
+                Some(callbacks) => check_callbacks(unit, behaviour, &callbacks, &exported, span, &mut diagnostics),
+                None => diagnostics.push(Diagnostic::new(
+                    Severity::Warning, "unknown_behaviour",
+                    format!("Behaviour {} is not an OTP behaviour and not found in the project", behaviour),
+                    span)),
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Required callbacks which are not exported are errors. Optional callbacks are only reported when their name
+/// is exported with another arity, which is most likely a mistake; leaving them out is normal.
+fn check_callbacks(unit: &CompileUnit, behaviour: &str, callbacks: &[Callback], exported: &HashSet<FunArity>,
+                   span: SourceSpan, diagnostics: &mut Vec<Diagnostic>) {
+    for callback in callbacks.iter().filter(|c| !c.is_exported(exported)) {
+        let mut other_arities: Vec<String> = exported.iter()
+            .filter(|f| f.name == callback.name)
+            .map(|f| f.to_string())
+            .collect();
+        other_arities.sort();
+        let arity_hint = if other_arities.is_empty() {
+            String::new()
+        } else {
+            format!(" (exported as {} instead)", other_arities.join(", "))
+        };
+        let (severity, code, kind) = match (callback.optional, other_arities.is_empty()) {
+            (true, true) => continue,
+            (true, false) => (Severity::Warning, "optional_callback", "Optional callback"),
+            (false, _) => (Severity::Error, "missing_callback", "Callback"),
+        };
+        diagnostics.push(Diagnostic::new(
+            severity, code,
+            format!("{} {} of behaviour {} is not exported by {}{}", kind, callback.describe(), behaviour,
+                    unit.name, arity_hint),
+            span.clone()));
+    }
+
+    // Exported functions which are not callbacks but have a name close to one
+    let callback_names: HashSet<&str> = callbacks.iter().map(|c| c.name.as_str()).collect();
+    let mut suspicious: Vec<&FunArity> = exported.iter()
+        .filter(|f| f.name.len() > 3 && !callback_names.contains(f.name.as_str()))
+        .collect();
+    suspicious.sort();
+    for fun in suspicious {
+        let close = callbacks.iter()
+            .find(|c| c.arities.contains(&fun.arity) && (1..=2).contains(&edit_distance(&fun.name, &c.name)));
+        if let Some(callback) = close {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning, "misspelled_callback",
+                format!("Exported {} looks like a misspelled callback {} of behaviour {}", fun,
+                        FunArity::new(&callback.name, fun.arity), behaviour),
+                span.clone()));
+        }
+    }
+}
+
+/// Levenshtein distance between two names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev_row: Vec<usize> = (0..=b_chars.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b_chars.len() + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = prev_row[j] + if a_char == *b_char { 0 } else { 1 };
+            row[j + 1] = substitution.min(prev_row[j + 1] + 1).min(row[j] + 1);
+        }
+        prev_row = row;
+    }
+    prev_row[b_chars.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::edit_distance;
+
+    #[test]
+    fn edit_distance_works() {
+        assert_eq!(edit_distance("handle_call", "handle_call"), 0);
+        assert_eq!(edit_distance("handle_cal", "handle_call"), 1);
+        assert_eq!(edit_distance("hadnle_call", "handle_call"), 2);
+        assert_eq!(edit_distance("", "init"), 4);
+    }
+}
@@ -0,0 +1,64 @@
+/// Callbacks of a behaviour: name, arities and whether the callback is optional. A callback with several
+/// arities is implemented by exporting any one of them.
+pub type CallbackTable = &'static [(&'static str, &'static [usize], bool)];
+
+const GEN_SERVER: CallbackTable = &[
+    ("init", &[1], false),
+    ("handle_call", &[3], false),
+    ("handle_cast", &[2], false),
+    ("handle_info", &[2], true),
+    ("handle_continue", &[2], true),
+    ("terminate", &[2], true),
+    ("code_change", &[3], true),
+    ("format_status", &[1, 2], true),
+];
+
+/// State callbacks of gen_statem are named after the states, only the fixed ones are listed
+const GEN_STATEM: CallbackTable = &[
+    ("init", &[1], false),
+    ("callback_mode", &[0], false),
+    ("handle_event", &[4], true),
+    ("terminate", &[3], true),
+    ("code_change", &[4], true),
+    ("format_status", &[1, 2], true),
+];
+
+const SUPERVISOR: CallbackTable = &[
+    ("init", &[1], false),
+];
+
+const APPLICATION: CallbackTable = &[
+    ("start", &[2], false),
+    ("stop", &[1], false),
+    ("prep_stop", &[1], true),
+    ("start_phase", &[3], true),
+    ("config_change", &[3], true),
+];
+
+const GEN_EVENT: CallbackTable = &[
+    ("init", &[1], false),
+    ("handle_event", &[2], false),
+    ("handle_call", &[2], false),
+    ("handle_info", &[2], true),
+    ("terminate", &[2], true),
+    ("code_change", &[3], true),
+    ("format_status", &[1, 2], true),
+];
+
+const SUPERVISOR_BRIDGE: CallbackTable = &[
+    ("init", &[1], false),
+    ("terminate", &[2], false),
+];
+
+/// Callback table for a behaviour shipped with OTP
+pub fn otp_behaviour_callbacks(behaviour: &str) -> Option<CallbackTable> {
+    match behaviour {
+        "gen_server" => Some(GEN_SERVER),
+        "gen_statem" => Some(GEN_STATEM),
+        "supervisor" => Some(SUPERVISOR),
+        "application" => Some(APPLICATION),
+        "gen_event" => Some(GEN_EVENT),
+        "supervisor_bridge" => Some(SUPERVISOR_BRIDGE),
+        _ => None,
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use erl_parse::cst::{Expr, Form};
+use crate::diagnostic::SourceSpan;
+use crate::project::ErlProjectImpl;
+use crate::project::compile_unit::CompileUnit;
+use crate::syntax;
+use crate::syntax::mfa::{FunArity, MFArity};
+use crate::syntax::walk::{walk_fun_clause, Visitor};
+
+/// How the callee was reached from the caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    /// `foo()` calling a function defined in the same module
+    Local,
+    /// `foo()` resolved through an `-import` attribute
+    Imported,
+    /// `foo()` not defined in the module, assumed to be an auto-imported BIF from `erlang`
+    AutoImported,
+    /// `mod:foo()` with literal module and function names
+    Remote,
+    /// `fun foo/1` or `fun mod:foo/1`
+    FunRef,
+    /// `apply(M, F, Args)` and alike with literal M, F and a proper list of Args
+    Apply,
+    /// `spawn(M, F, Args)` and alike with literal M, F and a proper list of Args
+    Spawn,
+}
+
+impl Display for CallKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            CallKind::Local => "local",
+            CallKind::Imported => "imported",
+            CallKind::AutoImported => "bif",
+            CallKind::Remote => "remote",
+            CallKind::FunRef => "fun",
+            CallKind::Apply => "apply",
+            CallKind::Spawn => "spawn",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// One call site: `caller` calls `callee`, `span` covers the call expression
+#[derive(Debug, Clone)]
+pub struct CallEdge {
+    pub caller: MFArity,
+    pub callee: MFArity,
+    pub kind: CallKind,
+    pub span: SourceSpan,
+}
+
+impl Display for CallEdge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {} ({}, {}:{})", self.caller, self.callee, self.kind, self.span.file.to_string_lossy(),
+               self.span.start_line)
+    }
+}
+
+/// Functions which call a MFA given in their arguments.
+/// Columns: module, function, arity, position of the module argument (function and args follow it), kind
+const INDIRECT_CALLS: &[(&str, &str, usize, usize, CallKind)] = &[
+    ("erlang", "apply", 3, 0, CallKind::Apply),
+    ("erlang", "spawn", 3, 0, CallKind::Spawn),
+    ("erlang", "spawn", 4, 1, CallKind::Spawn),
+    ("erlang", "spawn_link", 3, 0, CallKind::Spawn),
+    ("erlang", "spawn_link", 4, 1, CallKind::Spawn),
+    ("erlang", "spawn_monitor", 3, 0, CallKind::Spawn),
+    ("erlang", "spawn_opt", 4, 0, CallKind::Spawn),
+    ("erlang", "spawn_opt", 5, 1, CallKind::Spawn),
+    ("proc_lib", "spawn", 3, 0, CallKind::Spawn),
+    ("proc_lib", "spawn_link", 3, 0, CallKind::Spawn),
+    ("proc_lib", "start", 3, 0, CallKind::Spawn),
+    ("proc_lib", "start_link", 3, 0, CallKind::Spawn),
+    ("rpc", "call", 4, 1, CallKind::Apply),
+    ("rpc", "cast", 4, 1, CallKind::Apply),
+    ("timer", "apply_after", 4, 1, CallKind::Apply),
+    ("timer", "apply_interval", 4, 1, CallKind::Apply),
+];
+
+/// Function level call graph of all modules in the project
+#[derive(Default, Debug)]
+pub struct CallGraph {
+    edges: Vec<CallEdge>,
+    /// Indices into `edges` grouped by caller
+    outgoing: HashMap<MFArity, Vec<usize>>,
+    /// Indices into `edges` grouped by callee
+    incoming: HashMap<MFArity, Vec<usize>>,
+}
+
+impl CallGraph {
+    pub fn build(project: &ErlProjectImpl) -> Self {
+        let mut graph = CallGraph::default();
+        let modules = project.modules.read().unwrap();
+        for unit in modules.values() {
+            for edge in collect_calls(unit) {
+                graph.add_edge(edge);
+            }
+        }
+        graph
+    }
+
+    fn add_edge(&mut self, edge: CallEdge) {
+        let index = self.edges.len();
+        self.outgoing.entry(edge.caller.clone()).or_default().push(index);
+        self.incoming.entry(edge.callee.clone()).or_default().push(index);
+        self.edges.push(edge);
+    }
+
+    pub fn edges(&self) -> &[CallEdge] {
+        &self.edges
+    }
+
+    /// Call sites where `mfa` is called
+    pub fn callers(&self, mfa: &MFArity) -> Vec<&CallEdge> {
+        self.incoming.get(mfa).map(|ids| ids.iter().map(|i| &self.edges[*i]).collect()).unwrap_or_default()
+    }
+
+    /// Call sites inside of `mfa`
+    pub fn callees(&self, mfa: &MFArity) -> Vec<&CallEdge> {
+        self.outgoing.get(mfa).map(|ids| ids.iter().map(|i| &self.edges[*i]).collect()).unwrap_or_default()
+    }
+
+    /// Shortest chain of calls leading from `from` to `to` (breadth-first search)
+    pub fn find_path(&self, from: &MFArity, to: &MFArity) -> Option<Vec<&CallEdge>> {
+        let mut came_by: HashMap<&MFArity, usize> = HashMap::new();
+        let mut visited: HashSet<&MFArity> = HashSet::from([from]);
+        let mut queue: VecDeque<&MFArity> = VecDeque::from([from]);
+
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = Vec::new();
+                let mut node = current;
+                while let Some(edge_index) = came_by.get(node) {
+                    let edge = &self.edges[*edge_index];
+                    path.push(edge);
+                    node = &edge.caller;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for edge_index in self.outgoing.get(current).into_iter().flatten() {
+                let callee = &self.edges[*edge_index].callee;
+                if visited.insert(callee) {
+                    came_by.insert(callee, *edge_index);
+                    queue.push_back(callee);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Resolves function names called from one module: local functions, imports and auto-imported BIFs
+#[derive(Debug)]
+pub struct CallResolver {
+    module: String,
+    defined: HashSet<FunArity>,
+    imports: HashMap<FunArity, String>,
+}
+
+impl CallResolver {
+    pub fn new(unit: &CompileUnit) -> Self {
+        Self {
+            module: unit.name.clone(),
+            defined: syntax::defined_functions(&unit.forms),
+            imports: syntax::imported_functions(&unit.forms),
+        }
+    }
+
+    /// Resolve a call without module prefix: local function, import or an auto-imported BIF
+    pub fn resolve_local(&self, name: &str, arity: usize) -> (MFArity, CallKind) {
+        let fun_arity = FunArity::new(name, arity);
+        if self.defined.contains(&fun_arity) {
+            (MFArity::new(&self.module, name, arity), CallKind::Local)
+        } else if let Some(module) = self.imports.get(&fun_arity) {
+            (MFArity::new(module, name, arity), CallKind::Imported)
+        } else {
+            (MFArity::new("erlang", name, arity), CallKind::AutoImported)
+        }
+    }
+
+    /// Callee and arguments of a `name(...)` or `module:name(...)` expression with literal names
+    pub fn resolve_call<'e>(&self, expr: &'e Expr) -> Option<(MFArity, Vec<&'e Expr>)> {
+        match expr {
+            Expr::LocalCall(call) => {
+                let name = syntax::expr_atom(&call.func)?;
+                let args: Vec<&Expr> = call.args.iter().collect();
+                Some((self.resolve_local(name, args.len()).0, args))
+            }
+            Expr::RemoteCall(call) => {
+                let (module, name) = (syntax::expr_atom(&call.module_name)?, syntax::expr_atom(&call.func)?);
+                let args: Vec<&Expr> = call.args.iter().collect();
+                Some((MFArity::new(module, name, args.len()), args))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Walk all function declarations of a compile unit and record the calls they make
+pub fn collect_calls(unit: &CompileUnit) -> Vec<CallEdge> {
+    let resolver = CallResolver::new(unit);
+    let mut edges = Vec::new();
+
+    for form in unit.forms.iter() {
+        let Form::FunDecl(decl) = form else { continue };
+        let Some(fun_arity) = syntax::fun_decl_name(decl) else { continue };
+        let mut collector = CallCollector {
+            unit,
+            resolver: &resolver,
+            caller: MFArity::new(&unit.name, &fun_arity.name, fun_arity.arity),
+            edges: Vec::new(),
+        };
+        for clause in decl.clauses.iter() {
+            walk_fun_clause(&mut collector, clause);
+        }
+        edges.append(&mut collector.edges);
+    }
+    edges
+}
+
+struct CallCollector<'a> {
+    unit: &'a CompileUnit,
+    resolver: &'a CallResolver,
+    caller: MFArity,
+    edges: Vec<CallEdge>,
+}
+
+impl<'a> CallCollector<'a> {
+    fn add(&mut self, callee: MFArity, kind: CallKind, expr: &Expr) {
+        self.edges.push(CallEdge {
+            caller: self.caller.clone(),
+            callee,
+            kind,
+            span: SourceSpan::from_range(expr, &self.unit.path),
+        });
+    }
+
+    /// For `apply`/`spawn` style calls with literal arguments, add the edge to the MFA they will call
+    fn add_indirect(&mut self, callee: &MFArity, args: &[&Expr], expr: &Expr) {
+        let found = INDIRECT_CALLS.iter().find(|(m, f, a, _, _)| {
+            *m == callee.module && *f == callee.name && *a == callee.arity
+        });
+        let Some((_, _, _, m_index, kind)) = found else { return };
+        let (Some(m), Some(f), Some(a)) = (args.get(*m_index), args.get(m_index + 1), args.get(m_index + 2))
+            else { return };
+        if let (Some(module), Some(name), Some(arity)) =
+            (syntax::expr_atom(m), syntax::expr_atom(f), syntax::proper_list_len(a)) {
+            self.add(MFArity::new(module, name, arity), *kind, expr);
+        }
+    }
+}
+
+impl<'a> Visitor for CallCollector<'a> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::LocalCall(call) => {
+                let Some(name) = syntax::expr_atom(&call.func) else { return };
+                let args: Vec<&Expr> = call.args.iter().collect();
+                let (callee, kind) = self.resolver.resolve_local(name, args.len());
+                self.add_indirect(&callee, &args, expr);
+                self.add(callee, kind, expr);
+            }
+            Expr::RemoteCall(call) => {
+                let (Some(module), Some(name)) = (syntax::expr_atom(&call.module_name), syntax::expr_atom(&call.func))
+                    else { return };
+                let args: Vec<&Expr> = call.args.iter().collect();
+                let callee = MFArity::new(module, name, args.len());
+                self.add_indirect(&callee, &args, expr);
+                self.add(callee, CallKind::Remote, expr);
+            }
+            Expr::LocalFun(fun) => {
+                let Some(arity) = syntax::integer_value(&fun.arity) else { return };
+                let (callee, _) = self.resolver.resolve_local(fun.fun_name.value(), arity);
+                self.add(callee, CallKind::FunRef, expr);
+            }
+            Expr::RemoteFun(fun) => {
+                let module = fun.module_name.as_atom().map(|a| a.value());
+                let name = fun.fun_name.as_atom().map(|a| a.value());
+                let arity = fun.arity.as_integer().and_then(syntax::integer_value);
+                if let (Some(module), Some(name), Some(arity)) = (module, name, arity) {
+                    self.add(MFArity::new(module, name, arity), CallKind::FunRef, expr);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::path::{Path, PathBuf};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use crate::baseline;
+use crate::baseline::{BaselineFile, DEFAULT_BASELINE_FILE};
+use crate::callgraph::{CallEdge, CallGraph};
+use crate::diagnostic::fix::apply_fixes;
+use crate::diagnostic::sort_diagnostics;
+use crate::error::{IroncladError, IroncladResult};
+use crate::include_graph::IncludeGraph;
+use crate::lint::config::LintsConfig;
+use crate::lint::registry::RuleRegistry;
+use crate::metrics;
+use crate::metrics::MetricsFormat;
+use crate::project::ErlProjectImpl;
+use crate::syntax::mfa::MFArity;
+
+/// Command line definition. Running without a subcommand is the same as `ironclad check`
+pub fn build_command() -> Command {
+    Command::new("ironclad")
+        .about("Erlang Code Quality, Code Style and Type Inference Tool")
+        .arg(Arg::new("config")
+            .long("config")
+            .value_name("FILE")
+            .default_value("ironclad.toml")
+            .help("Project file to load"))
+        .subcommand(Command::new("check")
+            .about("Parse all input files and report findings")
+            .arg(Arg::new("baseline")
+                .long("baseline")
+                .value_name("FILE")
+                .num_args(0..=1)
+                .default_missing_value(DEFAULT_BASELINE_FILE)
+                .help("Only report findings which are not in the baseline file"))
+            .arg(Arg::new("fix")
+                .long("fix")
+                .action(ArgAction::SetTrue)
+                .help("Apply the automatic fixes of reported findings to the source files")))
+        .subcommand(Command::new("baseline")
+            .about("Manage the baseline of accepted findings")
+            .subcommand_required(true)
+            .subcommand(Command::new("write")
+                .about("Record all current findings to the baseline file")
+                .arg(Arg::new("file")
+                    .long("file")
+                    .value_name("FILE")
+                    .default_value(DEFAULT_BASELINE_FILE))))
+        .subcommand(Command::new("rules")
+            .about("List lint rules with their configured severity"))
+        .subcommand(Command::new("metrics")
+            .about("Print complexity and size metrics of every function")
+            .arg(Arg::new("format")
+                .long("format")
+                .value_parser(["table", "csv", "json"])
+                .default_value("table"))
+            .arg(Arg::new("modules")
+                .long("modules")
+                .action(ArgAction::SetTrue)
+                .help("Print module totals instead of functions (table and csv)")))
+        .subcommand(Command::new("callers")
+            .about("List call sites of a function")
+            .arg(mfa_arg("MFA")))
+        .subcommand(Command::new("callees")
+            .about("List calls made by a function")
+            .arg(mfa_arg("MFA")))
+        .subcommand(Command::new("includes")
+            .about("Print the include graph: which file includes which header"))
+        .subcommand(Command::new("path")
+            .about("Find the shortest call chain from one function to another")
+            .arg(mfa_arg("FROM"))
+            .arg(mfa_arg("TO")))
+}
+
+fn mfa_arg(name: &'static str) -> Arg {
+    Arg::new(name).required(true).help("Function as module:name/arity")
+}
+
+fn get_mfa(matches: &ArgMatches, name: &str) -> IroncladResult<MFArity> {
+    matches.get_one::<String>(name)
+        .ok_or_else(|| IroncladError::CommandLine(format!("Missing argument {}", name)))?
+        .parse()
+}
+
+/// Exit status of `check` when it reports findings, so that CI jobs fail on new findings
+pub const FINDINGS_EXIT_CODE: i32 = 1;
+
+/// Run the subcommand selected on the command line, the project is already parsed.
+/// Returns the process exit status.
+pub fn run_command(matches: &ArgMatches, project: &ErlProjectImpl) -> IroncladResult<i32> {
+    match matches.subcommand() {
+        Some(("callers", sub)) => {
+            let mfa = get_mfa(sub, "MFA")?;
+            print_edges(&CallGraph::build(project).callers(&mfa));
+        }
+        Some(("callees", sub)) => {
+            let mfa = get_mfa(sub, "MFA")?;
+            print_edges(&CallGraph::build(project).callees(&mfa));
+        }
+        Some(("path", sub)) => {
+            let (from, to) = (get_mfa(sub, "FROM")?, get_mfa(sub, "TO")?);
+            match CallGraph::build(project).find_path(&from, &to) {
+                Some(path) => print_edges(&path),
+                None => println!("No call path from {} to {}", from, to),
+            }
+        }
+        Some(("includes", _)) => {
+            for include in IncludeGraph::build(project).edges() {
+                let resolved = include.resolved.as_ref()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|| format!("{} (not found)", include.path));
+                println!("{}:{} -{} {}", include.included_from.to_string_lossy(), include.line,
+                         include.kind.as_str(), resolved);
+            }
+        }
+        Some(("rules", _)) => {
+            for active in configured_rules(project)?.rules() {
+                let state = if active.enabled { active.severity.to_string() } else { "disabled".to_string() };
+                let codes = active.rule.codes();
+                let codes = if codes.is_empty() { String::new() } else { format!(" ({})", codes.join(", ")) };
+                println!("{:<28} {:<9} {}{}", active.rule.id(), state, active.rule.description(), codes);
+            }
+        }
+        Some(("metrics", sub)) => {
+            let format: MetricsFormat = sub.get_one::<String>("format").unwrap().parse()?;
+            let output = metrics::render(&metrics::collect(project), format, sub.get_flag("modules"))?;
+            print!("{}", output);
+        }
+        Some(("baseline", sub)) => {
+            if let Some(("write", write)) = sub.subcommand() {
+                let path = PathBuf::from(write.get_one::<String>("file").unwrap());
+                let diagnostics = configured_rules(project)?.run(project)?;
+                let count = baseline::write_baseline(project, &diagnostics, &path)?;
+                println!("Baseline with {} findings written to {}", count, path.to_string_lossy());
+            }
+        }
+        Some(("check", sub)) => return run_check(project, sub.get_one::<String>("baseline"), sub.get_flag("fix")),
+        None => return run_check(project, None, false),
+        _ => {}
+    }
+    Ok(0)
+}
+
+/// Rule registry configured from the `[lints]` section of the project file
+fn configured_rules(project: &ErlProjectImpl) -> IroncladResult<RuleRegistry> {
+    let mut registry = RuleRegistry::new();
+    registry.configure(LintsConfig::from_table(project.project_conf.lints.as_ref())?)?;
+    Ok(registry)
+}
+
+/// Run all enabled rules and print the findings. With a baseline only new findings are printed,
+/// followed by the baseline entries which are fixed now. With `fix` the automatic fixes of the printed
+/// findings are written to the source files. Exits with `FINDINGS_EXIT_CODE` if anything was printed.
+fn run_check(project: &ErlProjectImpl, baseline_file: Option<&String>, fix: bool) -> IroncladResult<i32> {
+    let diagnostics = configured_rules(project)?.run(project)?;
+    let (mut diagnostics, fixed) = match baseline_file {
+        Some(path) => {
+            let baseline = BaselineFile::load(Path::new(path))?;
+            let comparison = baseline::compare(project, diagnostics, &baseline);
+            (comparison.new, comparison.fixed)
+        }
+        None => (diagnostics, Vec::new()),
+    };
+
+    sort_diagnostics(&mut diagnostics);
+    for diagnostic in diagnostics.iter() {
+        println!("{}", diagnostic);
+    }
+    if !fixed.is_empty() {
+        println!("Fixed since the baseline was written ({}):", fixed.len());
+        for entry in fixed.iter() {
+            let function = if entry.function.is_empty() { String::new() } else { format!(":{}", entry.function) };
+            println!("  {}{} [{}] {}", entry.module, function, entry.rule, entry.message);
+        }
+    }
+    if fix {
+        let summary = apply_fixes(&diagnostics)?;
+        println!("Applied {} fixes to {} files", summary.applied, summary.files);
+        if summary.skipped > 0 {
+            println!("Skipped {} overlapping fixes, run again to apply them", summary.skipped);
+        }
+    }
+    Ok(if diagnostics.is_empty() { 0 } else { FINDINGS_EXIT_CODE })
+}
+
+fn print_edges(edges: &[&CallEdge]) {
+    for edge in edges {
+        println!("{}", edge);
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use erl_parse::cst::{Expr, Literal};
+use crate::callgraph::collect_calls;
+use crate::deprecations::otp_table::{OtpDeprecation, OTP_DEPRECATIONS};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::project::ErlProjectImpl;
+use crate::project::compile_unit::CompileUnit;
+use crate::syntax;
+use crate::syntax::mfa::MFArity;
+
+pub mod otp_table;
+
+/// An entry of the `-deprecated` attribute of a project module
+#[derive(Debug, Clone)]
+pub struct ProjectDeprecation {
+    /// `None` for `-deprecated(module)` and `'_'`
+    pub name: Option<String>,
+    /// `None` for `'_'`
+    pub arity: Option<usize>,
+    /// Third element of the tuple: a text or one of `next_version`, `next_major_release`, `eventually`
+    pub description: Option<String>,
+}
+
+impl ProjectDeprecation {
+    fn matches(&self, callee: &MFArity) -> bool {
+        self.name.iter().all(|name| *name == callee.name) && self.arity.iter().all(|arity| *arity == callee.arity)
+    }
+}
+
+/// Entries of `-deprecated(...)` attributes, which take a list or a single tuple
+pub fn project_deprecations(unit: &CompileUnit) -> Vec<ProjectDeprecation> {
+    let mut result = Vec::new();
+    for value in syntax::wild_attributes(&unit.forms, "deprecated").flat_map(syntax::attribute_values) {
+        if syntax::expr_atom(value) == Some("module") {
+            result.push(ProjectDeprecation { name: None, arity: None, description: None });
+            continue;
+        }
+        let Some(elements) = syntax::tuple_elements(value) else { continue };
+        let Some(name) = elements.first().and_then(|e| syntax::expr_atom(e)) else { continue };
+        let arity = match elements.get(1).map(|e| syntax::unparenthesize(e)) {
+            Some(Expr::Literal(Literal::Integer(arity))) => syntax::integer_value(arity),
+            Some(other) if syntax::expr_atom(other) == Some("_") => None,
+            _ => continue,
+        };
+        let description = elements.get(2)
+            .and_then(|e| syntax::expr_string(e).or_else(|| syntax::expr_atom(e).map(|a| a.replace('_', " "))));
+        result.push(ProjectDeprecation {
+            name: if name == "_" { None } else { Some(name.to_string()) },
+            arity,
+            description,
+        });
+    }
+    result
+}
+
+fn find_otp_deprecation(callee: &MFArity) -> Option<&'static OtpDeprecation> {
+    OTP_DEPRECATIONS.iter().find(|d| {
+        d.module == callee.module
+            && (d.name == "_" || (d.name == callee.name && d.arity.iter().all(|arity| *arity == callee.arity)))
+    })
+}
+
+/// Report calls to deprecated and removed OTP functions, and to functions of project modules marked with
+/// `-deprecated`. With `target_otp` set, functions removed in that release are errors and functions
+/// deprecated after it are not reported.
+pub fn analyze(project: &ErlProjectImpl) -> Vec<Diagnostic> {
+    let target_otp = project.project_conf.compiler_options.target_otp;
+    let modules = project.modules.read().unwrap();
+    let deprecated_in_project: HashMap<&str, Vec<ProjectDeprecation>> = modules.values()
+        .map(|unit| (unit.name.as_str(), project_deprecations(unit)))
+        .filter(|(_, deprecations)| !deprecations.is_empty())
+        .collect();
+    let mut diagnostics = Vec::new();
+
+    for unit in modules.values() {
+        for edge in collect_calls(unit) {
+            // Calls within the deprecated module itself are not reported, same as erlc
+            if edge.callee.module == unit.name {
+                continue;
+            }
+            if let Some(deprecation) = find_otp_deprecation(&edge.callee) {
+                if target_otp.is_some_and(|target| target < deprecation.deprecated_in) {
+                    continue;
+                }
+                let removed = deprecation.removed_in
+                    .filter(|removed| target_otp.is_some_and(|target| target >= *removed));
+                let diagnostic = match removed {
+                    Some(removed) => Diagnostic::new(
+                        Severity::Error, "removed_function",
+                        format!("{} was removed in OTP {}, use {}", edge.callee, removed, deprecation.replacement),
+                        edge.span),
+                    None => {
+                        let removal = deprecation.removed_in
+                            .map(|removed| format!(" and removed in OTP {}", removed))
+                            .unwrap_or_default();
+                        Diagnostic::new(
+                            Severity::Warning, "deprecated_function",
+                            format!("{} is deprecated since OTP {}{}, use {}", edge.callee, deprecation.deprecated_in,
+                                    removal, deprecation.replacement),
+                            edge.span)
+                    }
+                };
+                diagnostics.push(diagnostic);
+                continue;
+            }
+
+            let Some(deprecations) = deprecated_in_project.get(edge.callee.module.as_str()) else { continue };
+            if let Some(deprecation) = deprecations.iter().find(|d| d.matches(&edge.callee)) {
+                let description = deprecation.description.as_ref()
+                    .map(|d| format!(": {}", d))
+                    .unwrap_or_default();
+                diagnostics.push(Diagnostic::new(Severity::Warning, "deprecated_function",
+                                                 format!("{} is deprecated{}", edge.callee, description),
+                                                 edge.span));
+            }
+        }
+    }
+    diagnostics
+}
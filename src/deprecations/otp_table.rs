@@ -0,0 +1,50 @@
+/// A deprecated OTP function or module. `name` of `"_"` covers the whole module, `arity` of `None` covers
+/// every arity of the function.
+pub struct OtpDeprecation {
+    pub module: &'static str,
+    pub name: &'static str,
+    pub arity: Option<usize>,
+    /// OTP release which deprecated it
+    pub deprecated_in: u32,
+    /// OTP release which removed it, if it is gone already
+    pub removed_in: Option<u32>,
+    pub replacement: &'static str,
+}
+
+const fn entry(module: &'static str, name: &'static str, arity: Option<usize>, deprecated_in: u32,
+               removed_in: Option<u32>, replacement: &'static str) -> OtpDeprecation {
+    OtpDeprecation { module, name, arity, deprecated_in, removed_in, replacement }
+}
+
+/// Deprecations with the release they happened in, most used first
+pub const OTP_DEPRECATIONS: &[OtpDeprecation] = &[
+    entry("erlang", "now", Some(0), 18, None,
+          "erlang:monotonic_time/0, erlang:system_time/0 or erlang:timestamp/0"),
+    entry("erlang", "get_stacktrace", Some(0), 21, Some(24), "the Class:Reason:Stacktrace pattern in catch clauses"),
+    entry("erlang", "hash", Some(2), 17, Some(20), "erlang:phash2/2"),
+    entry("random", "_", None, 19, None, "the rand module"),
+    entry("crypto", "hmac", None, 23, Some(24), "crypto:mac/4 or crypto:macN/5"),
+    entry("crypto", "hmac_init", Some(2), 23, Some(24), "crypto:mac_init/3"),
+    entry("crypto", "hmac_update", Some(2), 23, Some(24), "crypto:mac_update/2"),
+    entry("crypto", "hmac_final", None, 23, Some(24), "crypto:mac_final/1"),
+    entry("crypto", "cmac", None, 23, Some(24), "crypto:mac/4"),
+    entry("crypto", "poly1305", Some(2), 23, Some(24), "crypto:mac/3"),
+    entry("crypto", "block_encrypt", None, 23, Some(24), "crypto:crypto_one_time/4,5"),
+    entry("crypto", "block_decrypt", None, 23, Some(24), "crypto:crypto_one_time/4,5"),
+    entry("crypto", "stream_init", None, 23, Some(24), "crypto:crypto_init/3,4"),
+    entry("crypto", "stream_encrypt", Some(2), 23, Some(24), "crypto:crypto_update/2"),
+    entry("crypto", "stream_decrypt", Some(2), 23, Some(24), "crypto:crypto_update/2"),
+    entry("crypto", "rand_bytes", Some(1), 17, Some(20), "crypto:strong_rand_bytes/1"),
+    entry("crypto", "rand_uniform", Some(2), 20, Some(24), "rand:uniform/1"),
+    entry("pg2", "_", None, 23, Some(24), "the pg module"),
+    entry("http_uri", "_", None, 23, Some(25), "the uri_string module"),
+    entry("gen_fsm", "_", None, 20, None, "gen_statem"),
+    entry("slave", "_", None, 25, None, "the peer module"),
+    entry("ssl", "ssl_accept", None, 21, Some(24), "ssl:handshake/1,2,3"),
+    entry("filename", "find_src", None, 20, None, "filelib:find_source/1,3"),
+    entry("calendar", "local_time_to_universal_time", Some(1), 15, None,
+          "calendar:local_time_to_universal_time_dst/1"),
+    entry("queue", "lait", Some(1), 15, None, "queue:liat/1"),
+    entry("public_key", "ssh_decode", Some(2), 24, None, "ssh_file:decode/2"),
+    entry("public_key", "ssh_encode", Some(2), 24, None, "ssh_file:encode/2"),
+];
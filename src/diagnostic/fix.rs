@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use crate::diagnostic::{Diagnostic, SourceSpan};
+use crate::error::{IroncladError, IroncladResult};
+
+/// Replace the text covered by `span` with `replacement`. An empty span inserts, an empty replacement deletes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub span: SourceSpan,
+    pub replacement: String,
+}
+
+impl Fix {
+    pub fn replace(span: SourceSpan, replacement: &str) -> Self {
+        Self { span, replacement: replacement.to_string() }
+    }
+
+    pub fn insert(file: &std::path::Path, line: usize, column: usize, text: &str) -> Self {
+        Self::replace(SourceSpan::columns(file, line, column, column), text)
+    }
+
+    pub fn delete(span: SourceSpan) -> Self {
+        Self::replace(span, "")
+    }
+}
+
+/// Counts reported after applying fixes
+#[derive(Debug, Default)]
+pub struct FixSummary {
+    pub applied: usize,
+    /// Fixes overlapping with another fix in the same run, a second run picks them up
+    pub skipped: usize,
+    pub files: usize,
+}
+
+/// Apply the fixes of all diagnostics to the files on disk
+pub fn apply_fixes(diagnostics: &[Diagnostic]) -> IroncladResult<FixSummary> {
+    let mut by_file: BTreeMap<PathBuf, Vec<&Fix>> = BTreeMap::new();
+    for fix in diagnostics.iter().filter_map(|d| d.fix.as_ref()) {
+        by_file.entry(fix.span.file.clone()).or_default().push(fix);
+    }
+
+    let mut summary = FixSummary::default();
+    for (file, mut fixes) in by_file {
+        let mut text = std::fs::read_to_string(&file).map_err(IroncladError::from)?;
+        let line_starts = line_starts(&text);
+        // Apply from the end of the file so earlier offsets stay valid
+        fixes.sort_by(|a, b| b.span.cmp(&a.span));
+        fixes.dedup();
+
+        let mut applied_from = text.len();
+        let mut changed = false;
+        for fix in fixes {
+            let start = byte_offset(&text, &line_starts, fix.span.start_line, fix.span.start_column);
+            let end = byte_offset(&text, &line_starts, fix.span.end_line, fix.span.end_column);
+            if end > applied_from || start > end {
+                summary.skipped += 1;
+                continue;
+            }
+            text.replace_range(start..end, &fix.replacement);
+            applied_from = start;
+            summary.applied += 1;
+            changed = true;
+        }
+        if changed {
+            std::fs::write(&file, text).map_err(IroncladError::from)?;
+            summary.files += 1;
+        }
+    }
+    Ok(summary)
+}
+
+/// Source text covered by a span, for fixes which keep part of the original code
+pub fn source_slice<'t>(text: &'t str, span: &SourceSpan) -> &'t str {
+    let line_starts = line_starts(text);
+    let start = byte_offset(text, &line_starts, span.start_line, span.start_column);
+    let end = byte_offset(text, &line_starts, span.end_line, span.end_column);
+    &text[start..end.max(start)]
+}
+
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Byte offset of a 1-based line and character column, clamped to the end of the line or text
+fn byte_offset(text: &str, line_starts: &[usize], line: usize, column: usize) -> usize {
+    let Some(&line_start) = line_starts.get(line.saturating_sub(1)) else { return text.len() };
+    let line_end = text[line_start..].find('\n').map_or(text.len(), |i| line_start + i);
+    text[line_start..line_end].char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(line_end, |(i, _)| line_start + i)
+}
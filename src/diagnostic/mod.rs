@@ -0,0 +1,115 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use erl_tokenize::PositionRange;
+use crate::project::preprocessor_info::position_file;
+
+pub mod fix;
+
+pub use fix::Fix;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Severity::Error),
+            "warning" => Ok(Severity::Warning),
+            "info" => Ok(Severity::Info),
+            other => Err(format!("Unknown severity '{}', expected error, warning or info", other)),
+        }
+    }
+}
+
+/// Source range of a finding. Lines and columns are 1-based, the end is exclusive.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceSpan {
+    pub file: PathBuf,
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl SourceSpan {
+    /// Span covering a syntax tree node or token. `module_path` is used for positions which have no file
+    /// set, these belong to the module file itself and not to an included header.
+    pub fn from_range<T: PositionRange>(node: &T, module_path: &Path) -> Self {
+        let start = node.start_position();
+        let end = node.end_position();
+        Self {
+            file: position_file(&start, module_path),
+            start_line: start.line(),
+            start_column: start.column(),
+            end_line: end.line(),
+            end_column: end.column(),
+        }
+    }
+
+    /// Span within one line, columns are 1-based and the end is exclusive
+    pub fn columns(file: &Path, line: usize, start_column: usize, end_column: usize) -> Self {
+        Self { file: file.to_path_buf(), start_line: line, start_column, end_line: line, end_column }
+    }
+
+    /// Span for a finding which only knows the line
+    pub fn line(file: &Path, line: usize) -> Self {
+        Self { file: file.to_path_buf(), start_line: line, start_column: 1, end_line: line, end_column: 1 }
+    }
+
+    /// Span for a finding about the whole file
+    pub fn file(file: &Path) -> Self {
+        Self::line(file, 1)
+    }
+}
+
+/// A finding reported to the user
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Short machine-readable code, like `unused_include`
+    pub code: String,
+    pub message: String,
+    pub span: SourceSpan,
+    /// Source edit which resolves the finding, applied by `ironclad check --fix`
+    pub fix: Option<Fix>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &str, message: String, span: SourceSpan) -> Self {
+        Self { severity, code: code.to_string(), message, span, fix: None }
+    }
+
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}: {} [{}] {}", self.span.file.to_string_lossy(), self.span.start_line,
+               self.span.start_column, self.severity, self.code, self.message)
+    }
+}
+
+/// Sort diagnostics by file and position for stable output
+pub fn sort_diagnostics(diagnostics: &mut [Diagnostic]) {
+    diagnostics.sort_by(|a, b| a.span.cmp(&b.span).then_with(|| a.code.cmp(&b.code)));
+}
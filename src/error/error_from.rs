@@ -22,4 +22,16 @@ impl From<toml::de::Error> for IroncladError {
     fn from(value: toml::de::Error) -> Self {
         IroncladError::TomlConfig(value)
     }
-}
\ No newline at end of file
+}
+
+impl From<toml::ser::Error> for IroncladError {
+    fn from(value: toml::ser::Error) -> Self {
+        IroncladError::TomlWrite(value)
+    }
+}
+
+impl From<serde_json::Error> for IroncladError {
+    fn from(value: serde_json::Error) -> Self {
+        IroncladError::Json(value)
+    }
+}
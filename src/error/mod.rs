@@ -10,6 +10,14 @@ pub enum IroncladError {
     StdIoError(std::io::Error),
     /// Project loading error produced when loading TOML
     TomlConfig(toml::de::Error),
+    /// Error produced when saving a TOML file, such as the baseline
+    TomlWrite(toml::ser::Error),
+    /// Error produced when writing JSON output
+    Json(serde_json::Error),
+    /// Command line arguments could not be understood
+    CommandLine(String),
+    /// Invalid value in the `[lints]` section of the project file
+    LintConfig(String),
 }
 
 impl Default for IroncladError {
@@ -19,9 +27,13 @@ impl Default for IroncladError {
 }
 
 impl IroncladError {
-    /// Override for different error types to return different exit codes
+    /// Override for different error types to return different exit codes. Errors exit with 2, so that a run
+    /// which failed is told apart from `check` reporting findings (1) and does not pass in CI.
     pub fn get_process_exit_code(&self) -> i32 {
-        0
+        match self {
+            IroncladError::Ok => 0,
+            _ => 2,
+        }
     }
 }
 
@@ -36,6 +48,10 @@ impl std::fmt::Display for IroncladError {
             IroncladError::GlobPattern(gperr) => gperr.fmt(f),
             IroncladError::StdIoError(ioerr) => writeln!(f, "{}", ioerr),
             IroncladError::TomlConfig(cfgerr) => cfgerr.fmt(f),
+            IroncladError::TomlWrite(sererr) => sererr.fmt(f),
+            IroncladError::Json(jsonerr) => jsonerr.fmt(f),
+            IroncladError::CommandLine(msg) => write!(f, "{}", msg),
+            IroncladError::LintConfig(msg) => write!(f, "Lint configuration error: {}", msg),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,171 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use erl_parse::cst::Form;
+use erl_tokenize::PositionRange;
+use crate::diagnostic::{Diagnostic, Severity, SourceSpan};
+use crate::error::IroncladResult;
+use crate::project::ErlProjectImpl;
+use crate::project::compile_unit::CompileUnit;
+use crate::project::preprocessor_info::{position_file, IncludeDirective};
+use crate::syntax::mfa::FunArity;
+use crate::syntax::usage::NameUsage;
+
+/// Which files include which headers, merged from all modules in the project.
+/// Every file is a node, modules have only outgoing edges.
+#[derive(Debug, Default)]
+pub struct IncludeGraph {
+    /// Including file, mapped to its include directives; each directive is kept once
+    edges: BTreeMap<PathBuf, Vec<IncludeDirective>>,
+}
+
+impl IncludeGraph {
+    pub fn build(project: &ErlProjectImpl) -> Self {
+        let mut graph = IncludeGraph::default();
+        let modules = project.modules.read().unwrap();
+        for unit in modules.values() {
+            for include in unit.pp_info.includes.iter() {
+                graph.add(include);
+            }
+        }
+        graph
+    }
+
+    fn add(&mut self, include: &IncludeDirective) {
+        let directives = self.edges.entry(include.included_from.clone()).or_default();
+        let duplicate = directives.iter()
+            .any(|d| d.line == include.line && d.path == include.path);
+        if !duplicate {
+            directives.push(include.clone());
+        }
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item=&IncludeDirective> {
+        self.edges.values().flatten()
+    }
+
+    /// Headers included directly by `file`, which could be found on disk
+    pub fn includes_of(&self, file: &Path) -> impl Iterator<Item=&PathBuf> {
+        self.edges.get(file).into_iter().flatten().filter_map(|d| d.resolved.as_ref())
+    }
+
+    /// Whether any module or header includes this header
+    pub fn is_included(&self, header: &Path) -> bool {
+        self.edges().any(|d| d.resolved.as_deref() == Some(header))
+    }
+
+    /// The header and all headers it includes, recursively
+    pub fn closure(&self, header: &Path) -> HashSet<PathBuf> {
+        let mut result = HashSet::new();
+        let mut stack = vec![header.to_path_buf()];
+        while let Some(file) = stack.pop() {
+            if result.insert(file.clone()) {
+                stack.extend(self.includes_of(&file).cloned());
+            }
+        }
+        result
+    }
+
+    /// Include cycles, each one is listed once starting from its smallest path
+    pub fn find_cycles(&self) -> Vec<Vec<PathBuf>> {
+        let mut cycles = BTreeSet::new();
+        let mut finished = HashSet::new();
+        for start in self.edges.keys() {
+            let mut stack = Vec::new();
+            self.find_cycles_from(start, &mut stack, &mut finished, &mut cycles);
+        }
+        cycles.into_iter().collect()
+    }
+
+    fn find_cycles_from(&self, file: &Path, stack: &mut Vec<PathBuf>, finished: &mut HashSet<PathBuf>,
+                        cycles: &mut BTreeSet<Vec<PathBuf>>) {
+        if let Some(pos) = stack.iter().position(|f| f == file) {
+            let mut cycle = stack[pos..].to_vec();
+            let min_pos = cycle.iter().enumerate().min_by_key(|(_, f)| *f).map(|(i, _)| i).unwrap_or(0);
+            cycle.rotate_left(min_pos);
+            cycles.insert(cycle);
+            return;
+        }
+        if finished.contains(file) {
+            return;
+        }
+        stack.push(file.to_path_buf());
+        let next: Vec<PathBuf> = self.includes_of(file).cloned().collect();
+        for header in next.iter() {
+            self.find_cycles_from(header, stack, finished, cycles);
+        }
+        stack.pop();
+        finished.insert(file.to_path_buf());
+    }
+}
+
+/// Report headers nobody includes, includes that a module does not need, and include cycles
+pub fn analyze(project: &ErlProjectImpl) -> IroncladResult<Vec<Diagnostic>> {
+    let graph = IncludeGraph::build(project);
+    let mut diagnostics = Vec::new();
+
+    for header in project.find_header_files()? {
+        if !graph.is_included(&header) {
+            diagnostics.push(Diagnostic::new(Severity::Warning, "unused_header",
+                                             "Header file is not included by any module".to_string(),
+                                             SourceSpan::file(&header)));
+        }
+    }
+
+    for unit in project.modules.read().unwrap().values() {
+        check_unused_includes(unit, &graph, &mut diagnostics);
+    }
+
+    for cycle in graph.find_cycles() {
+        let chain: Vec<String> = cycle.iter().chain(cycle.first())
+            .map(|f| f.to_string_lossy().to_string())
+            .collect();
+        diagnostics.push(Diagnostic::new(Severity::Error, "include_cycle",
+                                         format!("Include cycle: {}", chain.join(" -> ")),
+                                         SourceSpan::file(&cycle[0])));
+    }
+    Ok(diagnostics)
+}
+
+/// An include is unused when the module outside of the header (and headers it pulls in) never refers
+/// to a record, type or macro defined there. Headers which define none of these are not reported.
+fn check_unused_includes(unit: &CompileUnit, graph: &IncludeGraph, diagnostics: &mut Vec<Diagnostic>) {
+    let usage = NameUsage::collect(unit);
+
+    for include in unit.pp_info.includes.iter().filter(|d| d.included_from == unit.path) {
+        let Some(header) = &include.resolved else { continue };
+        let closure = graph.closure(header);
+        let outside = |file: &PathBuf| !closure.contains(file);
+
+        let mut records = HashSet::new();
+        let mut types = HashSet::new();
+        for form in unit.forms.iter() {
+            if !closure.contains(&position_file(&form.start_position(), &unit.path)) {
+                continue;
+            }
+            match form {
+                Form::RecordDecl(decl) => { records.insert(decl.record_name.value().to_string()); }
+                Form::TypeDecl(decl) => {
+                    types.insert(FunArity::new(decl.type_name.value(), decl.variables.iter().count()));
+                }
+                _ => {}
+            }
+        }
+        let macros: HashSet<&str> = unit.pp_info.macro_defs.iter()
+            .filter(|m| closure.contains(&m.file))
+            .map(|m| m.name.as_str())
+            .collect();
+
+        if records.is_empty() && types.is_empty() && macros.is_empty() {
+            continue;
+        }
+        let used = usage.records.iter().any(|(name, file)| outside(file) && records.contains(name))
+            || usage.types.iter().any(|(fun_arity, file)| outside(file) && types.contains(fun_arity))
+            || unit.pp_info.macro_uses.iter().any(|m| outside(&m.file) && macros.contains(m.name.as_str()));
+        if !used {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning, "unused_include",
+                format!("No records, types or macros from '{}' are used in module {}", include.path, unit.name),
+                SourceSpan::line(&unit.path, include.line)));
+        }
+    }
+}
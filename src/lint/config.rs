@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use crate::diagnostic::Severity;
+use crate::error::{IroncladError, IroncladResult};
+
+/// Settings from one `[lints.<id>]` table of the project file
+#[derive(Debug, Default, Clone)]
+pub struct RuleConfig {
+    pub enabled: Option<bool>,
+    pub severity: Option<Severity>,
+    /// All other keys of the table, interpreted by the rule itself
+    pub params: toml::Table,
+}
+
+/// Parsed `[lints]` section, keyed by rule id or diagnostic code
+#[derive(Debug, Default)]
+pub struct LintsConfig {
+    pub rules: HashMap<String, RuleConfig>,
+}
+
+impl LintsConfig {
+    pub fn from_table(lints: Option<&toml::Table>) -> IroncladResult<Self> {
+        let mut config = LintsConfig::default();
+        for (id, value) in lints.into_iter().flatten() {
+            let table = value.as_table()
+                .ok_or_else(|| IroncladError::LintConfig(format!("[lints.{}] must be a table", id)))?;
+            let mut rule_config = RuleConfig::default();
+            for (key, value) in table.iter() {
+                match key.as_str() {
+                    "enabled" => {
+                        let enabled = value.as_bool().ok_or_else(|| {
+                            IroncladError::LintConfig(format!("lints.{}.enabled must be true or false", id))
+                        })?;
+                        rule_config.enabled = Some(enabled);
+                    }
+                    "severity" => {
+                        let severity = value.as_str()
+                            .ok_or_else(|| format!("lints.{}.severity must be a string", id))
+                            .and_then(|s| s.parse::<Severity>())
+                            .map_err(IroncladError::LintConfig)?;
+                        rule_config.severity = Some(severity);
+                    }
+                    _ => {
+                        rule_config.params.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            config.rules.insert(id.clone(), rule_config);
+        }
+        Ok(config)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&RuleConfig> {
+        self.rules.get(id)
+    }
+}
+
+/// Helpers for rules to read their parameters with a useful error message
+pub fn param_usize(params: &toml::Table, rule_id: &str, key: &str) -> IroncladResult<Option<usize>> {
+    match params.get(key) {
+        None => Ok(None),
+        Some(value) => value.as_integer()
+            .and_then(|i| usize::try_from(i).ok())
+            .map(Some)
+            .ok_or_else(|| {
+                IroncladError::LintConfig(format!("lints.{}.{} must be a positive integer", rule_id, key))
+            }),
+    }
+}
+
+pub fn param_string_list(params: &toml::Table, rule_id: &str, key: &str) -> IroncladResult<Option<Vec<String>>> {
+    let Some(value) = params.get(key) else { return Ok(None) };
+    let bad_value = || IroncladError::LintConfig(format!("lints.{}.{} must be a list of strings", rule_id, key));
+    let array = value.as_array().ok_or_else(bad_value)?;
+    array.iter()
+        .map(|item| item.as_str().map(|s| s.to_string()).ok_or_else(bad_value))
+        .collect::<IroncladResult<Vec<String>>>()
+        .map(Some)
+}
+
+pub fn param_bool(params: &toml::Table, rule_id: &str, key: &str) -> IroncladResult<Option<bool>> {
+    match params.get(key) {
+        None => Ok(None),
+        Some(value) => value.as_bool().map(Some).ok_or_else(|| {
+            IroncladError::LintConfig(format!("lints.{}.{} must be true or false", rule_id, key))
+        }),
+    }
+}
+
+pub fn param_string(params: &toml::Table, rule_id: &str, key: &str) -> IroncladResult<Option<String>> {
+    match params.get(key) {
+        None => Ok(None),
+        Some(value) => value.as_str().map(|s| Some(s.to_string())).ok_or_else(|| {
+            IroncladError::LintConfig(format!("lints.{}.{} must be a string", rule_id, key))
+        }),
+    }
+}
+
+/// A table of string values, like `prefixes = { billing = "bill_" }`
+pub fn param_string_map(params: &toml::Table, rule_id: &str, key: &str)
+                        -> IroncladResult<Option<HashMap<String, String>>> {
+    let Some(value) = params.get(key) else { return Ok(None) };
+    let bad_value = || IroncladError::LintConfig(format!("lints.{}.{} must be a table of strings", rule_id, key));
+    let table = value.as_table().ok_or_else(bad_value)?;
+    table.iter()
+        .map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())).ok_or_else(bad_value))
+        .collect::<IroncladResult<HashMap<String, String>>>()
+        .map(Some)
+}
@@ -0,0 +1,119 @@
+use std::cell::OnceCell;
+use erl_parse::cst::{Expr, Form, GuardTest, Pattern, Type};
+use erl_tokenize::{PositionRange, Token};
+use crate::callgraph::CallResolver;
+use crate::diagnostic::fix::source_slice;
+use crate::diagnostic::{Diagnostic, Fix, Severity, SourceSpan};
+use crate::error::IroncladResult;
+use crate::project::ErlProjectImpl;
+use crate::project::compile_unit::CompileUnit;
+use crate::syntax::mfa::MFArity;
+
+pub mod config;
+pub mod project_rules;
+pub mod registry;
+pub mod rules;
+pub mod suppress;
+
+/// A lint rule. Node hooks are called for every node of every module, parents before children,
+/// then `check_unit` once per module. Whole project analyses implement `check_project` instead.
+pub trait Rule {
+    /// Unique id, used as the diagnostic code and as the `[lints.<id>]` table name
+    fn id(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Receives the rule specific keys of the `[lints.<id>]` table
+    fn configure(&mut self, _params: &toml::Table) -> IroncladResult<()> {
+        Ok(())
+    }
+
+    fn check_form(&mut self, _ctx: &mut LintContext, _form: &Form) {}
+    fn check_expr(&mut self, _ctx: &mut LintContext, _expr: &Expr) {}
+    fn check_pattern(&mut self, _ctx: &mut LintContext, _pattern: &Pattern) {}
+    fn check_guard_test(&mut self, _ctx: &mut LintContext, _test: &GuardTest) {}
+    fn check_type(&mut self, _ctx: &mut LintContext, _ty: &Type) {}
+
+    /// Return true to receive `check_token` calls, tokenizing the source is not free
+    fn uses_tokens(&self) -> bool {
+        false
+    }
+    /// Every token of the module file including whitespace and comments, in source order
+    fn check_token(&mut self, _ctx: &mut LintContext, _token: &Token) {}
+
+    /// Called after all node and token hooks for the module
+    fn check_unit(&mut self, _ctx: &mut LintContext) {}
+
+    /// Diagnostic codes reported by `check_project` instead of the rule id, each can have its own `[lints.<code>]`
+    fn codes(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Called once after all modules were checked, for analyses which need to see the whole project.
+    /// Diagnostics produced here keep their own codes, `[lints.<id>] severity` overrides their severity.
+    fn check_project(&mut self, _project: &ErlProjectImpl, _diagnostics: &mut Vec<Diagnostic>) -> IroncladResult<()> {
+        Ok(())
+    }
+}
+
+/// Passed to rule hooks to access the module being checked and report findings
+pub struct LintContext<'a> {
+    pub project: &'a ErlProjectImpl,
+    pub unit: &'a CompileUnit,
+    rule_id: &'static str,
+    severity: Severity,
+    diagnostics: Vec<Diagnostic>,
+    /// Built on first use, shared by all rules checking the module
+    resolver: OnceCell<CallResolver>,
+}
+
+impl<'a> LintContext<'a> {
+    pub fn new(project: &'a ErlProjectImpl, unit: &'a CompileUnit) -> Self {
+        Self {
+            project,
+            unit,
+            rule_id: "",
+            severity: Severity::Warning,
+            diagnostics: Vec::new(),
+            resolver: OnceCell::new(),
+        }
+    }
+
+    /// Callee and arguments of a call expression, resolving imports and auto-imported BIFs
+    pub fn resolve_call<'e>(&self, expr: &'e Expr) -> Option<(MFArity, Vec<&'e Expr>)> {
+        self.resolver.get_or_init(|| CallResolver::new(self.unit)).resolve_call(expr)
+    }
+
+    /// Source text of a span in the module file, `None` for spans in included files. Fixes which keep part of
+    /// the original code take it from here.
+    pub fn source(&self, span: &SourceSpan) -> Option<&'a str> {
+        (span.file == self.unit.path).then(|| source_slice(&self.unit.source_text, span))
+    }
+
+    /// Set by the registry before calling into each rule
+    fn set_rule(&mut self, rule_id: &'static str, severity: Severity) {
+        self.rule_id = rule_id;
+        self.severity = severity;
+    }
+
+    /// Report a finding covering a syntax tree node or a token
+    pub fn report<T: PositionRange>(&mut self, node: &T, message: String) {
+        let span = SourceSpan::from_range(node, &self.unit.path);
+        self.report_span(span, message);
+    }
+
+    pub fn report_span(&mut self, span: SourceSpan, message: String) {
+        self.diagnostics.push(Diagnostic::new(self.severity, self.rule_id, message, span));
+    }
+
+    /// Report a finding with a source edit which resolves it
+    pub fn report_fix(&mut self, span: SourceSpan, message: String, fix: Fix) {
+        self.diagnostics.push(Diagnostic::new(self.severity, self.rule_id, message, span).with_fix(fix));
+    }
+
+    fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
@@ -0,0 +1,71 @@
+use crate::diagnostic::Diagnostic;
+use crate::error::IroncladResult;
+use crate::lint::Rule;
+use crate::project::ErlProjectImpl;
+use crate::{behaviours, deprecations, include_graph, macros, records};
+
+type ProjectAnalysis = fn(&ErlProjectImpl) -> IroncladResult<Vec<Diagnostic>>;
+
+/// Runs one of the whole project analyses. Its diagnostics use their own codes, which can be configured
+/// separately in `[lints.<code>]`.
+pub struct ProjectAnalysisRule {
+    id: &'static str,
+    description: &'static str,
+    codes: &'static [&'static str],
+    analysis: ProjectAnalysis,
+}
+
+impl Rule for ProjectAnalysisRule {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn codes(&self) -> &'static [&'static str] {
+        self.codes
+    }
+
+    fn check_project(&mut self, project: &ErlProjectImpl, diagnostics: &mut Vec<Diagnostic>) -> IroncladResult<()> {
+        diagnostics.extend((self.analysis)(project)?);
+        Ok(())
+    }
+}
+
+pub fn project_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(ProjectAnalysisRule {
+            id: "include_graph",
+            description: "Headers nobody includes, unneeded includes and include cycles",
+            codes: &["unused_header", "unused_include", "include_cycle"],
+            analysis: include_graph::analyze,
+        }),
+        Box::new(ProjectAnalysisRule {
+            id: "records",
+            description: "Unused records and fields, fields always set to one value, conflicting definitions",
+            codes: &["unused_record", "unused_record_field", "record_field_same_value", "record_conflict"],
+            analysis: |project| Ok(records::analyze(project)),
+        }),
+        Box::new(ProjectAnalysisRule {
+            id: "macros",
+            description: "Unused and conflicting macros, redefined predefined macros, conditions on undefined macros",
+            codes: &["unused_macro", "macro_redefined", "macro_shadows_predefined", "undefined_macro_condition"],
+            analysis: |project| Ok(macros::analyze(project)),
+        }),
+        Box::new(ProjectAnalysisRule {
+            id: "behaviours",
+            description: "Behaviour callbacks are exported with the right arity",
+            codes: &["missing_callback", "optional_callback", "misspelled_callback", "unknown_behaviour"],
+            analysis: |project| Ok(behaviours::analyze(project)),
+        }),
+        Box::new(ProjectAnalysisRule {
+            id: "deprecations",
+            description: "Calls to deprecated or removed OTP functions and to -deprecated project functions, \
+                          see compiler_options.target_otp",
+            codes: &["deprecated_function", "removed_function"],
+            analysis: |project| Ok(deprecations::analyze(project)),
+        }),
+    ]
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use erl_parse::cst::{Expr, Form, GuardTest, Pattern, Type};
+use erl_tokenize::Tokenizer;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::error::{IroncladError, IroncladResult};
+use crate::lint::config::LintsConfig;
+use crate::lint::{project_rules, rules, suppress, LintContext, Rule};
+use crate::project::ErlProjectImpl;
+use crate::project::compile_unit::CompileUnit;
+use crate::syntax::walk::{walk_forms, Visitor};
+
+/// A registered rule with its effective settings
+pub struct ActiveRule {
+    pub rule: Box<dyn Rule>,
+    pub enabled: bool,
+    pub severity: Severity,
+}
+
+/// All known rules, configured from the `[lints]` section of the project file
+pub struct RuleRegistry {
+    rules: Vec<ActiveRule>,
+    config: LintsConfig,
+}
+
+impl RuleRegistry {
+    /// Registry with all built-in rules enabled at their default severity
+    pub fn new() -> Self {
+        let mut registry = Self { rules: Vec::new(), config: LintsConfig::default() };
+        for rule in project_rules::project_rules().into_iter().chain(rules::builtin_rules()) {
+            registry.register(rule);
+        }
+        registry.register(Box::<suppress::UnusedSuppression>::default());
+        registry
+    }
+
+    pub fn register(&mut self, rule: Box<dyn Rule>) {
+        let severity = rule.default_severity();
+        self.rules.push(ActiveRule { rule, enabled: true, severity });
+    }
+
+    pub fn rules(&self) -> &[ActiveRule] {
+        &self.rules
+    }
+
+    /// Apply `[lints]` settings: enable/disable, severity and rule parameters. A table which names neither
+    /// a rule nor a diagnostic code is an error, a typo there would silently configure nothing.
+    pub fn configure(&mut self, config: LintsConfig) -> IroncladResult<()> {
+        let is_known = |id: &str| self.rules.iter().any(|r| r.rule.id() == id || r.rule.codes().contains(&id));
+        let mut unknown: Vec<&String> = config.rules.keys().filter(|id| !is_known(id)).collect();
+        unknown.sort();
+        if let Some(id) = unknown.first() {
+            return Err(IroncladError::LintConfig(format!(
+                "[lints.{}] is not a rule id or diagnostic code, `ironclad rules` lists them", id)));
+        }
+        for active in self.rules.iter_mut() {
+            let Some(rule_config) = config.get(active.rule.id()) else { continue };
+            active.enabled = rule_config.enabled.unwrap_or(active.enabled);
+            active.severity = rule_config.severity.unwrap_or(active.severity);
+            active.rule.configure(&rule_config.params)?;
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    /// Run enabled rules over every module, then the project wide rules.
+    /// Findings covered by suppression comments in the source are removed.
+    pub fn run(&mut self, project: &ErlProjectImpl) -> IroncladResult<Vec<Diagnostic>> {
+        let mut diagnostics = Vec::new();
+        {
+            let modules = project.modules.read().unwrap();
+            for unit in modules.values() {
+                diagnostics.extend(self.run_unit(project, unit));
+            }
+        }
+        for active in self.rules.iter_mut().filter(|r| r.enabled) {
+            let first_new = diagnostics.len();
+            active.rule.check_project(project, &mut diagnostics)?;
+            // The rule severity applies to all its codes, `[lints.<code>]` can still refine it below
+            if let Some(severity) = self.config.get(active.rule.id()).and_then(|c| c.severity) {
+                diagnostics[first_new..].iter_mut().for_each(|d| d.severity = severity);
+            }
+        }
+
+        let modules = project.modules.read().unwrap();
+        let units: Vec<&CompileUnit> = modules.values().collect();
+        let rule_ids: HashMap<&str, &str> = self.rules.iter()
+            .flat_map(|r| r.rule.codes().iter().map(|code| (*code, r.rule.id())))
+            .collect();
+        let unused_severity = self.rules.iter()
+            .find(|r| r.rule.id() == suppress::UNUSED_SUPPRESSION)
+            .filter(|r| r.enabled)
+            .map(|r| r.severity);
+        let diagnostics = suppress::apply_suppressions(&units, diagnostics, &rule_ids, unused_severity);
+        Ok(self.apply_code_settings(diagnostics))
+    }
+
+    fn run_unit(&mut self, project: &ErlProjectImpl, unit: &CompileUnit) -> Vec<Diagnostic> {
+        let mut ctx = LintContext::new(project, unit);
+        let mut dispatcher = Dispatcher { rules: &mut self.rules, ctx: &mut ctx };
+        walk_forms(&mut dispatcher, &unit.forms);
+
+        if dispatcher.rules.iter().any(|r| r.enabled && r.rule.uses_tokens()) {
+            // Tokenizing errors would have failed the parse already, stop quietly
+            for token in Tokenizer::new(unit.source_text.as_str()).map_while(Result::ok) {
+                dispatcher.each(|rule, ctx| if rule.uses_tokens() { rule.check_token(ctx, &token) });
+            }
+        }
+        dispatcher.each(|rule, ctx| rule.check_unit(ctx));
+        ctx.into_diagnostics()
+    }
+
+    /// Diagnostics from project wide rules carry their own codes, `[lints.<code>]` can disable them
+    /// or change their severity
+    fn apply_code_settings(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics.into_iter()
+            .filter_map(|mut diagnostic| {
+                if let Some(code_config) = self.config.get(&diagnostic.code) {
+                    if code_config.enabled == Some(false) {
+                        return None;
+                    }
+                    diagnostic.severity = code_config.severity.unwrap_or(diagnostic.severity);
+                }
+                Some(diagnostic)
+            })
+            .collect()
+    }
+}
+
+impl Default for RuleRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forwards syntax tree nodes from the walker to every enabled rule
+struct Dispatcher<'r, 'c> {
+    rules: &'r mut [ActiveRule],
+    ctx: &'r mut LintContext<'c>,
+}
+
+impl<'r, 'c> Dispatcher<'r, 'c> {
+    fn each<F: FnMut(&mut dyn Rule, &mut LintContext)>(&mut self, mut f: F) {
+        for active in self.rules.iter_mut().filter(|r| r.enabled) {
+            self.ctx.set_rule(active.rule.id(), active.severity);
+            f(active.rule.as_mut(), self.ctx);
+        }
+    }
+}
+
+impl<'r, 'c> Visitor for Dispatcher<'r, 'c> {
+    fn visit_form(&mut self, form: &Form) {
+        self.each(|rule, ctx| rule.check_form(ctx, form));
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        self.each(|rule, ctx| rule.check_expr(ctx, expr));
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        self.each(|rule, ctx| rule.check_pattern(ctx, pattern));
+    }
+
+    fn visit_guard_test(&mut self, test: &GuardTest) {
+        self.each(|rule, ctx| rule.check_guard_test(ctx, test));
+    }
+
+    fn visit_type(&mut self, ty: &Type) {
+        self.each(|rule, ctx| rule.check_type(ctx, ty));
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+use glob::Pattern;
+use crate::callgraph::collect_calls;
+use crate::diagnostic::Severity;
+use crate::error::{IroncladError, IroncladResult};
+use crate::lint::config::{param_string, param_string_list};
+use crate::lint::{LintContext, Rule};
+use crate::project::compile_unit::CompileUnit;
+use crate::syntax::mfa::MFArity;
+
+/// `module`, `module:name` or `module:name/arity`
+#[derive(Debug, Clone)]
+struct CallTarget {
+    module: String,
+    name: Option<String>,
+    arity: Option<usize>,
+}
+
+impl CallTarget {
+    fn parse(text: &str, rule_id: &str) -> IroncladResult<Self> {
+        let bad_value = || IroncladError::LintConfig(format!(
+            "lints.{}: '{}' must be module, module:function or module:function/arity", rule_id, text));
+        let (module, fun) = match text.split_once(':') {
+            Some((module, fun)) => (module, Some(fun)),
+            None => (text, None),
+        };
+        let (name, arity) = match fun.map(|f| f.split_once('/').unwrap_or((f, ""))) {
+            None => (None, None),
+            Some((name, "")) => (Some(name.to_string()), None),
+            Some((name, arity)) => (Some(name.to_string()), Some(arity.parse::<usize>().map_err(|_| bad_value())?)),
+        };
+        if module.is_empty() || name.as_ref().is_some_and(|n| n.is_empty()) {
+            return Err(bad_value());
+        }
+        Ok(Self { module: module.to_string(), name, arity })
+    }
+
+    fn matches(&self, callee: &MFArity) -> bool {
+        self.module == callee.module
+            && self.name.iter().all(|name| *name == callee.name)
+            && self.arity.iter().all(|arity| *arity == callee.arity)
+    }
+}
+
+/// One `[[lints.banned_calls.entries]]` entry. Without `paths` and `apps` the call is banned everywhere.
+#[derive(Debug)]
+struct BannedCall {
+    targets: Vec<CallTarget>,
+    message: Option<String>,
+    /// Glob patterns of module paths where the ban applies, relative to the project file directory
+    paths: Vec<Pattern>,
+    /// OTP applications where the ban applies
+    apps: Vec<String>,
+    except_paths: Vec<Pattern>,
+    except_apps: Vec<String>,
+}
+
+impl BannedCall {
+    fn from_table(table: &toml::Table, rule_id: &str) -> IroncladResult<Self> {
+        let patterns = |key: &str| -> IroncladResult<Vec<Pattern>> {
+            param_string_list(table, rule_id, key)?.unwrap_or_default().iter()
+                .map(|p| Pattern::new(p).map_err(|e| {
+                    IroncladError::LintConfig(format!("lints.{}.{}: bad pattern '{}': {}", rule_id, key, p, e))
+                }))
+                .collect()
+        };
+        let mut targets = param_string_list(table, rule_id, "calls")?.unwrap_or_default();
+        if let Some(call) = param_string(table, rule_id, "call")? {
+            targets.push(call);
+        }
+        if targets.is_empty() {
+            return Err(IroncladError::LintConfig(format!("lints.{}: each entry needs `call` or `calls`", rule_id)));
+        }
+        Ok(Self {
+            targets: targets.iter().map(|t| CallTarget::parse(t, rule_id)).collect::<IroncladResult<_>>()?,
+            message: param_string(table, rule_id, "message")?,
+            paths: patterns("paths")?,
+            apps: param_string_list(table, rule_id, "apps")?.unwrap_or_default(),
+            except_paths: patterns("except_paths")?,
+            except_apps: param_string_list(table, rule_id, "except_apps")?.unwrap_or_default(),
+        })
+    }
+
+    /// `root` is the project file directory; absolute patterns are matched against the full path
+    fn applies_to(&self, unit: &CompileUnit, root: &Path) -> bool {
+        let app = unit.otp_app();
+        let in_app = |apps: &[String]| app.as_ref().is_some_and(|app| apps.contains(app));
+        let relative = unit.path.strip_prefix(root).unwrap_or(&unit.path);
+        let in_paths = |paths: &[Pattern]| paths.iter().any(|p| p.matches_path(relative) || p.matches_path(&unit.path));
+
+        let included = (self.paths.is_empty() && self.apps.is_empty()) || in_paths(&self.paths) || in_app(&self.apps);
+        included && !in_paths(&self.except_paths) && !in_app(&self.except_apps)
+    }
+}
+
+/// Calls forbidden by the project, configured as a list of entries:
+///
+/// ```toml
+/// [[lints.banned_calls.entries]]
+/// calls = ["io:format", "ct:pal"]
+/// except_paths = ["scripts/**"]
+/// message = "Use logger"
+/// ```
+///
+/// Calls are resolved through `-import`, `fun M:F/A` and `apply`/`spawn` with literal arguments.
+/// Macros are already expanded in the syntax tree, so calls hidden in a macro are found too.
+#[derive(Default)]
+pub struct BannedCalls {
+    entries: Vec<BannedCall>,
+}
+
+impl Rule for BannedCalls {
+    fn id(&self) -> &'static str {
+        "banned_calls"
+    }
+
+    fn description(&self) -> &'static str {
+        "Call to a function or module the project has banned (param: entries)"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        let rule_id = self.id();
+        let Some(value) = params.get("entries") else { return Ok(()) };
+        let bad_value = || IroncladError::LintConfig(format!("lints.{}.entries must be a list of tables", rule_id));
+        self.entries = value.as_array().ok_or_else(bad_value)?.iter()
+            .map(|entry| entry.as_table().ok_or_else(bad_value).and_then(|t| BannedCall::from_table(t, rule_id)))
+            .collect::<IroncladResult<_>>()?;
+        Ok(())
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let root = ctx.project.root_dir();
+        let entries: Vec<&BannedCall> = self.entries.iter().filter(|e| e.applies_to(ctx.unit, &root)).collect();
+        if entries.is_empty() {
+            return;
+        }
+        for edge in collect_calls(ctx.unit) {
+            let Some(entry) = entries.iter().find(|e| e.targets.iter().any(|t| t.matches(&edge.callee))) else {
+                continue;
+            };
+            let reason = entry.message.as_ref().map(|m| format!(": {}", m)).unwrap_or_default();
+            ctx.report_span(edge.span, format!("Call to {} is banned here{}", edge.callee, reason));
+        }
+    }
+}
@@ -0,0 +1,473 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::rc::Rc;
+use erl_parse::cst::building_blocks::Guard;
+use erl_parse::cst::clauses::{CaseClause, CatchClause, FunClause};
+use erl_parse::cst::{Expr, Form, GuardTest, Pattern};
+use erl_tokenize::PositionRange;
+use crate::diagnostic::{Severity, SourceSpan};
+use crate::lint::{LintContext, Rule};
+use crate::syntax;
+use crate::syntax::mfa::FunArity;
+use crate::syntax::walk::{walk_expr, walk_fun_clause, walk_guard, walk_pattern, Visitor};
+
+type Position = (usize, usize);
+
+fn position<T: PositionRange>(node: &T) -> Position {
+    let start = node.start_position();
+    (start.line(), start.column())
+}
+
+/// Source text of a node with whitespace collapsed, `None` when the node comes from an included file
+fn span_text(ctx: &LintContext, span: &SourceSpan) -> Option<String> {
+    ctx.source(span).map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn node_text<T: PositionRange>(ctx: &LintContext, node: &T) -> Option<String> {
+    span_text(ctx, &SourceSpan::from_range(node, &ctx.unit.path))
+}
+
+/// `Class:Reason:Stacktrace` part of a catch clause as written, without the guard and `->`
+pub fn catch_head(ctx: &LintContext, clause: &CatchClause) -> Option<String> {
+    let clause_span = SourceSpan::from_range(clause, &ctx.unit.path);
+    let head_end = match (&clause.guard, clause.body.exprs.iter().next()) {
+        (Some(guard), _) => SourceSpan::from_range(guard, &ctx.unit.path),
+        (None, Some(expr)) => SourceSpan::from_range(expr, &ctx.unit.path),
+        (None, None) => return None,
+    };
+    let head = SourceSpan { end_line: head_end.start_line, end_column: head_end.start_column, ..clause_span };
+    span_text(ctx, &head).map(|text| {
+        text.trim_end_matches("->").trim_end().trim_end_matches("when").trim_end().to_string()
+    })
+}
+
+/// What clause comparison needs to know about a function, case, receive, try or catch clause
+struct ClauseView<'a> {
+    span: SourceSpan,
+    /// Whole clause text, for duplicates
+    text: Option<String>,
+    patterns: Vec<&'a Pattern>,
+    has_guard: bool,
+    guard: Option<String>,
+    /// Variables the guard refers to
+    guard_vars: HashSet<String>,
+    /// Where each head variable is bound, see `var_paths`
+    var_paths: VarPaths,
+    /// Variables which only match their bound value: bound before the clause or repeated in its patterns
+    bound: HashSet<String>,
+    /// `Class:Reason:Stacktrace` of catch clauses, compared as text instead of the patterns
+    catch_head: Option<String>,
+}
+
+impl<'a> ClauseView<'a> {
+    fn new<T: PositionRange>(ctx: &LintContext, clause: &T, patterns: Vec<&'a Pattern>, guard: Option<&Guard>,
+                             first_seen: &HashMap<String, Position>) -> Self {
+        let mut counter = VarCounter::default();
+        patterns.iter().for_each(|p| walk_pattern(&mut counter, p));
+        let mut guard_vars = GuardVars::default();
+        if let Some(guard) = guard {
+            walk_guard(&mut guard_vars, guard);
+        }
+        let paths = var_paths(&patterns);
+        let start = position(clause);
+        let bound = counter.counts.into_iter()
+            .filter(|(name, count)| *count > 1 || first_seen.get(name).is_some_and(|seen| *seen < start))
+            .map(|(name, _)| name)
+            .collect();
+        Self {
+            span: SourceSpan::from_range(clause, &ctx.unit.path),
+            text: node_text(ctx, clause),
+            patterns,
+            has_guard: guard.is_some(),
+            guard: guard.and_then(|g| node_text(ctx, g)),
+            guard_vars: guard_vars.names,
+            var_paths: paths,
+            bound,
+            catch_head: None,
+        }
+    }
+
+    fn fun<N>(ctx: &LintContext, clause: &'a FunClause<N>, first_seen: &HashMap<String, Position>) -> Self {
+        Self::new(ctx, clause, clause.patterns.iter().collect(), clause.guard.as_ref(), first_seen)
+    }
+
+    fn case(ctx: &LintContext, clause: &'a CaseClause, first_seen: &HashMap<String, Position>) -> Self {
+        Self::new(ctx, clause, vec![&clause.pattern], clause.guard.as_ref(), first_seen)
+    }
+
+    fn catch(ctx: &LintContext, clause: &'a CatchClause, first_seen: &HashMap<String, Position>) -> Self {
+        let mut view = Self::new(ctx, clause, vec![&clause.pattern], clause.guard.as_ref(), first_seen);
+        view.patterns.clear();
+        view.catch_head = catch_head(ctx, clause);
+        view
+    }
+
+    /// Whether this earlier clause matches everything `later` matches
+    fn covers(&self, ctx: &LintContext, later: &ClauseView) -> bool {
+        let same_guard = later.has_guard && self.guard.is_some() && self.guard == later.guard;
+        let guard_covers = !self.has_guard || (same_guard && self.same_guard_bindings(later));
+        if !guard_covers {
+            return false;
+        }
+        if let Some(head) = &self.catch_head {
+            return self.is_catch_all(head) || later.catch_head.as_ref() == Some(head);
+        }
+        !self.patterns.is_empty()
+            && self.patterns.len() == later.patterns.len()
+            && self.patterns.iter().zip(later.patterns.iter()).all(|(p, q)| self.subsumes(ctx, p, q))
+    }
+
+    /// Guards written the same way test the same values only when their variables are bound at the same places
+    /// of the heads: `f(X, _) when X > 0` and `f(_, X) when X > 0` test different arguments
+    fn same_guard_bindings(&self, later: &ClauseView) -> bool {
+        self.guard_vars.iter().all(|name| {
+            let paths = self.var_paths.get(name);
+            paths == later.var_paths.get(name) && paths.map_or(true, |paths| paths.iter().all(|p| p.is_some()))
+        })
+    }
+
+    /// `Class:Reason` or `Class:Reason:Stacktrace` with unbound variables only
+    fn is_catch_all(&self, head: &str) -> bool {
+        let parts: Vec<&str> = head.split(':').map(|p| p.trim()).collect();
+        (parts.len() == 2 || parts.len() == 3) && parts.iter().all(|part| {
+            part.starts_with(|c: char| c.is_ascii_uppercase() || c == '_') && !self.bound.contains(*part)
+        })
+    }
+
+    fn is_wildcard(&self, p: &Pattern) -> bool {
+        match p {
+            Pattern::Parenthesized(inner) => self.is_wildcard(&inner.item),
+            Pattern::Var(var) => var.value() == "_" || !self.bound.contains(var.value()),
+            _ => false,
+        }
+    }
+
+    /// Whether pattern `p` of this clause matches every term pattern `q` matches
+    fn subsumes(&self, ctx: &LintContext, p: &Pattern, q: &Pattern) -> bool {
+        match (p, q) {
+            (Pattern::Parenthesized(inner), _) => self.subsumes(ctx, &inner.item, q),
+            (_, Pattern::Parenthesized(inner)) => self.subsumes(ctx, p, &inner.item),
+            (Pattern::Var(_), _) if self.is_wildcard(p) => true,
+            (Pattern::Match(m), _) => self.subsumes(ctx, &m.left, q) && self.subsumes(ctx, &m.right, q),
+            (_, Pattern::Match(m)) => self.subsumes(ctx, p, &m.left) || self.subsumes(ctx, p, &m.right),
+            (Pattern::Tuple(a), Pattern::Tuple(b)) => {
+                let (a, b): (Vec<&Pattern>, Vec<&Pattern>) = (a.iter().collect(), b.iter().collect());
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(p, q)| self.subsumes(ctx, p, q))
+            }
+            (Pattern::List(a), Pattern::List(b)) => {
+                let mut b_elements = syntax::list_elements(b).peekable();
+                for p in syntax::list_elements(a) {
+                    match b_elements.next() {
+                        Some(q) if self.subsumes(ctx, p, q) => {}
+                        _ => return false,
+                    }
+                }
+                match (syntax::list_tail(a), syntax::list_tail(b)) {
+                    // [H | T] against [X, Y | Z]: T must match whatever follows
+                    (Some(tail), _) if b_elements.peek().is_some() => self.is_wildcard(tail),
+                    (Some(tail), Some(q)) => self.subsumes(ctx, tail, q),
+                    (Some(tail), None) => self.is_wildcard(tail) || is_empty_list(tail),
+                    (None, None) => b_elements.peek().is_none(),
+                    (None, Some(_)) => false,
+                }
+            }
+            // Literals and everything else: equal when written the same way
+            _ => node_text(ctx, p).is_some_and(|text| Some(text) == node_text(ctx, q)),
+        }
+    }
+}
+
+/// Where each variable of the patterns is bound, as paths of element indices starting with the pattern index.
+/// Positions inside maps, records and binaries are not tracked, they are `None` and never compare equal.
+fn var_paths(patterns: &[&Pattern]) -> VarPaths {
+    let mut paths = VarPaths::new();
+    for (index, pattern) in patterns.iter().enumerate() {
+        collect_var_paths(pattern, &mut vec![index], &mut paths);
+    }
+    paths.values_mut().for_each(|p| p.sort());
+    paths
+}
+
+type VarPaths = HashMap<String, Vec<Option<Vec<usize>>>>;
+
+fn collect_nested_paths(index: usize, pattern: &Pattern, path: &mut Vec<usize>, paths: &mut VarPaths) {
+    path.push(index);
+    collect_var_paths(pattern, path, paths);
+    path.pop();
+}
+
+fn collect_var_paths(pattern: &Pattern, path: &mut Vec<usize>, paths: &mut VarPaths) {
+    match pattern {
+        Pattern::Var(var) if var.value() != "_" => {
+            paths.entry(var.value().to_string()).or_default().push(Some(path.clone()));
+        }
+        Pattern::Var(_) | Pattern::Literal(_) => {}
+        Pattern::Parenthesized(inner) => collect_var_paths(&inner.item, path, paths),
+        Pattern::Match(m) => {
+            collect_var_paths(&m.left, path, paths);
+            collect_var_paths(&m.right, path, paths);
+        }
+        Pattern::Tuple(tuple) => {
+            tuple.iter().enumerate().for_each(|(i, p)| collect_nested_paths(i, p, path, paths));
+        }
+        Pattern::List(list) => {
+            syntax::list_elements(list).enumerate().for_each(|(i, p)| collect_nested_paths(i, p, path, paths));
+            if let Some(tail) = syntax::list_tail(list) {
+                collect_nested_paths(usize::MAX, tail, path, paths);
+            }
+        }
+        _ => {
+            let mut counter = VarCounter::default();
+            walk_pattern(&mut counter, pattern);
+            for name in counter.counts.into_keys() {
+                paths.entry(name).or_default().push(None);
+            }
+        }
+    }
+}
+
+fn is_empty_list(p: &Pattern) -> bool {
+    matches!(p, Pattern::List(list)
+             if syntax::list_elements(list).next().is_none() && syntax::list_tail(list).is_none())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClauseFindingKind {
+    Unreachable,
+    Duplicate,
+}
+
+struct ClauseFinding {
+    kind: ClauseFindingKind,
+    span: SourceSpan,
+    message: String,
+}
+
+/// Compare each clause with the clauses before it, report the first one which makes it a duplicate or unreachable
+fn compare_clauses(ctx: &LintContext, what: &str, clauses: &[ClauseView], findings: &mut Vec<ClauseFinding>) {
+    for (index, later) in clauses.iter().enumerate() {
+        for earlier in &clauses[..index] {
+            if earlier.text.is_some() && earlier.text == later.text {
+                findings.push(ClauseFinding {
+                    kind: ClauseFindingKind::Duplicate,
+                    span: later.span.clone(),
+                    message: format!("This {} clause duplicates the clause on line {}", what, earlier.span.start_line),
+                });
+                break;
+            }
+            if earlier.covers(ctx, later) {
+                findings.push(ClauseFinding {
+                    kind: ClauseFindingKind::Unreachable,
+                    span: later.span.clone(),
+                    message: format!("This {} clause can never match, the clause on line {} already matches \
+                                      everything it does", what, earlier.span.start_line),
+                });
+                break;
+            }
+        }
+    }
+}
+
+/// First position of every variable in one function clause, to tell bound variables from fresh ones
+#[derive(Default)]
+struct FirstSeen {
+    positions: HashMap<String, Position>,
+}
+
+impl FirstSeen {
+    fn add(&mut self, name: &str, at: Position) {
+        let seen = self.positions.entry(name.to_string()).or_insert(at);
+        *seen = (*seen).min(at);
+    }
+}
+
+impl Visitor for FirstSeen {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let Expr::Var(var) = expr {
+            self.add(var.value(), position(expr));
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        if let Pattern::Var(var) = pattern {
+            self.add(var.value(), position(pattern));
+        }
+    }
+}
+
+/// Variables used in a guard
+#[derive(Default)]
+struct GuardVars {
+    names: HashSet<String>,
+}
+
+impl Visitor for GuardVars {
+    fn visit_guard_test(&mut self, test: &GuardTest) {
+        if let GuardTest::Var(var) = test {
+            self.names.insert(var.value().to_string());
+        }
+    }
+}
+
+/// Occurrences of each variable in a clause head, except `_`
+#[derive(Default)]
+struct VarCounter {
+    counts: HashMap<String, usize>,
+}
+
+impl Visitor for VarCounter {
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        if let Pattern::Var(var) = pattern {
+            if var.value() != "_" {
+                *self.counts.entry(var.value().to_string()).or_default() += 1;
+            }
+        }
+    }
+}
+
+/// Finds case, receive, try and fun expressions within one function clause and compares their clauses
+struct NestedClauses<'c, 'u> {
+    ctx: &'c LintContext<'u>,
+    first_seen: &'c HashMap<String, Position>,
+    findings: Vec<ClauseFinding>,
+}
+
+impl<'c, 'u> Visitor for NestedClauses<'c, 'u> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        let (ctx, first_seen) = (self.ctx, self.first_seen);
+        let (what, views): (&str, Vec<ClauseView>) = match expr {
+            Expr::Case(case) => ("case", case.clauses.iter().map(|c| ClauseView::case(ctx, c, first_seen)).collect()),
+            Expr::Receive(receive) => {
+                ("receive", receive.clauses.iter().map(|c| ClauseView::case(ctx, c, first_seen)).collect())
+            }
+            Expr::Try(try_expr) => {
+                if let Some(branch) = &try_expr.branch {
+                    let views: Vec<ClauseView> = branch.clauses.iter()
+                        .map(|c| ClauseView::case(ctx, c, first_seen))
+                        .collect();
+                    compare_clauses(ctx, "try", &views, &mut self.findings);
+                }
+                let Some(catch) = &try_expr.catch else { return };
+                ("catch", catch.clauses.iter().map(|c| ClauseView::catch(ctx, c, first_seen)).collect())
+            }
+            // Variables in fun heads shadow the outer ones, so every one of them is fresh
+            Expr::AnonymousFun(fun) => {
+                ("fun", fun.clauses.iter().map(|c| ClauseView::fun(ctx, c, &HashMap::new())).collect())
+            }
+            _ => return,
+        };
+        compare_clauses(ctx, what, &views, &mut self.findings);
+    }
+}
+
+fn analyze_clauses(ctx: &LintContext, form: &Form) -> Vec<ClauseFinding> {
+    let Form::FunDecl(decl) = form else { return Vec::new() };
+    let mut findings = Vec::new();
+    let views: Vec<ClauseView> = decl.clauses.iter().map(|c| ClauseView::fun(ctx, c, &HashMap::new())).collect();
+    compare_clauses(ctx, "function", &views, &mut findings);
+
+    for clause in decl.clauses.iter() {
+        let mut first_seen = FirstSeen::default();
+        walk_fun_clause(&mut first_seen, clause);
+        let mut nested = NestedClauses { ctx, first_seen: &first_seen.positions, findings: Vec::new() };
+        clause.body.exprs.iter().for_each(|expr| walk_expr(&mut nested, expr));
+        findings.append(&mut nested.findings);
+    }
+    findings
+}
+
+/// Findings for the latest function, shared by the clause rules so the comparison runs once per function
+#[derive(Default)]
+struct ClauseCache {
+    /// Module path and offset of the function the findings belong to
+    form: Option<(PathBuf, usize)>,
+    findings: Vec<ClauseFinding>,
+}
+
+/// Reports one kind of finding of the clause comparison, which runs for every function
+pub struct ClauseRule {
+    id: &'static str,
+    description: &'static str,
+    kind: ClauseFindingKind,
+    cache: Rc<RefCell<ClauseCache>>,
+}
+
+impl Rule for ClauseRule {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn check_form(&mut self, ctx: &mut LintContext, form: &Form) {
+        let key = (ctx.unit.path.clone(), form.start_position().offset());
+        let mut cache = self.cache.borrow_mut();
+        if cache.form.as_ref() != Some(&key) {
+            cache.findings = analyze_clauses(ctx, form);
+            cache.form = Some(key);
+        }
+        for finding in cache.findings.iter().filter(|f| f.kind == self.kind) {
+            ctx.report_span(finding.span.clone(), finding.message.clone());
+        }
+    }
+}
+
+pub fn clause_rules() -> Vec<Box<dyn Rule>> {
+    let cache = Rc::new(RefCell::new(ClauseCache::default()));
+    vec![
+        Box::new(ClauseRule {
+            id: "unreachable_clause",
+            description: "Clause shadowed by an earlier clause with a more general pattern and no or the same guard",
+            kind: ClauseFindingKind::Unreachable,
+            cache: cache.clone(),
+        }),
+        Box::new(ClauseRule {
+            id: "duplicate_clause",
+            description: "Clause written twice in a function, case, receive or try",
+            kind: ClauseFindingKind::Duplicate,
+            cache,
+        }),
+    ]
+}
+
+/// Clauses of one function with other forms in between. The parser splits them into separate declarations,
+/// erlc then reports the function as defined twice.
+#[derive(Default)]
+pub struct SeparatedClauses;
+
+impl Rule for SeparatedClauses {
+    fn id(&self) -> &'static str {
+        "separated_clauses"
+    }
+
+    fn description(&self) -> &'static str {
+        "Clauses of the same function separated by other forms"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let unit = ctx.unit;
+        let mut first_lines: HashMap<FunArity, usize> = HashMap::new();
+        for form in unit.forms.iter() {
+            let Form::FunDecl(decl) = form else { continue };
+            let Some(fun_arity) = syntax::fun_decl_name(decl) else { continue };
+            let line = decl.start_position().line();
+            match first_lines.get(&fun_arity) {
+                Some(first_line) => {
+                    let message = format!("Clauses of {} are separated by other forms, the first ones start on line \
+                                           {}; keep all clauses together", fun_arity, first_line);
+                    ctx.report(decl, message);
+                }
+                None => {
+                    first_lines.insert(fun_arity, line);
+                }
+            }
+        }
+    }
+}
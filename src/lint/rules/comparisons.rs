@@ -0,0 +1,354 @@
+use std::collections::HashSet;
+use erl_parse::cst::{Expr, GuardTest, Literal, Pattern};
+use erl_tokenize::PositionRange;
+use crate::diagnostic::{Fix, SourceSpan};
+use crate::lint::{LintContext, Rule};
+use crate::syntax;
+
+/// Report with a fix replacing `span`, or without one when the code comes from an included file
+fn report_with_fix(ctx: &mut LintContext, span: SourceSpan, message: String, replacement: Option<String>) {
+    match replacement.filter(|_| span.file == ctx.unit.path) {
+        Some(replacement) => ctx.report_fix(span.clone(), message, Fix::replace(span, &replacement)),
+        None => ctx.report_span(span, message),
+    }
+}
+
+/// `and` and `or` evaluate both sides, `andalso` and `orelse` stop early and are what is almost always meant
+#[derive(Default)]
+pub struct StrictBooleanOperator {
+    /// Offset ranges of operands of other operators. `and`/`or` bind tighter than comparisons and arithmetic,
+    /// `andalso`/`orelse` looser, so `A and B =:= C` must become `(A andalso B) =:= C`.
+    operands: HashSet<(usize, usize)>,
+}
+
+impl StrictBooleanOperator {
+    fn offsets<T: PositionRange>(node: &T) -> (usize, usize) {
+        (node.start_position().offset(), node.end_position().offset())
+    }
+
+    /// Remember the operands of operators other than the boolean ones, which keep their meaning with
+    /// `andalso`/`orelse` in place of `and`/`or`
+    fn add_operands<T: PositionRange>(&mut self, op: &str, operands: &[&T]) {
+        if !matches!(op, "and" | "or" | "andalso" | "orelse") {
+            self.operands.extend(operands.iter().map(|operand| Self::offsets(*operand)));
+        }
+    }
+
+    fn check_operator<T: PositionRange>(&self, ctx: &mut LintContext, call: &T, op_span: SourceSpan, op: &str) {
+        let replacement = match op {
+            "and" => "andalso",
+            "or" => "orelse",
+            _ => return,
+        };
+        let message = format!("`{}` evaluates both operands, use `{}`", op, replacement);
+        if !self.operands.contains(&Self::offsets(call)) {
+            report_with_fix(ctx, op_span, message, Some(replacement.to_string()));
+            return;
+        }
+        let span = SourceSpan::from_range(call, &ctx.unit.path);
+        let before = SourceSpan { end_line: op_span.start_line, end_column: op_span.start_column, ..span.clone() };
+        let after = SourceSpan { start_line: op_span.end_line, start_column: op_span.end_column, ..span.clone() };
+        let parenthesized = match (ctx.source(&before), ctx.source(&after)) {
+            (Some(before), Some(after)) => Some(format!("({}{}{})", before, replacement, after)),
+            _ => None,
+        };
+        report_with_fix(ctx, span, message, parenthesized);
+    }
+}
+
+impl Rule for StrictBooleanOperator {
+    fn id(&self) -> &'static str {
+        "strict_boolean_operator"
+    }
+
+    fn description(&self) -> &'static str {
+        "and/or instead of andalso/orelse"
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        match expr {
+            Expr::BinaryOpCall(call) => {
+                let op = syntax::binary_op_text(&call.op);
+                self.add_operands(op, &[&call.left, &call.right]);
+                self.check_operator(ctx, expr, SourceSpan::from_range(&call.op, &ctx.unit.path), op);
+            }
+            Expr::UnaryOpCall(call) => self.add_operands(syntax::unary_op_text(&call.op), &[&call.operand]),
+            _ => {}
+        }
+    }
+
+    fn check_guard_test(&mut self, ctx: &mut LintContext, test: &GuardTest) {
+        match test {
+            GuardTest::BinaryOpCall(call) => {
+                let op = syntax::binary_op_text(&call.op);
+                self.add_operands(op, &[&call.left, &call.right]);
+                self.check_operator(ctx, test, SourceSpan::from_range(&call.op, &ctx.unit.path), op);
+            }
+            GuardTest::UnaryOpCall(call) => self.add_operands(syntax::unary_op_text(&call.op), &[&call.operand]),
+            _ => {}
+        }
+    }
+
+    fn check_unit(&mut self, _ctx: &mut LintContext) {
+        self.operands.clear();
+    }
+}
+
+/// `==` and `/=` with a float literal operand, which also equal the integer with the same value.
+/// No fix: `X =:= 1.0` stops matching the integer 1, which the code may rely on.
+#[derive(Default)]
+pub struct FloatEquality;
+
+impl FloatEquality {
+    fn check_operator(ctx: &mut LintContext, op_span: SourceSpan, op: &str, has_float: bool) {
+        let exact = match op {
+            "==" => "=:=",
+            "/=" => "=/=",
+            _ => return,
+        };
+        if has_float {
+            ctx.report_span(op_span, format!("{} compares a float with numeric conversion, so 1 {} 1.0. Use {} \
+                                              for an exact comparison", op, op, exact));
+        }
+    }
+}
+
+fn is_float_guard(test: &GuardTest) -> bool {
+    match test {
+        GuardTest::Parenthesized(inner) => is_float_guard(&inner.item),
+        GuardTest::Literal(Literal::Float(_)) => true,
+        _ => false,
+    }
+}
+
+impl Rule for FloatEquality {
+    fn id(&self) -> &'static str {
+        "float_equality"
+    }
+
+    fn description(&self) -> &'static str {
+        "== or /= with a float operand instead of =:= or =/="
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        if let Expr::BinaryOpCall(call) = expr {
+            let is_float = |e: &Expr| matches!(syntax::unparenthesize(e), Expr::Literal(Literal::Float(_)));
+            let span = SourceSpan::from_range(&call.op, &ctx.unit.path);
+            Self::check_operator(ctx, span, syntax::binary_op_text(&call.op),
+                                 is_float(&call.left) || is_float(&call.right));
+        }
+    }
+
+    fn check_guard_test(&mut self, ctx: &mut LintContext, test: &GuardTest) {
+        if let GuardTest::BinaryOpCall(call) = test {
+            let span = SourceSpan::from_range(&call.op, &ctx.unit.path);
+            Self::check_operator(ctx, span, syntax::binary_op_text(&call.op),
+                                 is_float_guard(&call.left) || is_float_guard(&call.right));
+        }
+    }
+}
+
+/// Type of a term as far as it is known from the syntax alone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TermKind {
+    Integer,
+    Float,
+    /// Integer or float
+    Number,
+    Atom,
+    Tuple,
+    Map,
+    Nil,
+    List,
+    Bitstring,
+}
+
+impl TermKind {
+    /// Position in the Erlang term order: number < atom < tuple < map < nil < list < bitstring
+    fn order(self) -> u8 {
+        match self {
+            TermKind::Integer | TermKind::Float | TermKind::Number => 0,
+            TermKind::Atom => 1,
+            TermKind::Tuple => 2,
+            TermKind::Map => 3,
+            TermKind::Nil => 4,
+            TermKind::List => 5,
+            TermKind::Bitstring => 6,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            TermKind::Integer => "an integer",
+            TermKind::Float => "a float",
+            TermKind::Number => "a number",
+            TermKind::Atom => "an atom",
+            TermKind::Tuple => "a tuple",
+            TermKind::Map => "a map",
+            TermKind::Nil => "[]",
+            TermKind::List => "a non-empty list",
+            TermKind::Bitstring => "a binary",
+        }
+    }
+}
+
+/// Return kinds of BIFs which always return the same kind of term
+const BIF_KINDS: &[(&str, TermKind)] = &[
+    ("length", TermKind::Integer), ("size", TermKind::Integer), ("byte_size", TermKind::Integer),
+    ("bit_size", TermKind::Integer), ("tuple_size", TermKind::Integer), ("map_size", TermKind::Integer),
+    ("round", TermKind::Integer), ("trunc", TermKind::Integer), ("ceil", TermKind::Integer),
+    ("floor", TermKind::Integer), ("float", TermKind::Float), ("abs", TermKind::Number),
+    ("list_to_atom", TermKind::Atom), ("binary_to_atom", TermKind::Atom), ("node", TermKind::Atom),
+    ("atom_to_binary", TermKind::Bitstring), ("list_to_binary", TermKind::Bitstring),
+    ("integer_to_binary", TermKind::Bitstring), ("term_to_binary", TermKind::Bitstring),
+    ("iolist_to_binary", TermKind::Bitstring), ("integer_to_list", TermKind::List),
+    ("float_to_list", TermKind::List), ("make_tuple", TermKind::Tuple),
+];
+
+fn term_kind(ctx: &LintContext, expr: &Expr) -> Option<TermKind> {
+    let expr = syntax::unparenthesize(expr);
+    let kind = match expr {
+        Expr::Literal(Literal::Integer(_) | Literal::Char(_)) => TermKind::Integer,
+        Expr::Literal(Literal::Float(_)) => TermKind::Float,
+        Expr::Literal(Literal::Atom(_)) => TermKind::Atom,
+        Expr::Literal(Literal::String(_)) if syntax::expr_string(expr)?.is_empty() => TermKind::Nil,
+        Expr::Literal(Literal::String(_)) => TermKind::List,
+        Expr::Tuple(_) | Expr::Record(_) => TermKind::Tuple,
+        Expr::Map(_) | Expr::MapUpdate(_) => TermKind::Map,
+        Expr::List(list) if syntax::list_elements(list).next().is_none() => TermKind::Nil,
+        Expr::List(_) => TermKind::List,
+        Expr::Bits(_) | Expr::BitsComprehension(_) => TermKind::Bitstring,
+        Expr::UnaryOpCall(call) => match syntax::unary_op_text(&call.op) {
+            "not" => TermKind::Atom,
+            "bnot" => TermKind::Integer,
+            _ => term_kind(ctx, &call.operand).filter(|k| k.order() == 0)?,
+        },
+        Expr::BinaryOpCall(call) => match syntax::binary_op_text(&call.op) {
+            // andalso/orelse return their right operand as is, which need not be a boolean
+            "==" | "/=" | "=:=" | "=/=" | "<" | "=<" | ">" | ">=" | "and" | "or" | "xor" => TermKind::Atom,
+            "div" | "rem" | "band" | "bor" | "bxor" | "bsl" | "bsr" => TermKind::Integer,
+            "/" => TermKind::Float,
+            "+" | "-" | "*" => TermKind::Number,
+            _ => return None,
+        },
+        _ => {
+            let (callee, _) = ctx.resolve_call(expr)?;
+            if callee.module != "erlang" {
+                return None;
+            }
+            if callee.name.starts_with("is_") {
+                TermKind::Atom
+            } else {
+                BIF_KINDS.iter().find(|(name, _)| *name == callee.name).map(|(_, kind)| *kind)?
+            }
+        }
+    };
+    Some(kind)
+}
+
+/// Result of a comparison decided by the operand kinds alone
+fn constant_comparison(op: &str, left: TermKind, right: TermKind) -> Option<bool> {
+    let same_order = left.order() == right.order();
+    let exact_mismatch = !same_order
+        || matches!((left, right), (TermKind::Integer, TermKind::Float) | (TermKind::Float, TermKind::Integer));
+    match op {
+        "=:=" | "=/=" if exact_mismatch => Some(op == "=/="),
+        "==" | "/=" if !same_order => Some(op == "/="),
+        "<" | "=<" if !same_order => Some(left.order() < right.order()),
+        ">" | ">=" if !same_order => Some(left.order() > right.order()),
+        _ => None,
+    }
+}
+
+/// Terms which evaluate without side effects and without failing: literals, variables and data built of them
+fn is_plain_term(expr: &Expr) -> bool {
+    match syntax::unparenthesize(expr) {
+        Expr::Literal(_) | Expr::Var(_) => true,
+        Expr::Tuple(tuple) => tuple.iter().all(is_plain_term),
+        Expr::List(list) => syntax::list_elements(list).all(is_plain_term)
+            && syntax::list_tail(list).map_or(true, is_plain_term),
+        _ => false,
+    }
+}
+
+/// Comparisons whose result follows from the kinds of the operands, like `length(L) == undefined`.
+/// The fix replaces the comparison with its result only when that drops no calls: `length(X) == ok` also
+/// fails for a non-list `X`.
+#[derive(Default)]
+pub struct ConstantComparison;
+
+impl Rule for ConstantComparison {
+    fn id(&self) -> &'static str {
+        "constant_comparison"
+    }
+
+    fn description(&self) -> &'static str {
+        "Comparison which is always true or always false because of the operand types"
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        let Expr::BinaryOpCall(call) = expr else { return };
+        let op = syntax::binary_op_text(&call.op);
+        let (Some(left), Some(right)) = (term_kind(ctx, &call.left), term_kind(ctx, &call.right)) else { return };
+        let Some(result) = constant_comparison(op, left, right) else { return };
+        let replacement = (is_plain_term(&call.left) && is_plain_term(&call.right)).then(|| result.to_string());
+        report_with_fix(ctx, SourceSpan::from_range(expr, &ctx.unit.path),
+                        format!("Comparing {} with {} using {} is always {}", left.name(), right.name(), op, result),
+                        replacement);
+    }
+}
+
+fn boolean_atom(pattern: &Pattern) -> Option<bool> {
+    match pattern {
+        Pattern::Literal(Literal::Atom(atom)) if atom.value() == "true" => Some(true),
+        Pattern::Literal(Literal::Atom(atom)) if atom.value() == "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// `case X of true -> true; false -> false end` is `X`, and with the results swapped it is `not X`
+#[derive(Default)]
+pub struct RedundantBooleanCase;
+
+impl Rule for RedundantBooleanCase {
+    fn id(&self) -> &'static str {
+        "redundant_boolean_case"
+    }
+
+    fn description(&self) -> &'static str {
+        "case on a boolean which returns the same or the negated boolean"
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        let Expr::Case(case) = expr else { return };
+        let mut negated = Vec::new();
+        let mut matched = Vec::new();
+        for clause in case.clauses.iter() {
+            let mut body = clause.body.exprs.iter();
+            let (Some(result), None) = (body.next(), body.next()) else { return };
+            let (Some(pattern), Some(result)) = (boolean_atom(&clause.pattern), syntax::expr_atom(result)) else {
+                return;
+            };
+            if clause.guard.is_some() || !matches!(result, "true" | "false") {
+                return;
+            }
+            matched.push(pattern);
+            negated.push((result == "true") != pattern);
+        }
+        matched.sort();
+        if matched != [false, true] || negated[0] != negated[1] {
+            return;
+        }
+
+        let subject = ctx.source(&SourceSpan::from_range(&case.expr, &ctx.unit.path));
+        let simple = matches!(syntax::unparenthesize(&case.expr),
+                              Expr::Var(_) | Expr::LocalCall(_) | Expr::RemoteCall(_) | Expr::Parenthesized(_));
+        let operand = subject.map(|s| if simple { s.to_string() } else { format!("({})", s) });
+        let (message, replacement) = if negated[0] {
+            ("This case negates a boolean, use `not`", operand.map(|s| format!("not {}", s)))
+        } else {
+            ("This case returns the boolean it matches on, use the expression directly", operand)
+        };
+        report_with_fix(ctx, SourceSpan::from_range(expr, &ctx.unit.path), message.to_string(), replacement);
+    }
+}
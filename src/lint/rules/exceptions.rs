@@ -0,0 +1,152 @@
+use erl_parse::cst::clauses::CatchClause;
+use erl_parse::cst::Expr;
+use crate::lint::rules::clauses::catch_head;
+use crate::lint::{LintContext, Rule};
+use crate::syntax::walk::{walk_body, Visitor};
+
+/// Modules whose calls count as logging the exception
+const LOGGING_MODULES: &[&str] = &["logger", "error_logger", "lager", "io", "ct"];
+
+/// Calls which raise the exception again or a new one
+const RAISING_CALLS: &[&str] = &["raise", "error", "exit", "throw"];
+
+fn is_variable(part: &str) -> bool {
+    part.starts_with(|c: char| c.is_ascii_uppercase() || c == '_')
+}
+
+/// Parts of a catch clause head split at `:`, like `["Class", "Reason", "Stack"]`
+fn head_parts(head: &str) -> Vec<&str> {
+    head.split(':').map(|p| p.trim()).collect()
+}
+
+/// Whether a catch clause catches every exception of every class: `_:_`, `Class:Reason:Stack` and alike
+fn catches_everything(ctx: &LintContext, clause: &CatchClause) -> bool {
+    if clause.guard.is_some() {
+        return false;
+    }
+    catch_head(ctx, clause).is_some_and(|head| {
+        let parts = head_parts(&head);
+        (parts.len() == 2 || parts.len() == 3) && parts.iter().all(|p| is_variable(p))
+    })
+}
+
+/// Finds calls which log or raise inside a catch clause body
+struct HandlerCalls<'c, 'u> {
+    ctx: &'c LintContext<'u>,
+    handled: bool,
+}
+
+impl<'c, 'u> Visitor for HandlerCalls<'c, 'u> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        let Some((callee, _)) = self.ctx.resolve_call(expr) else { return };
+        if LOGGING_MODULES.contains(&callee.module.as_str())
+            || (callee.module == "erlang" && RAISING_CALLS.contains(&callee.name.as_str())) {
+            self.handled = true;
+        }
+    }
+}
+
+/// `catch _:_ -> ok` and `catch _ -> ...`: a catch clause which ignores the exception it matches, does not
+/// log it and does not raise it again, so failures disappear without a trace
+#[derive(Default)]
+pub struct SwallowedException;
+
+impl Rule for SwallowedException {
+    fn id(&self) -> &'static str {
+        "swallowed_exception"
+    }
+
+    fn description(&self) -> &'static str {
+        "Catch clause discards every exception without logging or rethrowing it"
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        let Expr::Try(try_expr) = expr else { return };
+        let Some(catch) = &try_expr.catch else { return };
+        for clause in catch.clauses.iter().filter(|c| c.guard.is_none()) {
+            let Some(head) = catch_head(ctx, clause) else { continue };
+            // Only underscore variables: the exception is not even looked at
+            if !head_parts(&head).iter().all(|p| p.starts_with('_')) {
+                continue;
+            }
+            let mut calls = HandlerCalls { ctx: &*ctx, handled: false };
+            walk_body(&mut calls, &clause.body);
+            if !calls.handled {
+                ctx.report(clause, format!("`catch {} ->` discards the exception without logging or rethrowing \
+                                            it. Match the exceptions you expect, or log them", head));
+            }
+        }
+    }
+}
+
+/// Old style `catch Expr`, which turns errors, exits and throws into values that look like results
+#[derive(Default)]
+pub struct OldStyleCatch;
+
+impl Rule for OldStyleCatch {
+    fn id(&self) -> &'static str {
+        "old_style_catch"
+    }
+
+    fn description(&self) -> &'static str {
+        "catch Expr instead of try ... catch"
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        if let Expr::Catch(_) = expr {
+            ctx.report(expr, "`catch Expr` mixes thrown values, {'EXIT', Reason} tuples and normal results, and \
+                              builds a stacktrace for errors. Use try ... catch with the classes you expect"
+                .to_string());
+        }
+    }
+}
+
+/// `try Expr of ... catch Class:Reason -> ...`: the catch-all covers only `Expr`, exceptions in the `of`
+/// clauses are not caught. A catch-all next to `of` usually means the author expected otherwise.
+#[derive(Default)]
+pub struct TryOfCatchAll;
+
+impl Rule for TryOfCatchAll {
+    fn id(&self) -> &'static str {
+        "try_of_catch_all"
+    }
+
+    fn description(&self) -> &'static str {
+        "try ... of with a catch-all clause, which does not cover the of clauses"
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        let Expr::Try(try_expr) = expr else { return };
+        let (Some(_), Some(catch)) = (&try_expr.branch, &try_expr.catch) else { return };
+        if let Some(clause) = catch.clauses.iter().find(|c| catches_everything(ctx, c)) {
+            ctx.report(clause, "This catch-all handles any exception between try and of, while exceptions raised \
+                                in the of clauses propagate. Catch only what the try body raises, or move the \
+                                of clauses out of the try"
+                .to_string());
+        }
+    }
+}
+
+/// `erlang:get_stacktrace/0`, which returned the stacktrace of the latest exception of the process. It always
+/// returns `[]` since OTP 23 and was removed in OTP 24.
+#[derive(Default)]
+pub struct GetStacktrace;
+
+impl Rule for GetStacktrace {
+    fn id(&self) -> &'static str {
+        "get_stacktrace"
+    }
+
+    fn description(&self) -> &'static str {
+        "erlang:get_stacktrace/0 instead of the Class:Reason:Stacktrace pattern"
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        let Some((callee, _)) = ctx.resolve_call(expr) else { return };
+        if callee.module == "erlang" && callee.name == "get_stacktrace" && callee.arity == 0 {
+            ctx.report(expr, "erlang:get_stacktrace() returns [] since OTP 23 and was removed in OTP 24. Bind the \
+                              stacktrace in the catch clause: catch Class:Reason:Stacktrace ->"
+                .to_string());
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::{HashMap, HashSet};
+use erl_parse::cst::Form;
+use glob::Pattern;
+use crate::diagnostic::{Severity, SourceSpan};
+use crate::error::{IroncladError, IroncladResult};
+use crate::lint::config::param_string_list;
+use crate::lint::{LintContext, Rule};
+use crate::syntax;
+use crate::syntax::mfa::FunArity;
+
+/// One of the export checks, all of them compare the `-export` and `-export_type` lists with the module
+pub struct ExportRule {
+    id: &'static str,
+    description: &'static str,
+    severity: Severity,
+    check: fn(&mut LintContext, &ModuleExports),
+}
+
+impl Rule for ExportRule {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn default_severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let exports = ModuleExports::collect(ctx);
+        (self.check)(ctx, &exports);
+    }
+}
+
+/// Export list entries in source order, with what the module defines and imports
+struct ModuleExports {
+    functions: Vec<(FunArity, SourceSpan)>,
+    types: Vec<(FunArity, SourceSpan)>,
+    defined_functions: HashSet<FunArity>,
+    defined_types: HashSet<FunArity>,
+    imports: HashMap<FunArity, String>,
+}
+
+impl ModuleExports {
+    fn collect(ctx: &LintContext) -> Self {
+        let (forms, path) = (&ctx.unit.forms, &ctx.unit.path);
+        let mut functions = Vec::new();
+        let mut types = Vec::new();
+        let mut defined_types = HashSet::new();
+        for form in forms.iter() {
+            match form {
+                Form::ExportAttr(attr) => {
+                    for export in attr.exports.iter() {
+                        if let Some(arity) = syntax::integer_value(&export.arity) {
+                            functions.push((FunArity::new(export.name.value(), arity),
+                                            SourceSpan::from_range(export, path)));
+                        }
+                    }
+                }
+                Form::ExportTypeAttr(attr) => {
+                    for export in attr.exports.iter() {
+                        if let Some(arity) = syntax::integer_value(&export.arity) {
+                            types.push((FunArity::new(export.name.value(), arity),
+                                        SourceSpan::from_range(export, path)));
+                        }
+                    }
+                }
+                Form::TypeDecl(decl) => {
+                    defined_types.insert(FunArity::new(decl.type_name.value(), decl.variables.iter().count()));
+                }
+                _ => {}
+            }
+        }
+        Self {
+            functions,
+            types,
+            defined_functions: syntax::defined_functions(forms),
+            defined_types,
+            imports: syntax::imported_functions(forms),
+        }
+    }
+}
+
+/// A hint listing the arities a name is defined with, for typos in the export list
+fn other_arities(defined: &HashSet<FunArity>, name: &str) -> String {
+    let mut arities: Vec<String> = defined.iter().filter(|f| f.name == name).map(|f| f.to_string()).collect();
+    arities.sort();
+    if arities.is_empty() {
+        String::new()
+    } else {
+        format!(", the module defines {}", arities.join(", "))
+    }
+}
+
+fn undefined_export(ctx: &mut LintContext, exports: &ModuleExports) {
+    for (fun, span) in exports.functions.iter() {
+        if !exports.defined_functions.contains(fun) && !exports.imports.contains_key(fun) {
+            ctx.report_span(span.clone(), format!("Exported function {} is not defined{}", fun,
+                                                  other_arities(&exports.defined_functions, &fun.name)));
+        }
+    }
+}
+
+fn duplicate_export(ctx: &mut LintContext, exports: &ModuleExports) {
+    let mut first_lines: HashMap<&FunArity, usize> = HashMap::new();
+    for (fun, span) in exports.functions.iter() {
+        match first_lines.get(fun) {
+            Some(line) => ctx.report_span(span.clone(), format!("Function {} is already exported on line {}", fun,
+                                                                line)),
+            None => {
+                first_lines.insert(fun, span.start_line);
+            }
+        }
+    }
+}
+
+fn export_imported(ctx: &mut LintContext, exports: &ModuleExports) {
+    for (fun, span) in exports.functions.iter() {
+        let Some(module) = exports.imports.get(fun) else { continue };
+        let problem = if exports.defined_functions.contains(fun) {
+            "the local definition conflicts with the import"
+        } else {
+            "a module can only export its own functions"
+        };
+        ctx.report_span(span.clone(), format!("Exported function {} is also imported from {}; {}", fun, module,
+                                              problem));
+    }
+}
+
+fn undefined_export_type(ctx: &mut LintContext, exports: &ModuleExports) {
+    for (ty, span) in exports.types.iter() {
+        if !exports.defined_types.contains(ty) {
+            ctx.report_span(span.clone(), format!("Exported type {} is not defined{}", ty,
+                                                  other_arities(&exports.defined_types, &ty.name)));
+        }
+    }
+}
+
+pub fn export_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(ExportRule {
+            id: "undefined_export",
+            description: "-export lists a function the module does not define",
+            severity: Severity::Error,
+            check: undefined_export,
+        }),
+        Box::new(ExportRule {
+            id: "duplicate_export",
+            description: "Function exported more than once",
+            severity: Severity::Warning,
+            check: duplicate_export,
+        }),
+        Box::new(ExportRule {
+            id: "export_imported",
+            description: "Exported function also listed in -import",
+            severity: Severity::Error,
+            check: export_imported,
+        }),
+        Box::new(ExportRule {
+            id: "undefined_export_type",
+            description: "-export_type lists a type the module does not define",
+            severity: Severity::Error,
+            check: undefined_export_type,
+        }),
+    ]
+}
+
+/// `-compile(export_all)`, which hides unused functions and makes every function part of the API.
+/// Modules matching one of the `except` glob patterns, test suites by default, may use it.
+pub struct ExportAll {
+    except: Vec<Pattern>,
+}
+
+impl Default for ExportAll {
+    fn default() -> Self {
+        Self {
+            except: ["*_SUITE", "*_tests"].iter().map(|p| Pattern::new(p).unwrap()).collect(),
+        }
+    }
+}
+
+impl Rule for ExportAll {
+    fn id(&self) -> &'static str {
+        "export_all"
+    }
+
+    fn description(&self) -> &'static str {
+        "-compile(export_all) outside of test modules (param: except, default [\"*_SUITE\", \"*_tests\"])"
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        let rule_id = self.id();
+        if let Some(except) = param_string_list(params, rule_id, "except")? {
+            self.except = except.iter()
+                .map(|p| Pattern::new(p).map_err(|e| {
+                    IroncladError::LintConfig(format!("lints.{}.except: bad pattern '{}': {}", rule_id, p, e))
+                }))
+                .collect::<IroncladResult<_>>()?;
+        }
+        Ok(())
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let unit = ctx.unit;
+        if self.except.iter().any(|p| p.matches(&unit.name)) {
+            return;
+        }
+        let export_all = syntax::wild_attributes(&unit.forms, "compile")
+            .flat_map(syntax::attribute_values)
+            .filter(|value| syntax::expr_atom(value) == Some("export_all"));
+        for value in export_all {
+            ctx.report(value, "export_all exports every function, which hides unused code and makes internal \
+                               functions part of the API. List the exports explicitly".to_string());
+        }
+    }
+}
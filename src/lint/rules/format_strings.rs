@@ -0,0 +1,244 @@
+use erl_parse::cst::{Expr, Literal};
+use crate::diagnostic::Severity;
+use crate::lint::{LintContext, Rule};
+use crate::syntax;
+use crate::syntax::mfa::MFArity;
+
+/// Log level functions of `logger` and `lager`
+const LOG_LEVELS: &[&str] = &["debug", "info", "notice", "warning", "error", "critical", "alert", "emergency"];
+
+/// One `~` control sequence of a format string
+#[derive(Debug)]
+struct Directive {
+    control: char,
+    /// Index of the formatted term in the argument list, `None` for `~~` and `~n`
+    value_index: Option<usize>,
+}
+
+/// A parsed format string: its directives and the number of arguments they consume
+#[derive(Debug)]
+struct FormatString {
+    directives: Vec<Directive>,
+    arg_count: usize,
+}
+
+/// Number of arguments consumed by a control character besides `*` widths and the `K` modifier
+fn control_args(control: char) -> Option<usize> {
+    match control {
+        '~' | 'n' => Some(0),
+        'c' | 'f' | 'e' | 'g' | 's' | 'w' | 'p' | 'B' | 'b' | '#' | '+' | 'i' => Some(1),
+        'W' | 'P' | 'X' | 'x' => Some(2),
+        _ => None,
+    }
+}
+
+/// Parse a format string as described in `io:format/2`: `~F.P.PadModC`
+fn parse_format(text: &str) -> Result<FormatString, String> {
+    let mut chars = text.chars().peekable();
+    let mut result = FormatString { directives: Vec::new(), arg_count: 0 };
+    while let Some(c) = chars.next() {
+        if c != '~' {
+            continue;
+        }
+        let mut star_args = 0;
+        // Field width, then precision and padding after dots
+        if chars.peek() == Some(&'-') {
+            chars.next();
+        }
+        let mut fields = 0;
+        loop {
+            match chars.peek() {
+                Some('*') => {
+                    chars.next();
+                    star_args += 1;
+                }
+                Some(d) if d.is_ascii_digit() => {
+                    chars.next();
+                }
+                Some('.') if fields < 2 => {
+                    chars.next();
+                    fields += 1;
+                    if fields == 2 {
+                        match chars.next() {
+                            Some('*') => star_args += 1,
+                            Some(_) => {}
+                            None => return Err("the format string ends inside a control sequence".to_string()),
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+        while let Some(&modifier) = chars.peek().filter(|m| matches!(m, 't' | 'l' | 'k' | 'K')) {
+            chars.next();
+            if modifier == 'K' {
+                star_args += 1;
+            }
+        }
+        let Some(control) = chars.next() else {
+            return Err("the format string ends inside a control sequence".to_string());
+        };
+        let Some(args) = control_args(control) else {
+            return Err(format!("~{} is not a valid control sequence", control));
+        };
+        let value_index = (args > 0).then_some(result.arg_count + star_args);
+        result.arg_count += star_args + args;
+        result.directives.push(Directive { control, value_index });
+    }
+    Ok(result)
+}
+
+/// A call with a literal format string and its argument list, when that is a literal list too
+struct FormatCall<'e> {
+    callee: MFArity,
+    format: &'e Expr,
+    text: String,
+    /// `None` when the arguments are built at runtime
+    args: Option<Vec<&'e Expr>>,
+}
+
+/// Positions of the format string and the argument list for the known formatting functions
+fn format_positions(callee: &MFArity, first_is_string: bool) -> Option<(usize, Option<usize>)> {
+    let (module, name, arity) = (callee.module.as_str(), callee.name.as_str(), callee.arity);
+    match (module, name, arity) {
+        ("io", "format" | "fwrite", 1) | ("error_logger", "info_msg" | "warning_msg" | "error_msg", 1) => {
+            Some((0, None))
+        }
+        ("io" | "io_lib", "format" | "fwrite", 2)
+        | ("error_logger", "info_msg" | "warning_msg" | "error_msg" | "format", 2) => Some((0, Some(1))),
+        ("io", "format" | "fwrite", 3) => Some((1, Some(2))),
+        ("logger", "log", 3 | 4) => Some((1, Some(2))),
+        // Other logger forms take a report or a map, only a literal string marks the format form
+        ("logger", level, 2 | 3) if LOG_LEVELS.contains(&level) && first_is_string => Some((0, Some(1))),
+        ("lager", level, 1) if LOG_LEVELS.contains(&level) => Some((0, None)),
+        ("lager", level, 2) if LOG_LEVELS.contains(&level) => Some((0, Some(1))),
+        ("lager", level, 3) if LOG_LEVELS.contains(&level) => Some((1, Some(2))),
+        _ => None,
+    }
+}
+
+fn format_call<'e>(ctx: &LintContext, expr: &'e Expr) -> Option<FormatCall<'e>> {
+    let (callee, args) = ctx.resolve_call(expr)?;
+    let first_is_string = args.first().is_some_and(|arg| syntax::expr_string(arg).is_some());
+    let (format_index, args_index) = format_positions(&callee, first_is_string)?;
+    let format = *args.get(format_index)?;
+    let text = syntax::expr_string(format)?;
+    let format_args = match args_index {
+        None => Some(Vec::new()),
+        Some(index) => match syntax::unparenthesize(args.get(index)?) {
+            Expr::List(list) if syntax::list_tail(list).is_none() => Some(syntax::list_elements(list).collect()),
+            // io:format("~c~c", "ab") passes each character as an argument
+            Expr::Literal(Literal::String(_)) => syntax::expr_string(args[index])
+                .map(|s| s.chars().map(|_| args[index]).collect()),
+            _ => None,
+        },
+    };
+    Some(FormatCall { callee, format, text, args: format_args })
+}
+
+/// A check of calls with a literal format string
+pub struct FormatRule {
+    id: &'static str,
+    description: &'static str,
+    severity: Severity,
+    check: fn(&mut LintContext, &FormatCall, &FormatString),
+}
+
+impl Rule for FormatRule {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn default_severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        let Some(call) = format_call(ctx, expr) else { return };
+        if let Ok(format) = parse_format(&call.text) {
+            (self.check)(ctx, &call, &format);
+        }
+    }
+}
+
+fn format_args_mismatch(ctx: &mut LintContext, call: &FormatCall, format: &FormatString) {
+    let Some(args) = &call.args else { return };
+    if args.len() != format.arg_count {
+        ctx.report(call.format, format!("The format string of {} expects {} argument(s), but {} are given",
+                                        call.callee, format.arg_count, args.len()));
+    }
+}
+
+/// A term which `~s` can not print: numbers, tuples, maps, records and funs
+fn non_string_kind(expr: &Expr) -> Option<&'static str> {
+    match syntax::unparenthesize(expr) {
+        Expr::Literal(Literal::Integer(_)) | Expr::Literal(Literal::Char(_)) => Some("an integer"),
+        Expr::Literal(Literal::Float(_)) => Some("a float"),
+        Expr::Tuple(_) => Some("a tuple"),
+        Expr::Map(_) | Expr::MapUpdate(_) => Some("a map"),
+        Expr::Record(_) | Expr::RecordUpdate(_) => Some("a record"),
+        Expr::AnonymousFun(_) | Expr::NamedFun(_) | Expr::LocalFun(_) | Expr::RemoteFun(_) => Some("a fun"),
+        _ => None,
+    }
+}
+
+fn format_string_arg(ctx: &mut LintContext, call: &FormatCall, format: &FormatString) {
+    let Some(args) = &call.args else { return };
+    for directive in format.directives.iter().filter(|d| d.control == 's') {
+        let Some(arg) = directive.value_index.and_then(|index| args.get(index)) else { continue };
+        // Arguments given as a string literal are characters, checked by the argument count only
+        if matches!(syntax::unparenthesize(arg), Expr::Literal(Literal::String(_))) {
+            continue;
+        }
+        if let Some(kind) = non_string_kind(arg) {
+            ctx.report(*arg, format!("~s is given {}, which fails at runtime. Use ~p or ~w", kind));
+        }
+    }
+}
+
+/// Invalid directives are found before the other checks, which skip format strings that do not parse
+#[derive(Default)]
+pub struct InvalidFormatDirective;
+
+impl Rule for InvalidFormatDirective {
+    fn id(&self) -> &'static str {
+        "invalid_format_directive"
+    }
+
+    fn description(&self) -> &'static str {
+        "Invalid control sequence in the format string of io:format, logger and alike"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        let Some(call) = format_call(ctx, expr) else { return };
+        if let Err(reason) = parse_format(&call.text) {
+            ctx.report(call.format, format!("Bad format string for {}: {}", call.callee, reason));
+        }
+    }
+}
+
+pub fn format_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(InvalidFormatDirective),
+        Box::new(FormatRule {
+            id: "format_args_mismatch",
+            description: "Number of format directives differs from the number of arguments",
+            severity: Severity::Error,
+            check: format_args_mismatch,
+        }),
+        Box::new(FormatRule {
+            id: "format_string_arg",
+            description: "~s directive given a number, tuple, map, record or fun",
+            severity: Severity::Warning,
+            check: format_string_arg,
+        }),
+    ]
+}
@@ -0,0 +1,267 @@
+use std::path::PathBuf;
+use erl_parse::cst::{GuardTest, Literal, Pattern};
+use erl_tokenize::{PositionRange, Token};
+use crate::diagnostic::{Severity, SourceSpan};
+use crate::lint::{LintContext, Rule};
+use crate::project::preprocessor_info::position_file;
+use crate::syntax;
+use crate::syntax::mfa::FunArity;
+
+/// BIFs allowed in guards, with their arities
+const GUARD_BIFS: &[(&str, usize)] = &[
+    ("abs", 1), ("binary_part", 2), ("binary_part", 3), ("bit_size", 1), ("byte_size", 1), ("ceil", 1),
+    ("element", 2), ("float", 1), ("floor", 1), ("hd", 1), ("is_map_key", 2), ("length", 1), ("map_get", 2),
+    ("map_size", 1), ("max", 2), ("min", 2), ("node", 0), ("node", 1), ("round", 1), ("self", 0), ("size", 1),
+    ("tl", 1), ("trunc", 1), ("tuple_size", 1),
+    ("is_atom", 1), ("is_binary", 1), ("is_bitstring", 1), ("is_boolean", 1), ("is_float", 1),
+    ("is_function", 1), ("is_function", 2), ("is_integer", 1), ("is_list", 1), ("is_map", 1), ("is_number", 1),
+    ("is_pid", 1), ("is_port", 1), ("is_record", 2), ("is_record", 3), ("is_reference", 1), ("is_tuple", 1),
+];
+
+fn is_guard_bif(name: &str, arity: usize) -> bool {
+    GUARD_BIFS.contains(&(name, arity))
+}
+
+/// Calls in guards to anything but the guard BIFs, which erlc rejects as "illegal guard expression"
+#[derive(Default)]
+pub struct IllegalGuardCall;
+
+impl Rule for IllegalGuardCall {
+    fn id(&self) -> &'static str {
+        "illegal_guard_call"
+    }
+
+    fn description(&self) -> &'static str {
+        "Call to a local function or a non-guard BIF in a guard"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check_guard_test(&mut self, ctx: &mut LintContext, test: &GuardTest) {
+        match test {
+            GuardTest::LocalCall(call) => {
+                let Some(name) = syntax::guard_atom(&call.func) else { return };
+                let arity = call.args.iter().count();
+                if is_guard_bif(name, arity) {
+                    return;
+                }
+                let fun_arity = FunArity::new(name, arity);
+                let message = if syntax::defined_functions(&ctx.unit.forms).contains(&fun_arity) {
+                    format!("Local function {} can not be called in a guard, call it before the clause and \
+                             match on the result", fun_arity)
+                } else {
+                    format!("{} is not a guard BIF and can not be called in a guard", fun_arity)
+                };
+                ctx.report(test, message);
+            }
+            GuardTest::RemoteCall(call) => {
+                let (Some(module), Some(name)) = (syntax::guard_atom(&call.module_name), syntax::guard_atom(&call.func))
+                    else { return };
+                let arity = call.args.iter().count();
+                if module != "erlang" || !is_guard_bif(name, arity) {
+                    ctx.report(test, format!("{}:{}/{} is not a guard BIF and can not be called in a guard",
+                                             module, name, arity));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Where a record or map update may be starting, seen in the tokens of a guard
+enum UpdateState {
+    Idle,
+    /// `Expr#`
+    Hash(SourceSpan),
+    /// `Expr#name`
+    HashName(SourceSpan),
+}
+
+/// `R#rec{...}` and `M#{...}` updates in a `when` guard. The syntax tree has no guard node for them, so they
+/// are found in the tokens between `when` and `->`.
+pub struct GuardUpdate {
+    in_guard: bool,
+    /// The previous significant token ends an expression, like a variable, `)` or `}`
+    after_operand: bool,
+    /// The previous token is `.`, which ends the form when whitespace follows, unlike in `R#rec.field`
+    after_dot: bool,
+    state: UpdateState,
+}
+
+impl Default for GuardUpdate {
+    fn default() -> Self {
+        Self { in_guard: false, after_operand: false, after_dot: false, state: UpdateState::Idle }
+    }
+}
+
+impl Rule for GuardUpdate {
+    fn id(&self) -> &'static str {
+        "illegal_guard_update"
+    }
+
+    fn description(&self) -> &'static str {
+        "Record or map update in a guard"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn uses_tokens(&self) -> bool {
+        true
+    }
+
+    fn check_token(&mut self, ctx: &mut LintContext, token: &Token) {
+        if std::mem::take(&mut self.after_dot) && matches!(token, Token::Whitespace(_) | Token::Comment(_)) {
+            self.in_guard = false;
+        }
+        let text = match token {
+            Token::Whitespace(_) | Token::Comment(_) => return,
+            Token::Keyword(keyword) => keyword.text(),
+            Token::Symbol(symbol) => symbol.text(),
+            Token::Atom(_) => "atom",
+            _ => "",
+        };
+        match text {
+            "when" => self.in_guard = true,
+            "->" => self.in_guard = false,
+            "." => self.after_dot = true,
+            _ => {}
+        }
+
+        let state = std::mem::replace(&mut self.state, UpdateState::Idle);
+        if self.in_guard {
+            match (state, text) {
+                (UpdateState::Idle, "#") if self.after_operand => {
+                    self.state = UpdateState::Hash(SourceSpan::from_range(token, &ctx.unit.path));
+                }
+                (UpdateState::Hash(span), "atom") => self.state = UpdateState::HashName(span),
+                (UpdateState::Hash(span), "{") => {
+                    ctx.report_span(span, "Map update is not allowed in a guard, build the map before the \
+                                           clause".to_string());
+                }
+                (UpdateState::HashName(span), "{") => {
+                    ctx.report_span(span, "Record update is not allowed in a guard, build the record before the \
+                                           clause".to_string());
+                }
+                _ => {}
+            }
+        }
+        self.after_operand = matches!(token, Token::Variable(_)) || text == ")" || text == "}";
+    }
+
+    fn check_unit(&mut self, _ctx: &mut LintContext) {
+        *self = Self::default();
+    }
+}
+
+/// Whether a pattern is a constant expression allowed as a binary segment size: integers and bound variables,
+/// combined with arithmetic
+fn is_valid_size(size: &Pattern) -> bool {
+    match size {
+        Pattern::Literal(Literal::Integer(_)) | Pattern::Var(_) => true,
+        Pattern::Parenthesized(inner) => is_valid_size(&inner.item),
+        Pattern::UnaryOpCall(call) => is_valid_size(&call.operand),
+        Pattern::BinaryOpCall(call) => {
+            is_arithmetic(syntax::binary_op_text(&call.op)) && is_valid_size(&call.left) && is_valid_size(&call.right)
+        }
+        _ => false,
+    }
+}
+
+/// Literal numbers combined with arithmetic, evaluated by the compiler
+fn is_constant(pattern: &Pattern) -> bool {
+    match pattern {
+        Pattern::Literal(Literal::Integer(_) | Literal::Float(_) | Literal::Char(_)) => true,
+        Pattern::Parenthesized(inner) => is_constant(&inner.item),
+        Pattern::UnaryOpCall(call) => is_constant(&call.operand),
+        Pattern::BinaryOpCall(call) => {
+            is_arithmetic(syntax::binary_op_text(&call.op)) && is_constant(&call.left) && is_constant(&call.right)
+        }
+        _ => false,
+    }
+}
+
+/// Arithmetic with a non-constant operand somewhere inside
+fn is_bad_arithmetic(pattern: &Pattern) -> bool {
+    match pattern {
+        Pattern::Parenthesized(inner) => is_bad_arithmetic(&inner.item),
+        Pattern::BinaryOpCall(call) => is_arithmetic(syntax::binary_op_text(&call.op)) && !is_constant(pattern),
+        _ => false,
+    }
+}
+
+fn is_arithmetic(op: &str) -> bool {
+    matches!(op, "+" | "-" | "*" | "/" | "div" | "rem" | "band" | "bor" | "bxor" | "bsl" | "bsr")
+}
+
+/// Patterns erlc rejects as "illegal pattern": binary sizes which are not integers or variables, and
+/// operators other than arithmetic on literal numbers and `"prefix" ++ Tail`. Function calls in patterns are
+/// already rejected by the parser.
+#[derive(Default)]
+pub struct IllegalPattern {
+    /// File and offsets of binary segment sizes in the current module, these are expressions and may use
+    /// variables. Patterns from included headers have offsets of their own file.
+    size_ranges: Vec<(PathBuf, usize, usize)>,
+}
+
+impl Rule for IllegalPattern {
+    fn id(&self) -> &'static str {
+        "illegal_pattern"
+    }
+
+    fn description(&self) -> &'static str {
+        "Pattern which can not be matched: bad binary segment size or operator"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check_pattern(&mut self, ctx: &mut LintContext, pattern: &Pattern) {
+        match pattern {
+            Pattern::Bits(bits) => {
+                for elem in bits.iter() {
+                    let Some(size) = elem.size.as_ref().map(|s| &s.size) else { continue };
+                    let file = position_file(&size.start_position(), &ctx.unit.path);
+                    self.size_ranges.push((file, size.start_position().offset(), size.end_position().offset()));
+                    if !is_valid_size(size) {
+                        ctx.report(size, "Binary segment size in a pattern must be an integer, a bound variable \
+                                          or arithmetic on them".to_string());
+                    }
+                }
+            }
+            Pattern::BinaryOpCall(call) => {
+                let position = pattern.start_position();
+                let file = position_file(&position, &ctx.unit.path);
+                let offset = position.offset();
+                if self.size_ranges.iter().any(|(f, start, end)| *f == file && (*start..*end).contains(&offset)) {
+                    return;
+                }
+                let op = syntax::binary_op_text(&call.op);
+                // Any proper list can prefix a pattern: "abc" ++ T and [$a, $b] ++ T
+                let list_prefix = op == "++" && match &call.left {
+                    Pattern::Literal(Literal::String(_)) => true,
+                    Pattern::List(list) => syntax::list_tail(list).is_none(),
+                    _ => false,
+                };
+                if list_prefix {
+                    return;
+                }
+                if !is_arithmetic(op) {
+                    ctx.report(pattern, format!("Operator {} is not allowed in a pattern", op));
+                } else if !is_constant(pattern) && !is_bad_arithmetic(&call.left) && !is_bad_arithmetic(&call.right) {
+                    // Reported once, at the innermost operator with a non-constant operand
+                    ctx.report(pattern, "Arithmetic in a pattern must only use literal numbers".to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_unit(&mut self, _ctx: &mut LintContext) {
+        self.size_ranges.clear();
+    }
+}
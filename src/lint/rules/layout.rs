@@ -0,0 +1,312 @@
+use erl_tokenize::Token;
+use crate::diagnostic::{Fix, SourceSpan};
+use crate::error::IroncladResult;
+use crate::lint::config::{param_string_list, param_usize};
+use crate::lint::{LintContext, Rule};
+
+// Layout rules read the module file as written, before preprocessing. Included headers are not checked.
+
+fn char_len(line: &str) -> usize {
+    line.chars().count()
+}
+
+/// Lines longer than `max_length` characters
+pub struct LineLength {
+    max_length: usize,
+}
+
+impl Default for LineLength {
+    fn default() -> Self {
+        Self { max_length: 100 }
+    }
+}
+
+impl Rule for LineLength {
+    fn id(&self) -> &'static str {
+        "line_too_long"
+    }
+
+    fn description(&self) -> &'static str {
+        "Line is longer than the limit (param: max_length)"
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        self.max_length = param_usize(params, self.id(), "max_length")?.unwrap_or(self.max_length);
+        Ok(())
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let unit = ctx.unit;
+        for (index, line) in unit.source_text.lines().enumerate() {
+            let length = char_len(line);
+            if length > self.max_length {
+                let span = SourceSpan::columns(&unit.path, index + 1, self.max_length + 1, length + 1);
+                ctx.report_span(span, format!("Line is {} characters long, the limit is {}", length,
+                                              self.max_length));
+            }
+        }
+    }
+}
+
+/// Spaces or tabs at the end of a line
+#[derive(Default)]
+pub struct TrailingWhitespace;
+
+impl Rule for TrailingWhitespace {
+    fn id(&self) -> &'static str {
+        "trailing_whitespace"
+    }
+
+    fn description(&self) -> &'static str {
+        "Line ends with spaces or tabs"
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let unit = ctx.unit;
+        for (index, line) in unit.source_text.lines().enumerate() {
+            let trimmed = char_len(line.trim_end());
+            let length = char_len(line);
+            if trimmed < length {
+                let span = SourceSpan::columns(&unit.path, index + 1, trimmed + 1, length + 1);
+                ctx.report_fix(span.clone(), "Trailing whitespace".to_string(), Fix::delete(span));
+            }
+        }
+    }
+}
+
+/// Tabs in the indentation, fixed by expanding them to `tab_width` spaces
+pub struct TabIndentation {
+    tab_width: usize,
+}
+
+impl Default for TabIndentation {
+    fn default() -> Self {
+        Self { tab_width: 4 }
+    }
+}
+
+impl Rule for TabIndentation {
+    fn id(&self) -> &'static str {
+        "tab_indentation"
+    }
+
+    fn description(&self) -> &'static str {
+        "Line is indented with tabs (param: tab_width, used by the fix)"
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        self.tab_width = param_usize(params, self.id(), "tab_width")?.unwrap_or(self.tab_width);
+        Ok(())
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let unit = ctx.unit;
+        for (index, line) in unit.source_text.lines().enumerate() {
+            let indent: String = line.chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+            if !indent.contains('\t') {
+                continue;
+            }
+            let width = self.tab_width.max(1);
+            let mut expanded = String::new();
+            for c in indent.chars() {
+                match c {
+                    '\t' => expanded.push_str(&" ".repeat(width - expanded.len() % width)),
+                    _ => expanded.push(c),
+                }
+            }
+            let span = SourceSpan::columns(&unit.path, index + 1, 1, char_len(&indent) + 1);
+            ctx.report_fix(span.clone(), "Indentation contains tabs".to_string(), Fix::replace(span, &expanded));
+        }
+    }
+}
+
+/// The last line of the file has no newline
+#[derive(Default)]
+pub struct MissingFinalNewline;
+
+impl Rule for MissingFinalNewline {
+    fn id(&self) -> &'static str {
+        "missing_final_newline"
+    }
+
+    fn description(&self) -> &'static str {
+        "File does not end with a newline"
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let unit = ctx.unit;
+        let text = unit.source_text.as_str();
+        if text.is_empty() || text.ends_with('\n') {
+            return;
+        }
+        let line = text.lines().count();
+        let column = char_len(text.lines().last().unwrap_or_default()) + 1;
+        ctx.report_fix(SourceSpan::columns(&unit.path, line, column, column),
+                       "File does not end with a newline".to_string(),
+                       Fix::insert(&unit.path, line, column, "\n"));
+    }
+}
+
+/// More than `max_blank` blank lines in a row, the extra ones are removed by the fix
+pub struct MultipleBlankLines {
+    max_blank: usize,
+}
+
+impl Default for MultipleBlankLines {
+    fn default() -> Self {
+        Self { max_blank: 1 }
+    }
+}
+
+impl Rule for MultipleBlankLines {
+    fn id(&self) -> &'static str {
+        "multiple_blank_lines"
+    }
+
+    fn description(&self) -> &'static str {
+        "Too many consecutive blank lines (param: max_blank)"
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        self.max_blank = param_usize(params, self.id(), "max_blank")?.unwrap_or(self.max_blank);
+        Ok(())
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let unit = ctx.unit;
+        let lines: Vec<&str> = unit.source_text.lines().collect();
+        let mut index = 0;
+        while index < lines.len() {
+            if !lines[index].trim().is_empty() {
+                index += 1;
+                continue;
+            }
+            let run_start = index;
+            while index < lines.len() && lines[index].trim().is_empty() {
+                index += 1;
+            }
+            let count = index - run_start;
+            if count > self.max_blank {
+                // Lines are 1-based: the extra lines start after the allowed ones and end before `index + 1`
+                let span = SourceSpan {
+                    file: unit.path.clone(),
+                    start_line: run_start + self.max_blank + 1,
+                    start_column: 1,
+                    end_line: index + 1,
+                    end_column: 1,
+                };
+                ctx.report_fix(span.clone(), format!("{} blank lines in a row, at most {} allowed", count,
+                                                     self.max_blank),
+                               Fix::delete(span));
+            }
+        }
+    }
+}
+
+/// Symbol text of a token, `None` for atoms, strings, whitespace and comments
+fn symbol_text(token: &Token) -> Option<&str> {
+    match token {
+        Token::Symbol(symbol) => Some(symbol.text()),
+        _ => None,
+    }
+}
+
+/// A comma followed directly by the next element
+#[derive(Default)]
+pub struct CommaSpacing {
+    /// The comma just seen, waiting for the next token
+    pending: Option<SourceSpan>,
+}
+
+impl Rule for CommaSpacing {
+    fn id(&self) -> &'static str {
+        "comma_spacing"
+    }
+
+    fn description(&self) -> &'static str {
+        "Comma is not followed by a space or a line break"
+    }
+
+    fn uses_tokens(&self) -> bool {
+        true
+    }
+
+    fn check_token(&mut self, ctx: &mut LintContext, token: &Token) {
+        if let Some(comma) = self.pending.take() {
+            if !matches!(token, Token::Whitespace(_)) {
+                let fix = Fix::insert(&comma.file, comma.end_line, comma.end_column, " ");
+                ctx.report_fix(comma, "Missing space after comma".to_string(), fix);
+            }
+        }
+        if symbol_text(token) == Some(",") {
+            self.pending = Some(SourceSpan::from_range(token, &ctx.unit.path));
+        }
+    }
+
+    fn check_unit(&mut self, _ctx: &mut LintContext) {
+        self.pending = None;
+    }
+}
+
+/// Operators which must have a space or line break on both sides
+pub struct OperatorSpacing {
+    operators: Vec<String>,
+    /// The operator just seen, waiting for the next token
+    pending: Option<(SourceSpan, String)>,
+    previous_is_space: bool,
+}
+
+impl Default for OperatorSpacing {
+    fn default() -> Self {
+        Self {
+            operators: ["->", "=", "|", "||"].iter().map(|op| op.to_string()).collect(),
+            pending: None,
+            previous_is_space: true,
+        }
+    }
+}
+
+impl Rule for OperatorSpacing {
+    fn id(&self) -> &'static str {
+        "operator_spacing"
+    }
+
+    fn description(&self) -> &'static str {
+        "Operator is not surrounded by spaces (param: operators, default -> = | ||)"
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        if let Some(operators) = param_string_list(params, self.id(), "operators")? {
+            self.operators = operators;
+        }
+        Ok(())
+    }
+
+    fn uses_tokens(&self) -> bool {
+        true
+    }
+
+    fn check_token(&mut self, ctx: &mut LintContext, token: &Token) {
+        let is_space = matches!(token, Token::Whitespace(_) | Token::Comment(_));
+        if let Some((op_span, op)) = self.pending.take() {
+            if !is_space {
+                let fix = Fix::insert(&op_span.file, op_span.end_line, op_span.end_column, " ");
+                ctx.report_fix(op_span, format!("Missing space after {}", op), fix);
+            }
+        }
+        if let Some(op) = symbol_text(token).filter(|text| self.operators.iter().any(|o| o == text)) {
+            let span = SourceSpan::from_range(token, &ctx.unit.path);
+            if !self.previous_is_space {
+                let fix = Fix::insert(&span.file, span.start_line, span.start_column, " ");
+                ctx.report_fix(span.clone(), format!("Missing space before {}", op), fix);
+            }
+            self.pending = Some((span, op.to_string()));
+        }
+        self.previous_is_space = is_space;
+    }
+
+    fn check_unit(&mut self, _ctx: &mut LintContext) {
+        self.pending = None;
+        self.previous_is_space = true;
+    }
+}
@@ -0,0 +1,110 @@
+use erl_parse::cst::Form;
+use crate::diagnostic::SourceSpan;
+use crate::error::IroncladResult;
+use crate::lint::config::param_usize;
+use crate::lint::{LintContext, Rule};
+use crate::metrics::{FunctionMetrics, ModuleMetrics};
+
+#[derive(Clone, Copy)]
+enum Measure {
+    Function(fn(&FunctionMetrics) -> usize),
+    Module(fn(&ModuleMetrics) -> usize),
+}
+
+/// Reports functions or modules where a metric is above `max`
+pub struct MetricThreshold {
+    id: &'static str,
+    description: &'static str,
+    /// Metric name for the message: "cyclomatic complexity" for functions, "exported functions" for modules
+    what: &'static str,
+    measure: Measure,
+    max: usize,
+}
+
+impl Rule for MetricThreshold {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        self.max = param_usize(params, self.id, "max")?.unwrap_or(self.max);
+        Ok(())
+    }
+
+    fn check_form(&mut self, ctx: &mut LintContext, form: &Form) {
+        let (Measure::Function(measure), Form::FunDecl(decl)) = (self.measure, form) else { return };
+        let Some(metrics) = FunctionMetrics::compute(decl, &ctx.unit.source_text) else { return };
+        let value = measure(&metrics);
+        if value > self.max {
+            ctx.report(decl, format!("Function {} {} is {}, the limit is {}", metrics.function, self.what, value,
+                                     self.max));
+        }
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let Measure::Module(measure) = self.measure else { return };
+        let value = measure(&ModuleMetrics::compute(ctx.unit));
+        if value > self.max {
+            let message = format!("Module {} has {} {}, the limit is {}", ctx.unit.name, value, self.what, self.max);
+            ctx.report_span(SourceSpan::file(&ctx.unit.path), message);
+        }
+    }
+}
+
+pub fn metric_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(MetricThreshold {
+            id: "function_complexity",
+            description: "Function cyclomatic complexity is above the limit (param: max)",
+            what: "cyclomatic complexity",
+            measure: Measure::Function(|f| f.complexity),
+            max: 15,
+        }),
+        Box::new(MetricThreshold {
+            id: "function_nesting",
+            description: "case/if/receive/try nested deeper than the limit (param: max)",
+            what: "nesting depth",
+            measure: Measure::Function(|f| f.nesting),
+            max: 4,
+        }),
+        Box::new(MetricThreshold {
+            id: "function_clauses",
+            description: "Function has more clauses than the limit (param: max)",
+            what: "clause count",
+            measure: Measure::Function(|f| f.clauses),
+            max: 20,
+        }),
+        Box::new(MetricThreshold {
+            id: "function_length",
+            description: "Function has more lines of code than the limit (param: max)",
+            what: "length in lines of code",
+            measure: Measure::Function(|f| f.loc),
+            max: 60,
+        }),
+        Box::new(MetricThreshold {
+            id: "function_arguments",
+            description: "Function takes more arguments than the limit (param: max)",
+            what: "argument count",
+            measure: Measure::Function(|f| f.arguments),
+            max: 6,
+        }),
+        Box::new(MetricThreshold {
+            id: "module_functions",
+            description: "Module defines more functions than the limit (param: max)",
+            what: "functions",
+            measure: Measure::Module(|m| m.functions),
+            max: 80,
+        }),
+        Box::new(MetricThreshold {
+            id: "module_exports",
+            description: "Module exports more functions than the limit (param: max)",
+            what: "exported functions",
+            measure: Measure::Module(|m| m.exports),
+            max: 40,
+        }),
+    ]
+}
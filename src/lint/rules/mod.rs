@@ -0,0 +1,63 @@
+use crate::lint::Rule;
+
+pub mod banned_calls;
+pub mod clauses;
+pub mod comparisons;
+pub mod exceptions;
+pub mod exports;
+pub mod format_strings;
+pub mod guards;
+pub mod layout;
+pub mod metrics;
+pub mod naming;
+pub mod performance;
+pub mod processes;
+pub mod security;
+pub mod specs;
+pub mod variables;
+
+/// Rules which check one module at a time, registered in the order they run
+pub fn builtin_rules() -> Vec<Box<dyn Rule>> {
+    let mut rules: Vec<Box<dyn Rule>> = Vec::new();
+    rules.extend(variables::scope_rules());
+    rules.push(Box::<variables::AnonymousVariable>::default());
+    rules.extend(naming::naming_rules());
+    rules.push(Box::<naming::ModulePrefix>::default());
+    rules.push(Box::<layout::LineLength>::default());
+    rules.push(Box::<layout::TrailingWhitespace>::default());
+    rules.push(Box::<layout::TabIndentation>::default());
+    rules.push(Box::<layout::MissingFinalNewline>::default());
+    rules.push(Box::<layout::MultipleBlankLines>::default());
+    rules.push(Box::<layout::CommaSpacing>::default());
+    rules.push(Box::<layout::OperatorSpacing>::default());
+    rules.extend(metrics::metric_rules());
+    rules.extend(specs::spec_rules());
+    rules.push(Box::<banned_calls::BannedCalls>::default());
+    rules.extend(security::security_rules());
+    rules.push(Box::<security::PathFromArguments>::default());
+    rules.push(Box::<performance::LengthInGuard>::default());
+    rules.push(Box::<performance::SizeCall>::default());
+    rules.push(Box::<performance::ListOpsInLoop>::default());
+    rules.extend(performance::performance_rules());
+    rules.extend(format_strings::format_rules());
+    rules.push(Box::<guards::IllegalGuardCall>::default());
+    rules.push(Box::<guards::GuardUpdate>::default());
+    rules.push(Box::<guards::IllegalPattern>::default());
+    rules.extend(clauses::clause_rules());
+    rules.push(Box::<clauses::SeparatedClauses>::default());
+    rules.extend(exports::export_rules());
+    rules.push(Box::<exports::ExportAll>::default());
+    rules.push(Box::<comparisons::StrictBooleanOperator>::default());
+    rules.push(Box::<comparisons::FloatEquality>::default());
+    rules.push(Box::<comparisons::ConstantComparison>::default());
+    rules.push(Box::<comparisons::RedundantBooleanCase>::default());
+    rules.push(Box::<exceptions::SwallowedException>::default());
+    rules.push(Box::<exceptions::OldStyleCatch>::default());
+    rules.push(Box::<exceptions::TryOfCatchAll>::default());
+    rules.push(Box::<exceptions::GetStacktrace>::default());
+    rules.push(Box::<processes::UnsupervisedSpawn>::default());
+    rules.push(Box::<processes::CallTimeout>::default());
+    rules.push(Box::<processes::ReceiveWithoutAfter>::default());
+    rules.push(Box::<processes::SleepInCallback>::default());
+    rules
+}
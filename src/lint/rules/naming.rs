@@ -0,0 +1,268 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use erl_parse::cst::{Expr, Form, Literal, Pattern};
+use erl_tokenize::PositionRange;
+use regex::Regex;
+use crate::diagnostic::SourceSpan;
+use crate::error::{IroncladError, IroncladResult};
+use crate::lint::config::{param_bool, param_string, param_string_map};
+use crate::lint::{LintContext, Rule};
+
+/// What a name must look like: one of the built-in styles or a regular expression
+#[derive(Debug, Clone)]
+pub enum NameStyle {
+    /// `lower_case_with_underscores`, digits and `@` allowed
+    SnakeCase,
+    /// `CapitalizedWords` without underscores, a leading `_` is allowed for variables
+    CamelCase,
+    /// `UPPER_CASE_WITH_UNDERSCORES`
+    ScreamingSnake,
+    Pattern(Regex),
+}
+
+impl NameStyle {
+    /// Read the `style` or `pattern` parameter of a naming rule
+    fn from_params(params: &toml::Table, rule_id: &str) -> IroncladResult<Option<Self>> {
+        if let Some(pattern) = param_string(params, rule_id, "pattern")? {
+            let regex = Regex::new(&pattern).map_err(|e| {
+                IroncladError::LintConfig(format!("lints.{}.pattern is not a valid regex: {}", rule_id, e))
+            })?;
+            return Ok(Some(NameStyle::Pattern(regex)));
+        }
+        match param_string(params, rule_id, "style")?.as_deref() {
+            None => Ok(None),
+            Some("snake_case") => Ok(Some(NameStyle::SnakeCase)),
+            Some("CamelCase") => Ok(Some(NameStyle::CamelCase)),
+            Some("SCREAMING_SNAKE") => Ok(Some(NameStyle::ScreamingSnake)),
+            Some(other) => Err(IroncladError::LintConfig(format!(
+                "lints.{}.style must be snake_case, CamelCase or SCREAMING_SNAKE, got {}", rule_id, other))),
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let mut chars = name.chars();
+        match self {
+            NameStyle::SnakeCase => {
+                chars.next().is_some_and(|c| c.is_ascii_lowercase())
+                    && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '@')
+            }
+            NameStyle::CamelCase => {
+                let mut chars = name.trim_start_matches('_').chars();
+                chars.next().is_some_and(|c| c.is_ascii_uppercase()) && chars.all(|c| c.is_ascii_alphanumeric())
+            }
+            NameStyle::ScreamingSnake => {
+                chars.next().is_some_and(|c| c.is_ascii_uppercase())
+                    && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            }
+            NameStyle::Pattern(regex) => regex.is_match(name),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            NameStyle::SnakeCase => "snake_case".to_string(),
+            NameStyle::CamelCase => "CamelCase".to_string(),
+            NameStyle::ScreamingSnake => "SCREAMING_SNAKE".to_string(),
+            NameStyle::Pattern(regex) => format!("pattern {}", regex.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NameKind {
+    Module,
+    Function,
+    Variable,
+    Record,
+    RecordField,
+    Macro,
+    Type,
+    Atom,
+}
+
+/// Checks one kind of names against a style, configured with `style = "snake_case"` or `pattern = "^..."`
+pub struct NamingRule {
+    id: &'static str,
+    description: &'static str,
+    what: &'static str,
+    kind: NameKind,
+    style: NameStyle,
+    /// Names already reported in the current module, variables and atoms are reported once
+    seen: HashSet<String>,
+    /// Header locations already reported, headers are seen by every module including them
+    seen_locations: HashSet<(PathBuf, usize)>,
+}
+
+impl NamingRule {
+    fn new(id: &'static str, description: &'static str, what: &'static str, kind: NameKind,
+           style: NameStyle) -> Self {
+        Self { id, description, what, kind, style, seen: HashSet::new(), seen_locations: HashSet::new() }
+    }
+
+    fn check_name<T: PositionRange>(&mut self, ctx: &mut LintContext, node: &T, name: &str) {
+        if self.style.matches(name) {
+            return;
+        }
+        let span = SourceSpan::from_range(node, &ctx.unit.path);
+        if span.file != ctx.unit.path && !self.is_new_project_location(ctx, &span.file, span.start_line) {
+            return;
+        }
+        ctx.report_span(span, format!("{} name {} does not follow {}", self.what, name, self.style.describe()));
+    }
+
+    /// Whether a header location belongs to the project and was not reported for another module yet.
+    /// Names from OTP and dependency headers are not the project's to rename.
+    fn is_new_project_location(&mut self, ctx: &LintContext, file: &Path, line: usize) -> bool {
+        ctx.project.input_dirs().iter().any(|dir| file.starts_with(dir))
+            && self.seen_locations.insert((file.to_path_buf(), line))
+    }
+
+    /// Variables and atoms appear many times, only the first occurrence in a module is reported
+    fn check_name_once<T: PositionRange>(&mut self, ctx: &mut LintContext, node: &T, name: &str) {
+        if name == "_" || !self.seen.insert(name.to_string()) {
+            return;
+        }
+        self.check_name(ctx, node, name);
+    }
+}
+
+impl Rule for NamingRule {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        if let Some(style) = NameStyle::from_params(params, self.id)? {
+            self.style = style;
+        }
+        Ok(())
+    }
+
+    fn check_form(&mut self, ctx: &mut LintContext, form: &Form) {
+        match (self.kind, form) {
+            (NameKind::Module, Form::ModuleAttr(attr)) => {
+                self.check_name(ctx, &attr.module_name, attr.module_name.value());
+            }
+            (NameKind::Function, Form::FunDecl(decl)) => {
+                if let Some(clause) = decl.clauses.iter().next() {
+                    self.check_name(ctx, &clause.name, clause.name.value());
+                }
+            }
+            (NameKind::Record, Form::RecordDecl(decl)) => {
+                self.check_name(ctx, &decl.record_name, decl.record_name.value());
+            }
+            (NameKind::RecordField, Form::RecordDecl(decl)) => {
+                for field in decl.fields.iter() {
+                    self.check_name(ctx, &field.field_name, field.field_name.value());
+                }
+            }
+            (NameKind::Type, Form::TypeDecl(decl)) => {
+                self.check_name(ctx, &decl.type_name, decl.type_name.value());
+            }
+            _ => {}
+        }
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        match (self.kind, expr) {
+            (NameKind::Variable, Expr::Var(var)) => self.check_name_once(ctx, var, var.value()),
+            // Quoted atoms like 'EXIT' are written that way on purpose
+            (NameKind::Atom, Expr::Literal(Literal::Atom(atom))) if !atom.text().starts_with('\'') => {
+                self.check_name_once(ctx, atom, atom.value());
+            }
+            _ => {}
+        }
+    }
+
+    fn check_pattern(&mut self, ctx: &mut LintContext, pattern: &Pattern) {
+        match (self.kind, pattern) {
+            (NameKind::Variable, Pattern::Var(var)) => self.check_name_once(ctx, var, var.value()),
+            (NameKind::Atom, Pattern::Literal(Literal::Atom(atom))) if !atom.text().starts_with('\'') => {
+                self.check_name_once(ctx, atom, atom.value());
+            }
+            _ => {}
+        }
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        self.seen.clear();
+        if self.kind != NameKind::Macro {
+            return;
+        }
+        let unit = ctx.unit;
+        for def in unit.pp_info.macro_defs.iter() {
+            if self.style.matches(&def.name) || !self.is_new_project_location(ctx, &def.file, def.line) {
+                continue;
+            }
+            ctx.report_span(SourceSpan::line(&def.file, def.line),
+                            format!("Macro name {} does not follow {}", def.name, self.style.describe()));
+        }
+    }
+}
+
+pub fn naming_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(NamingRule::new("module_naming", "Module names follow a style (params: style, pattern)",
+                                 "Module", NameKind::Module, NameStyle::SnakeCase)),
+        Box::new(NamingRule::new("function_naming", "Function names follow a style (params: style, pattern)",
+                                 "Function", NameKind::Function, NameStyle::SnakeCase)),
+        Box::new(NamingRule::new("variable_naming", "Variable names follow a style (params: style, pattern)",
+                                 "Variable", NameKind::Variable, NameStyle::CamelCase)),
+        Box::new(NamingRule::new("record_naming", "Record names follow a style (params: style, pattern)",
+                                 "Record", NameKind::Record, NameStyle::SnakeCase)),
+        Box::new(NamingRule::new("record_field_naming",
+                                 "Record field names follow a style (params: style, pattern)",
+                                 "Record field", NameKind::RecordField, NameStyle::SnakeCase)),
+        Box::new(NamingRule::new("macro_naming", "Macro names follow a style (params: style, pattern)",
+                                 "Macro", NameKind::Macro, NameStyle::ScreamingSnake)),
+        Box::new(NamingRule::new("type_naming", "Type names follow a style (params: style, pattern)",
+                                 "Type", NameKind::Type, NameStyle::SnakeCase)),
+        Box::new(NamingRule::new("atom_naming", "Unquoted atoms follow a style (params: style, pattern)",
+                                 "Atom", NameKind::Atom, NameStyle::SnakeCase)),
+    ]
+}
+
+/// Modules of an OTP application must start with a prefix, `<app>_` unless configured otherwise
+#[derive(Default)]
+pub struct ModulePrefix {
+    /// Application name to the required prefix
+    prefixes: HashMap<String, String>,
+    /// Require `<app>_` for applications not listed in `prefixes`
+    all_apps: bool,
+}
+
+impl Rule for ModulePrefix {
+    fn id(&self) -> &'static str {
+        "module_prefix"
+    }
+
+    fn description(&self) -> &'static str {
+        "Module names start with the prefix of their OTP application (params: prefixes, all_apps)"
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        self.prefixes = param_string_map(params, self.id(), "prefixes")?.unwrap_or_default();
+        self.all_apps = param_bool(params, self.id(), "all_apps")?.unwrap_or(self.all_apps);
+        Ok(())
+    }
+
+    fn check_form(&mut self, ctx: &mut LintContext, form: &Form) {
+        let Form::ModuleAttr(attr) = form else { return };
+        let Some(app) = ctx.unit.otp_app() else { return };
+        let prefix = match self.prefixes.get(&app) {
+            Some(prefix) => prefix.clone(),
+            None if self.all_apps => format!("{}_", app),
+            None => return,
+        };
+        let name = attr.module_name.value();
+        // The application module itself is usually named after the application
+        if name != app && !name.starts_with(&prefix) {
+            ctx.report(&attr.module_name,
+                       format!("Module {} of application {} must start with {}", name, app, prefix));
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::HashSet;
+use erl_parse::cst::{Expr, Form, GuardTest, Literal, Pattern};
+use crate::diagnostic::{Fix, SourceSpan};
+use crate::lint::{LintContext, Rule};
+use crate::syntax;
+use crate::syntax::mfa::MFArity;
+use crate::syntax::walk::{walk_fun_clause, walk_pattern, Visitor};
+
+/// Higher order `lists` functions whose fun argument runs once per element
+const LIST_ITERATORS: &[&str] = &[
+    "map", "foreach", "foldl", "foldr", "filter", "filtermap", "flatmap", "mapfoldl", "mapfoldr", "any", "all",
+    "takewhile", "dropwhile", "partition", "zipwith",
+];
+
+/// Calls which walk or copy a whole list and become quadratic when repeated per element
+const LINEAR_LIST_CALLS: &[(&str, usize, &str)] = &[
+    ("append", 2, "prepend to an accumulator and reverse it once at the end"),
+    ("flatten", 1, "build an iolist or a deep list and flatten it once"),
+    ("flatten", 2, "build an iolist or a deep list and flatten it once"),
+    ("nth", 2, "walk the list with pattern matching, or use a tuple or map for indexed access"),
+];
+
+/// The list argument of `length(L)` compared to 0 or 1 in a way which only tests for emptiness, with true
+/// for "is empty" and false for "is not empty"
+fn length_emptiness_test(test: &GuardTest) -> Option<(&GuardTest, bool)> {
+    let GuardTest::BinaryOpCall(call) = test else { return None };
+    let GuardTest::LocalCall(length) = &call.left else { return None };
+    if syntax::guard_atom(&length.func) != Some("length") {
+        return None;
+    }
+    let mut args = length.args.iter();
+    let (Some(list), None) = (args.next(), args.next()) else { return None };
+    let GuardTest::Literal(Literal::Integer(value)) = &call.right else { return None };
+    match (syntax::binary_op_text(&call.op), value.text()) {
+        ("==" | "=:=" | "=<", "0") | ("<", "1") => Some((list, true)),
+        (">" | "/=" | "=/=", "0") | (">=", "1") => Some((list, false)),
+        _ => None,
+    }
+}
+
+/// `length(L) == 0` or `length(L) > 0` in a guard walks the whole list to test whether it is empty.
+/// Only the "is empty" tests are fixed, to `L =:= []`. `L =/= []` would also be true for terms which are not
+/// lists, where `length(L) > 0` fails the guard.
+#[derive(Default)]
+pub struct LengthInGuard;
+
+impl Rule for LengthInGuard {
+    fn id(&self) -> &'static str {
+        "length_in_guard"
+    }
+
+    fn description(&self) -> &'static str {
+        "length/1 compared with 0 in a guard to test for an empty list"
+    }
+
+    fn check_guard_test(&mut self, ctx: &mut LintContext, test: &GuardTest) {
+        let Some((list, is_empty)) = length_emptiness_test(test) else { return };
+        let span = SourceSpan::from_range(test, &ctx.unit.path);
+        let message = if is_empty {
+            "length/1 walks the whole list only to test whether it is empty. Compare with [] instead"
+        } else {
+            "length/1 walks the whole list only to test whether it is not empty. Match [_ | _] instead"
+        };
+        let list_text = ctx.source(&SourceSpan::from_range(list, &ctx.unit.path))
+            .filter(|_| is_empty && span.file == ctx.unit.path);
+        match list_text {
+            Some(list_text) => {
+                let replacement = format!("{} =:= []", list_text);
+                ctx.report_fix(span.clone(), message.to_string(), Fix::replace(span, &replacement));
+            }
+            None => ctx.report_span(span, message.to_string()),
+        }
+    }
+}
+
+/// A performance check of one call or expression
+pub struct PerformanceRule {
+    id: &'static str,
+    description: &'static str,
+    check: fn(&mut LintContext, &Expr),
+}
+
+impl Rule for PerformanceRule {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        (self.check)(ctx, expr);
+    }
+}
+
+fn string_concat(ctx: &mut LintContext, expr: &Expr) {
+    let Some((callee, _)) = ctx.resolve_call(expr) else { return };
+    if callee.module == "string" && callee.name == "concat" && callee.arity == 2 {
+        ctx.report(expr, "string:concat/2 copies its first argument on every call, which adds up for long or \
+                          repeatedly extended strings. Build an iolist ([A, B]) or use binaries".to_string());
+    }
+}
+
+fn double_reverse(ctx: &mut LintContext, expr: &Expr) {
+    let is_reverse = |callee: &MFArity| callee.module == "lists" && callee.name == "reverse" && callee.arity == 1;
+    let Some((outer, args)) = ctx.resolve_call(expr) else { return };
+    if !is_reverse(&outer) {
+        return;
+    }
+    let Some((inner, inner_args)) = args.first().and_then(|arg| ctx.resolve_call(syntax::unparenthesize(arg)))
+        else { return };
+    if !is_reverse(&inner) {
+        return;
+    }
+    let span = SourceSpan::from_range(expr, &ctx.unit.path);
+    let message = "lists:reverse of lists:reverse copies the list twice and returns it unchanged".to_string();
+    match ctx.source(&SourceSpan::from_range(inner_args[0], &ctx.unit.path)).filter(|_| span.file == ctx.unit.path) {
+        Some(list_text) => ctx.report_fix(span.clone(), message, Fix::replace(span, list_text)),
+        None => ctx.report_span(span, message),
+    }
+}
+
+pub fn performance_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(PerformanceRule {
+            id: "string_concat",
+            description: "string:concat/2, which copies its first argument",
+            check: string_concat,
+        }),
+        Box::new(PerformanceRule {
+            id: "double_reverse",
+            description: "lists:reverse(lists:reverse(L))",
+            check: double_reverse,
+        }),
+    ]
+}
+
+const SIZE_MESSAGE: &str = "size/1 accepts both tuples and binaries, so the compiler and dialyzer can not tell \
+                            which one is meant. Use byte_size/1 or tuple_size/1";
+
+/// `size/1` in expressions and guards
+#[derive(Default)]
+pub struct SizeCall;
+
+impl Rule for SizeCall {
+    fn id(&self) -> &'static str {
+        "size_call"
+    }
+
+    fn description(&self) -> &'static str {
+        "size/1 instead of byte_size/1 or tuple_size/1"
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        let Some((callee, _)) = ctx.resolve_call(expr) else { return };
+        if callee.module == "erlang" && callee.name == "size" && callee.arity == 1 {
+            ctx.report(expr, SIZE_MESSAGE.to_string());
+        }
+    }
+
+    fn check_guard_test(&mut self, ctx: &mut LintContext, test: &GuardTest) {
+        let GuardTest::LocalCall(call) = test else { return };
+        if syntax::guard_atom(&call.func) == Some("size") && call.args.iter().count() == 1 {
+            ctx.report(test, SIZE_MESSAGE.to_string());
+        }
+    }
+}
+
+/// List operations repeated once per element: `Acc ++ [X]` in a recursive function, and `lists:append/2`,
+/// `lists:flatten` or `lists:nth/2` in a recursive function, a list comprehension or a fun passed to
+/// `lists:map` and alike. Each of them is linear, so the loop becomes quadratic.
+#[derive(Default)]
+pub struct ListOpsInLoop;
+
+impl Rule for ListOpsInLoop {
+    fn id(&self) -> &'static str {
+        "list_ops_in_loop"
+    }
+
+    fn description(&self) -> &'static str {
+        "Appending to an accumulator, lists:append, lists:flatten or lists:nth repeated in a loop"
+    }
+
+    fn check_form(&mut self, ctx: &mut LintContext, form: &Form) {
+        let Form::FunDecl(decl) = form else { return };
+        let Some(fun_arity) = syntax::fun_decl_name(decl) else { return };
+        let mut scan = LoopScan {
+            ctx: &*ctx,
+            this: MFArity::new(&ctx.unit.name, &fun_arity.name, fun_arity.arity),
+            recursive: false,
+            loops: Vec::new(),
+            list_calls: Vec::new(),
+            appends: Vec::new(),
+        };
+        let mut arguments = VarNames::default();
+        for clause in decl.clauses.iter() {
+            clause.patterns.iter().for_each(|p| walk_pattern(&mut arguments, p));
+            walk_fun_clause(&mut scan, clause);
+        }
+        let LoopScan { recursive, loops, list_calls, appends, .. } = scan;
+
+        for (span, callee, advice) in list_calls {
+            if recursive || loops.iter().any(|outer| contains(outer, &span)) {
+                ctx.report_span(span, format!("{} inside a loop walks the list on every iteration; {}",
+                                              callee, advice));
+            }
+        }
+        if !recursive {
+            return;
+        }
+        for (span, var) in appends {
+            if arguments.names.contains(&var) {
+                ctx.report_span(span, format!("{} ++ ... in a recursive function copies the accumulator on every \
+                                               call. Prepend with [X | {}] and reverse once at the end", var, var));
+            }
+        }
+    }
+}
+
+fn contains(outer: &SourceSpan, inner: &SourceSpan) -> bool {
+    outer.file == inner.file
+        && (outer.start_line, outer.start_column) <= (inner.start_line, inner.start_column)
+        && (inner.end_line, inner.end_column) <= (outer.end_line, outer.end_column)
+}
+
+struct LoopScan<'c, 'u> {
+    ctx: &'c LintContext<'u>,
+    this: MFArity,
+    /// The function calls itself
+    recursive: bool,
+    /// List comprehensions and funs passed to `lists` iterators
+    loops: Vec<SourceSpan>,
+    list_calls: Vec<(SourceSpan, MFArity, &'static str)>,
+    /// `Var ++ ...` with the variable name
+    appends: Vec<(SourceSpan, String)>,
+}
+
+impl<'c, 'u> Visitor for LoopScan<'c, 'u> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        let path = &self.ctx.unit.path;
+        match expr {
+            Expr::ListComprehension(_) | Expr::BitsComprehension(_) => {
+                self.loops.push(SourceSpan::from_range(expr, path));
+            }
+            Expr::BinaryOpCall(call) if syntax::binary_op_text(&call.op) == "++" => {
+                if let Expr::Var(var) = syntax::unparenthesize(&call.left) {
+                    self.appends.push((SourceSpan::from_range(expr, path), var.value().to_string()));
+                }
+            }
+            _ => {}
+        }
+        let Some((callee, args)) = self.ctx.resolve_call(expr) else { return };
+        if callee == self.this {
+            self.recursive = true;
+        }
+        if callee.module != "lists" {
+            return;
+        }
+        if LIST_ITERATORS.contains(&callee.name.as_str()) {
+            let funs = args.iter().filter(|arg| matches!(syntax::unparenthesize(arg), Expr::AnonymousFun(_)));
+            self.loops.extend(funs.map(|fun| SourceSpan::from_range(*fun, path)));
+        }
+        let found = LINEAR_LIST_CALLS.iter().find(|(name, arity, _)| *name == callee.name && *arity == callee.arity);
+        if let Some((_, _, advice)) = found {
+            self.list_calls.push((SourceSpan::from_range(expr, path), callee, advice));
+        }
+    }
+}
+
+/// Names of the variables bound in function heads, except `_`
+#[derive(Default)]
+struct VarNames {
+    names: HashSet<String>,
+}
+
+impl Visitor for VarNames {
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        if let Pattern::Var(var) = pattern {
+            if var.value() != "_" {
+                self.names.insert(var.value().to_string());
+            }
+        }
+    }
+}
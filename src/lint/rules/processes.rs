@@ -0,0 +1,233 @@
+use std::collections::{HashMap, HashSet};
+use erl_parse::cst::forms::FunDecl;
+use erl_parse::cst::{Expr, Form};
+use erl_tokenize::PositionRange;
+use glob::Pattern;
+use crate::callgraph::{collect_calls, CallEdge};
+use crate::error::{IroncladError, IroncladResult};
+use crate::lint::config::param_string_list;
+use crate::lint::{LintContext, Rule};
+use crate::syntax;
+use crate::syntax::mfa::{FunArity, MFArity};
+use crate::syntax::walk::{walk_fun_clause, Visitor};
+
+fn is_erlang_call(callee: &MFArity, names: &[&str]) -> bool {
+    callee.module == "erlang" && names.contains(&callee.name.as_str())
+}
+
+/// Calls grouped by the function making them
+fn calls_by_caller(edges: Vec<CallEdge>) -> HashMap<MFArity, Vec<CallEdge>> {
+    let mut result: HashMap<MFArity, Vec<CallEdge>> = HashMap::new();
+    for edge in edges {
+        result.entry(edge.caller.clone()).or_default().push(edge);
+    }
+    result
+}
+
+/// `spawn/1,2,3,4` in a function which neither links nor monitors: when the process dies nobody notices, and
+/// when the caller dies the process lives on. Supervisors are not checked, they manage processes themselves.
+#[derive(Default)]
+pub struct UnsupervisedSpawn;
+
+impl Rule for UnsupervisedSpawn {
+    fn id(&self) -> &'static str {
+        "unsupervised_spawn"
+    }
+
+    fn description(&self) -> &'static str {
+        "spawn without link or monitor outside of supervisors"
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let unit = ctx.unit;
+        if syntax::implemented_behaviours(&unit.forms).contains(&"supervisor") {
+            return;
+        }
+        let mut callers: Vec<(MFArity, Vec<CallEdge>)> = calls_by_caller(collect_calls(unit)).into_iter().collect();
+        callers.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, edges) in callers {
+            if edges.iter().any(|e| is_erlang_call(&e.callee, &["link", "monitor"])) {
+                continue;
+            }
+            for edge in edges.into_iter().filter(|e| is_erlang_call(&e.callee, &["spawn"])) {
+                ctx.report_span(edge.span, format!("{} starts a process nobody links to or monitors, so its crash \
+                                                    goes unnoticed. Use spawn_link, spawn_monitor or start it \
+                                                    under a supervisor", edge.callee));
+            }
+        }
+    }
+}
+
+/// `gen_server:call/2` with its implicit 5 second timeout, `gen_statem:call/2` which waits forever by default,
+/// and calls with an `infinity` timeout, in the modules matching the `modules` glob patterns (all by default)
+pub struct CallTimeout {
+    modules: Vec<Pattern>,
+}
+
+impl Default for CallTimeout {
+    fn default() -> Self {
+        Self { modules: vec![Pattern::new("*").unwrap()] }
+    }
+}
+
+impl Rule for CallTimeout {
+    fn id(&self) -> &'static str {
+        "call_timeout"
+    }
+
+    fn description(&self) -> &'static str {
+        "gen_server/gen_statem call with the default or an infinity timeout (param: modules, default [\"*\"])"
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        let rule_id = self.id();
+        if let Some(modules) = param_string_list(params, rule_id, "modules")? {
+            self.modules = modules.iter()
+                .map(|p| Pattern::new(p).map_err(|e| {
+                    IroncladError::LintConfig(format!("lints.{}.modules: bad pattern '{}': {}", rule_id, p, e))
+                }))
+                .collect::<IroncladResult<_>>()?;
+        }
+        Ok(())
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        let Some((callee, args)) = ctx.resolve_call(expr) else { return };
+        if !matches!(callee.module.as_str(), "gen_server" | "gen_statem") || callee.name != "call" {
+            return;
+        }
+        let message = match args.get(2) {
+            None if callee.module == "gen_statem" => {
+                format!("{} waits forever by default and blocks the caller if the state machine hangs. Pass a \
+                         finite timeout", callee)
+            }
+            None => format!("{} waits 5 seconds by default and then exits the caller. Pass a timeout chosen for \
+                             this call", callee),
+            Some(timeout) if syntax::expr_atom(timeout) == Some("infinity") => {
+                format!("{} with an infinity timeout blocks forever if the server hangs. Pass a finite timeout",
+                        callee)
+            }
+            Some(_) => return,
+        };
+        if self.modules.iter().any(|p| p.matches(&ctx.unit.name)) {
+            ctx.report(expr, message);
+        }
+    }
+}
+
+/// Whether a function calls itself, which makes a `receive` at the top of its body a server loop
+fn is_recursive(ctx: &LintContext, decl: &FunDecl) -> bool {
+    let Some(fun_arity) = syntax::fun_decl_name(decl) else { return false };
+    let this = MFArity::new(&ctx.unit.name, &fun_arity.name, fun_arity.arity);
+    let mut finder = SelfCall { ctx, this, found: false };
+    decl.clauses.iter().for_each(|clause| walk_fun_clause(&mut finder, clause));
+    finder.found
+}
+
+struct SelfCall<'c, 'u> {
+    ctx: &'c LintContext<'u>,
+    this: MFArity,
+    found: bool,
+}
+
+impl<'c, 'u> Visitor for SelfCall<'c, 'u> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if self.ctx.resolve_call(expr).is_some_and(|(callee, _)| callee == self.this) {
+            self.found = true;
+        }
+    }
+}
+
+/// `receive` without `after` anywhere but at the top of a recursive loop function. A reply which never comes
+/// blocks the process forever.
+#[derive(Default)]
+pub struct ReceiveWithoutAfter;
+
+impl Rule for ReceiveWithoutAfter {
+    fn id(&self) -> &'static str {
+        "receive_without_after"
+    }
+
+    fn description(&self) -> &'static str {
+        "receive without an after timeout outside of a process main loop"
+    }
+
+    fn check_form(&mut self, ctx: &mut LintContext, form: &Form) {
+        let Form::FunDecl(decl) = form else { return };
+        // Receives which are a whole clause body expression of a loop function
+        let loop_receives: HashSet<usize> = if is_recursive(ctx, decl) {
+            decl.clauses.iter()
+                .flat_map(|clause| clause.body.exprs.iter())
+                .filter(|expr| matches!(expr, Expr::Receive(_)))
+                .map(|expr| expr.start_position().offset())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let mut finder = ReceiveFinder { ctx: &mut *ctx, loop_receives: &loop_receives };
+        decl.clauses.iter().for_each(|clause| walk_fun_clause(&mut finder, clause));
+    }
+}
+
+struct ReceiveFinder<'c, 'u> {
+    ctx: &'c mut LintContext<'u>,
+    loop_receives: &'c HashSet<usize>,
+}
+
+impl<'c, 'u> Visitor for ReceiveFinder<'c, 'u> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        let Expr::Receive(receive) = expr else { return };
+        if receive.timeout.is_none() && !self.loop_receives.contains(&expr.start_position().offset()) {
+            self.ctx.report(expr, "receive without after waits forever if the message never arrives. Add an after \
+                                   clause with a timeout".to_string());
+        }
+    }
+}
+
+/// Callbacks of `gen_server` and `gen_statem` which run inside the server process
+const SERVER_CALLBACKS: &[&str] = &[
+    "init", "handle_call", "handle_cast", "handle_info", "handle_continue", "handle_event", "terminate",
+    "code_change", "format_status",
+];
+
+/// `timer:sleep/1` in a `gen_server` or `gen_statem` callback blocks the server: calls time out and system
+/// messages wait. State functions of a `gen_statem` count as callbacks too.
+#[derive(Default)]
+pub struct SleepInCallback;
+
+impl Rule for SleepInCallback {
+    fn id(&self) -> &'static str {
+        "sleep_in_callback"
+    }
+
+    fn description(&self) -> &'static str {
+        "timer:sleep in a gen_server or gen_statem callback"
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let unit = ctx.unit;
+        let behaviours = syntax::implemented_behaviours(&unit.forms);
+        let is_statem = behaviours.contains(&"gen_statem");
+        if !is_statem && !behaviours.contains(&"gen_server") {
+            return;
+        }
+        let exported: HashSet<FunArity> = if syntax::has_export_all(&unit.forms) {
+            syntax::defined_functions(&unit.forms)
+        } else {
+            syntax::exported_functions(&unit.forms)
+        };
+        let is_callback = |caller: &MFArity| {
+            let fun_arity = FunArity::new(&caller.name, caller.arity);
+            exported.contains(&fun_arity)
+                && (SERVER_CALLBACKS.contains(&caller.name.as_str()) || (is_statem && caller.arity == 3))
+        };
+        for edge in collect_calls(unit) {
+            let callee = &edge.callee;
+            if callee.module == "timer" && callee.name == "sleep" && is_callback(&edge.caller) {
+                ctx.report_span(edge.span, format!("timer:sleep in callback {}/{} blocks the server process, \
+                                                    use erlang:send_after/3 or a state timeout instead",
+                                                   edge.caller.name, edge.caller.arity));
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashSet;
+use erl_parse::cst::{Expr, Form, Pattern};
+use crate::diagnostic::Severity;
+use crate::lint::{LintContext, Rule};
+use crate::syntax;
+use crate::syntax::mfa::MFArity;
+use crate::syntax::walk::{walk_expr, walk_fun_clause, walk_pattern, Visitor};
+
+/// Calls which assemble a string or binary from parts
+const STRING_BUILDERS: &[(&str, &str)] = &[
+    ("lists", "concat"), ("lists", "append"), ("lists", "flatten"), ("io_lib", "format"), ("string", "join"),
+    ("filename", "join"), ("erlang", "iolist_to_binary"), ("unicode", "characters_to_list"),
+    ("unicode", "characters_to_binary"),
+];
+
+/// `file` functions whose first argument is a path
+const FILE_PATH_FUNCTIONS: &[&str] = &[
+    "read_file", "write_file", "open", "delete", "del_dir", "del_dir_r", "make_dir", "list_dir", "consult",
+    "path_consult", "eval", "script", "rename", "copy", "read_file_info", "read_link", "make_symlink",
+    "set_cwd",
+];
+
+fn is_literal(expr: &Expr) -> bool {
+    syntax::literal_text(expr).is_some()
+}
+
+/// Whether a string argument is assembled at runtime: `++`, a binary with variable parts, `io_lib:format`
+/// and alike
+fn is_built_string(ctx: &LintContext, expr: &Expr) -> bool {
+    match syntax::unparenthesize(expr) {
+        Expr::BinaryOpCall(call) => syntax::binary_op_text(&call.op) == "++",
+        Expr::Bits(bits) => bits.iter().any(|elem| !is_literal(&elem.element)),
+        other => ctx.resolve_call(other).is_some_and(|(callee, _)| {
+            STRING_BUILDERS.contains(&(callee.module.as_str(), callee.name.as_str()))
+        }),
+    }
+}
+
+fn is_call(callee: &MFArity, module: &str, name: &str) -> bool {
+    callee.module == module && callee.name == name
+}
+
+/// A security check of one call or expression, the message explains the risk and what to use instead
+pub struct SecurityRule {
+    id: &'static str,
+    description: &'static str,
+    severity: Severity,
+    check: fn(&mut LintContext, &Expr),
+}
+
+impl Rule for SecurityRule {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn default_severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_expr(&mut self, ctx: &mut LintContext, expr: &Expr) {
+        (self.check)(ctx, expr);
+    }
+}
+
+fn atom_from_data(ctx: &mut LintContext, expr: &Expr) {
+    let Some((callee, args)) = ctx.resolve_call(expr) else { return };
+    let safe = match (callee.module.as_str(), callee.name.as_str()) {
+        ("erlang", "list_to_atom") => "list_to_existing_atom/1",
+        ("erlang", "binary_to_atom") => "binary_to_existing_atom/1,2",
+        _ => return,
+    };
+    if args.first().is_some_and(|arg| !is_literal(arg)) {
+        ctx.report(expr, format!("{} on runtime data can exhaust the atom table, which is never garbage collected \
+                                  and crashes the node when full. Use {} or keep the value as a string",
+                                 callee, safe));
+    }
+}
+
+fn unsafe_binary_to_term(ctx: &mut LintContext, expr: &Expr) {
+    let Some((callee, args)) = ctx.resolve_call(expr) else { return };
+    if !is_call(&callee, "erlang", "binary_to_term") {
+        return;
+    }
+    let has_safe = match args.get(1) {
+        None => false,
+        Some(options) => match syntax::unparenthesize(options) {
+            Expr::List(list) => syntax::list_elements(list).any(|o| syntax::expr_atom(o) == Some("safe")),
+            // Options built at runtime, can not tell
+            _ => true,
+        },
+    };
+    if !has_safe {
+        ctx.report(expr, "binary_to_term without the [safe] option creates new atoms and funs from external data, \
+                          which can exhaust the atom table or run unexpected code. Use binary_to_term(Bin, [safe])"
+            .to_string());
+    }
+}
+
+fn command_injection(ctx: &mut LintContext, expr: &Expr) {
+    let Some((callee, args)) = ctx.resolve_call(expr) else { return };
+    let command = if is_call(&callee, "os", "cmd") {
+        args.first().copied()
+    } else if is_call(&callee, "erlang", "open_port") {
+        args.first()
+            .and_then(|name| syntax::tuple_elements(name))
+            .filter(|elements| elements.len() == 2 && syntax::expr_atom(elements[0]) == Some("spawn"))
+            .map(|elements| elements[1])
+    } else {
+        None
+    };
+    if command.is_some_and(|command| is_built_string(ctx, command)) {
+        ctx.report(expr, format!("{} runs a shell command assembled from parts, any of which can inject shell \
+                                  syntax. Use open_port({{spawn_executable, Path}}, [{{args, Args}}]) with a fixed \
+                                  executable and a list of arguments", callee));
+    }
+}
+
+fn ssl_verify_none(ctx: &mut LintContext, expr: &Expr) {
+    let Some(elements) = syntax::tuple_elements(expr) else { return };
+    if elements.len() == 2 && syntax::expr_atom(elements[0]) == Some("verify")
+        && syntax::expr_atom(elements[1]) == Some("verify_none") {
+        ctx.report(expr, "{verify, verify_none} accepts any certificate, so the connection is open to \
+                          man-in-the-middle attacks. Use {verify, verify_peer} with cacerts or cacertfile"
+            .to_string());
+    }
+}
+
+fn weak_hash(ctx: &mut LintContext, expr: &Expr) {
+    let Some((callee, args)) = ctx.resolve_call(expr) else { return };
+    let algorithm = match (callee.module.as_str(), callee.name.as_str()) {
+        ("crypto", "hash") | ("crypto", "hash_init") => args.first().and_then(|a| syntax::expr_atom(a)),
+        ("erlang", "md5") | ("erlang", "md5_init") => Some("md5"),
+        _ => None,
+    };
+    if let Some(algorithm @ ("md5" | "sha" | "md4")) = algorithm {
+        ctx.report(expr, format!("{} is broken for security purposes, collisions can be computed. Use sha256 or \
+                                  stronger; for passwords use a slow KDF like crypto:pbkdf2_hmac/5", algorithm));
+    }
+}
+
+pub fn security_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(SecurityRule {
+            id: "atom_from_data",
+            description: "list_to_atom/binary_to_atom on runtime data can exhaust the atom table",
+            severity: Severity::Warning,
+            check: atom_from_data,
+        }),
+        Box::new(SecurityRule {
+            id: "unsafe_binary_to_term",
+            description: "binary_to_term without the [safe] option",
+            severity: Severity::Warning,
+            check: unsafe_binary_to_term,
+        }),
+        Box::new(SecurityRule {
+            id: "command_injection",
+            description: "os:cmd or open_port({spawn, ...}) with a command assembled at runtime",
+            severity: Severity::Error,
+            check: command_injection,
+        }),
+        Box::new(SecurityRule {
+            id: "ssl_verify_none",
+            description: "TLS options disable certificate verification",
+            severity: Severity::Error,
+            check: ssl_verify_none,
+        }),
+        Box::new(SecurityRule {
+            id: "weak_hash",
+            description: "md5 or sha1 hashing",
+            severity: Severity::Warning,
+            check: weak_hash,
+        }),
+    ]
+}
+
+/// `file` functions called with a path assembled from the arguments of the enclosing function, which may
+/// come from a user and contain `../`
+#[derive(Default)]
+pub struct PathFromArguments;
+
+impl Rule for PathFromArguments {
+    fn id(&self) -> &'static str {
+        "path_from_arguments"
+    }
+
+    fn description(&self) -> &'static str {
+        "file functions called with a path built from function arguments"
+    }
+
+    fn check_form(&mut self, ctx: &mut LintContext, form: &Form) {
+        let Form::FunDecl(decl) = form else { return };
+        for clause in decl.clauses.iter() {
+            let mut arguments = VarNames::default();
+            clause.patterns.iter().for_each(|p| walk_pattern(&mut arguments, p));
+            if arguments.names.is_empty() {
+                continue;
+            }
+            walk_fun_clause(&mut FileCallFinder { ctx: &mut *ctx, arguments: &arguments.names }, clause);
+        }
+    }
+}
+
+struct FileCallFinder<'c, 'u> {
+    ctx: &'c mut LintContext<'u>,
+    arguments: &'c HashSet<String>,
+}
+
+impl<'c, 'u> Visitor for FileCallFinder<'c, 'u> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        let Some((callee, args)) = self.ctx.resolve_call(expr) else { return };
+        if callee.module != "file" || !FILE_PATH_FUNCTIONS.contains(&callee.name.as_str()) {
+            return;
+        }
+        let Some(path) = args.first() else { return };
+        if !is_built_string(self.ctx, path) {
+            return;
+        }
+        let mut used = VarNames::default();
+        walk_expr(&mut used, path);
+        let mut from_arguments: Vec<&String> = used.names.intersection(self.arguments).collect();
+        if from_arguments.is_empty() {
+            return;
+        }
+        from_arguments.sort();
+        let names: Vec<&str> = from_arguments.iter().map(|s| s.as_str()).collect();
+        self.ctx.report(expr, format!("{} is called with a path built from argument {}; a value like \"../\" \
+                                       escapes the intended directory. Check it with filelib:safe_relative_path/2 \
+                                       before use", callee, names.join(", ")));
+    }
+}
+
+/// Names of all variables in the visited patterns or expressions, except `_`
+#[derive(Default)]
+struct VarNames {
+    names: HashSet<String>,
+}
+
+impl Visitor for VarNames {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let Expr::Var(var) = expr {
+            self.names.insert(var.value().to_string());
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        if let Pattern::Var(var) = pattern {
+            if var.value() != "_" {
+                self.names.insert(var.value().to_string());
+            }
+        }
+    }
+}
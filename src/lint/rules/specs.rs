@@ -0,0 +1,180 @@
+use std::collections::{HashMap, HashSet};
+use erl_parse::cst::forms::{FunDecl, FunSpec};
+use erl_parse::cst::{Form, Literal, Pattern, Type};
+use crate::diagnostic::Severity;
+use crate::lint::{LintContext, Rule};
+use crate::syntax;
+use crate::syntax::mfa::FunArity;
+
+/// One of the spec checks, all of them compare the `-spec` forms of a module with its functions
+pub struct SpecRule {
+    id: &'static str,
+    description: &'static str,
+    severity: Severity,
+    check: fn(&mut LintContext, &ModuleSpecs),
+}
+
+impl Rule for SpecRule {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn default_severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_unit(&mut self, ctx: &mut LintContext) {
+        let specs = ModuleSpecs::collect(&ctx.unit.forms, &ctx.unit.name);
+        (self.check)(ctx, &specs);
+    }
+}
+
+/// Specs and functions of a module in source order
+struct ModuleSpecs<'a> {
+    specs: Vec<(FunArity, &'a FunSpec)>,
+    functions: HashMap<FunArity, &'a FunDecl>,
+    exported: Vec<FunArity>,
+}
+
+impl<'a> ModuleSpecs<'a> {
+    fn collect(forms: &'a [Form], module: &str) -> Self {
+        let mut specs = Vec::new();
+        let mut functions = HashMap::new();
+        for form in forms {
+            match form {
+                Form::FunSpec(spec) => {
+                    if let Some(fun) = syntax::fun_spec_name(spec, module) {
+                        specs.push((fun, spec));
+                    }
+                }
+                Form::FunDecl(decl) => {
+                    if let Some(fun) = syntax::fun_decl_name(decl) {
+                        functions.entry(fun).or_insert(decl);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut exported: Vec<FunArity> = syntax::exported_functions(forms).into_iter().collect();
+        exported.sort();
+        Self { specs, functions, exported }
+    }
+
+    fn has_spec(&self, fun: &FunArity) -> bool {
+        self.specs.iter().any(|(f, _)| f == fun)
+    }
+}
+
+/// Exported functions without a spec. Modules with `export_all` only count the explicit exports.
+fn missing_spec(ctx: &mut LintContext, specs: &ModuleSpecs) {
+    for fun in specs.exported.iter().filter(|f| !specs.has_spec(f)) {
+        if let Some(decl) = specs.functions.get(fun) {
+            ctx.report(*decl, format!("Exported function {} has no -spec", fun));
+        }
+    }
+}
+
+fn spec_without_function(ctx: &mut LintContext, specs: &ModuleSpecs) {
+    for (fun, spec) in specs.specs.iter().filter(|(f, _)| !specs.functions.contains_key(f)) {
+        let mut other_arities: Vec<String> = specs.functions.keys()
+            .filter(|f| f.name == fun.name)
+            .map(|f| f.to_string())
+            .collect();
+        other_arities.sort();
+        let hint = if other_arities.is_empty() {
+            String::new()
+        } else {
+            format!(", the module defines {}", other_arities.join(", "))
+        };
+        ctx.report(*spec, format!("Spec for {} has no matching function{}", fun, hint));
+    }
+}
+
+fn duplicate_spec(ctx: &mut LintContext, specs: &ModuleSpecs) {
+    let mut seen: HashSet<&FunArity> = HashSet::new();
+    for (fun, spec) in specs.specs.iter() {
+        if !seen.insert(fun) {
+            ctx.report(*spec, format!("Function {} already has a -spec", fun));
+        }
+    }
+}
+
+/// Spec clauses with a different number of arguments, and literal atom arguments no function clause accepts
+fn spec_clause_mismatch(ctx: &mut LintContext, specs: &ModuleSpecs) {
+    for (fun, spec) in specs.specs.iter() {
+        for clause in spec.clauses.iter() {
+            let args: Vec<&Type> = clause.args.iter().collect();
+            if args.len() != fun.arity {
+                ctx.report(clause, format!("Spec clause for {} has {} arguments, the first clause has {}", fun,
+                                           args.len(), fun.arity));
+                continue;
+            }
+            let Some(decl) = specs.functions.get(fun) else { continue };
+            for (index, arg) in args.iter().enumerate() {
+                let Some(atom) = type_atom(arg) else { continue };
+                let accepted = decl.clauses.iter().any(|fun_clause| {
+                    !fun_clause.patterns.iter().nth(index).is_some_and(|p| pattern_excludes_atom(p, atom))
+                });
+                if !accepted {
+                    ctx.report(*arg, format!("Spec of {} allows '{}' as argument {}, but no clause of the function \
+                                              matches it", fun, atom, index + 1));
+                }
+            }
+        }
+    }
+}
+
+/// The atom if a spec argument type is a single atom, like `ok` in `-spec f(ok) -> ...`
+fn type_atom(ty: &Type) -> Option<&str> {
+    match ty {
+        Type::Literal(Literal::Atom(atom)) => Some(atom.value()),
+        Type::Parenthesized(inner) => type_atom(&inner.item),
+        Type::Annotated(annotated) => type_atom(&annotated.ty),
+        _ => None,
+    }
+}
+
+/// Whether the pattern can never match the atom: another literal, or a compound value
+fn pattern_excludes_atom(pattern: &Pattern, atom: &str) -> bool {
+    match pattern {
+        Pattern::Literal(Literal::Atom(token)) => token.value() != atom,
+        Pattern::Literal(_) | Pattern::Tuple(_) | Pattern::List(_) | Pattern::Bits(_) | Pattern::Map(_)
+        | Pattern::Record(_) => true,
+        Pattern::Parenthesized(inner) => pattern_excludes_atom(&inner.item, atom),
+        Pattern::Match(m) => pattern_excludes_atom(&m.left, atom) || pattern_excludes_atom(&m.right, atom),
+        _ => false,
+    }
+}
+
+pub fn spec_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(SpecRule {
+            id: "missing_spec",
+            description: "Exported function has no -spec",
+            severity: Severity::Info,
+            check: missing_spec,
+        }),
+        Box::new(SpecRule {
+            id: "spec_without_function",
+            description: "-spec names a function which the module does not define",
+            severity: Severity::Error,
+            check: spec_without_function,
+        }),
+        Box::new(SpecRule {
+            id: "duplicate_spec",
+            description: "Function has more than one -spec",
+            severity: Severity::Error,
+            check: duplicate_spec,
+        }),
+        Box::new(SpecRule {
+            id: "spec_clause_mismatch",
+            description: "Spec clauses disagree with each other or with the function clause heads",
+            severity: Severity::Warning,
+            check: spec_clause_mismatch,
+        }),
+    ]
+}
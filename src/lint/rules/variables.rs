@@ -0,0 +1,148 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use erl_parse::cst::{Form, Pattern};
+use erl_tokenize::PositionRange;
+use crate::diagnostic::Severity;
+use crate::error::IroncladResult;
+use crate::lint::config::param_usize;
+use crate::lint::{LintContext, Rule};
+use crate::scope::{analyze_function, ScopeEvent, ScopeEventKind};
+
+/// Events for the latest function, shared by the scope rules so the analysis runs once per function
+#[derive(Default)]
+struct ScopeCache {
+    /// Module path and offset of the function the events belong to
+    form: Option<(PathBuf, usize)>,
+    events: Vec<ScopeEvent>,
+}
+
+/// Reports one kind of finding of the variable scope analysis, which runs for every function
+pub struct ScopeEventRule {
+    id: &'static str,
+    description: &'static str,
+    severity: Severity,
+    kind: ScopeEventKind,
+    message: fn(&ScopeEvent) -> String,
+    cache: Rc<RefCell<ScopeCache>>,
+}
+
+impl Rule for ScopeEventRule {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn default_severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_form(&mut self, ctx: &mut LintContext, form: &Form) {
+        let Form::FunDecl(decl) = form else { return };
+        let key = (ctx.unit.path.clone(), form.start_position().offset());
+        let mut cache = self.cache.borrow_mut();
+        if cache.form.as_ref() != Some(&key) {
+            cache.events = analyze_function(decl, &ctx.unit.path);
+            cache.form = Some(key);
+        }
+        for event in cache.events.iter().filter(|e| e.kind == self.kind) {
+            ctx.report_span(event.span.clone(), (self.message)(event));
+        }
+    }
+}
+
+pub fn scope_rules() -> Vec<Box<dyn Rule>> {
+    let cache = Rc::new(RefCell::new(ScopeCache::default()));
+    vec![
+        Box::new(ScopeEventRule {
+            id: "unused_variable",
+            description: "Variable is bound but never used",
+            severity: Severity::Warning,
+            kind: ScopeEventKind::Unused,
+            cache: cache.clone(),
+            message: |e| format!("Variable {} is unused, remove it or rename to _{}", e.name, e.name),
+        }),
+        Box::new(ScopeEventRule {
+            id: "underscore_variable_used",
+            description: "Variable starting with _ is used after binding",
+            severity: Severity::Warning,
+            kind: ScopeEventKind::UnderscoreUsed,
+            cache: cache.clone(),
+            message: |e| format!("Variable {} is used, remove the leading underscore", e.name),
+        }),
+        Box::new(ScopeEventRule {
+            id: "repeated_pattern_variable",
+            description: "Variable appears twice in one pattern, which silently compares the two values",
+            severity: Severity::Warning,
+            kind: ScopeEventKind::RepeatedInPattern,
+            cache: cache.clone(),
+            message: |e| format!("Variable {} appears again in the same pattern, values must be equal \
+                                  to match", e.name),
+        }),
+        Box::new(ScopeEventRule {
+            id: "shadowed_variable",
+            description: "Fun head or comprehension generator rebinds a variable instead of matching it",
+            severity: Severity::Warning,
+            kind: ScopeEventKind::Shadowed,
+            cache: cache.clone(),
+            message: |e| format!("Variable {} shadows a variable of the enclosing scope, it is rebound and \
+                                  not matched", e.name),
+        }),
+        Box::new(ScopeEventRule {
+            id: "unsafe_variable",
+            description: "Variable bound in only some branches is used after the branching expression",
+            severity: Severity::Error,
+            kind: ScopeEventKind::Unsafe,
+            cache,
+            message: |e| format!("Variable {} is unsafe in '{}', it is not bound in every branch",
+                                 e.name, e.construct.unwrap_or_default()),
+        }),
+    ]
+}
+
+/// Function clauses with several bare `_` arguments are hard to read, `_Name` tells what is ignored
+pub struct AnonymousVariable {
+    /// Report clauses with at least this many `_` arguments
+    min_count: usize,
+}
+
+impl Default for AnonymousVariable {
+    fn default() -> Self {
+        Self { min_count: 2 }
+    }
+}
+
+impl Rule for AnonymousVariable {
+    fn id(&self) -> &'static str {
+        "anonymous_variable"
+    }
+
+    fn description(&self) -> &'static str {
+        "Function clause ignores several arguments with _ where a _Name would clarify (param: min_count)"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> IroncladResult<()> {
+        self.min_count = param_usize(params, self.id(), "min_count")?.unwrap_or(self.min_count);
+        Ok(())
+    }
+
+    fn check_form(&mut self, ctx: &mut LintContext, form: &Form) {
+        let Form::FunDecl(decl) = form else { return };
+        for clause in decl.clauses.iter() {
+            let anonymous: Vec<&Pattern> = clause.patterns.iter()
+                .filter(|p| matches!(p, Pattern::Var(var) if var.value() == "_"))
+                .collect();
+            if anonymous.len() >= self.min_count {
+                ctx.report(anonymous[0], format!("{} arguments of {} are ignored with _, consider naming them",
+                                                 anonymous.len(), clause.name.value()));
+            }
+        }
+    }
+}
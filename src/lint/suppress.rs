@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use erl_tokenize::{PositionRange, Token, Tokenizer};
+use crate::diagnostic::{Diagnostic, Severity, SourceSpan};
+use crate::lint::Rule;
+use crate::project::compile_unit::CompileUnit;
+use crate::syntax;
+
+const COMMENT_PREFIX: &str = "ironclad:";
+
+/// Diagnostic code used to report suppressions which matched nothing
+pub const UNUSED_SUPPRESSION: &str = "unused_suppression";
+
+/// Registers `unused_suppression` so `[lints.unused_suppression]` can disable it or change its severity.
+/// The warnings themselves come from `apply_suppressions`, after all other rules ran.
+#[derive(Default)]
+pub struct UnusedSuppression;
+
+impl Rule for UnusedSuppression {
+    fn id(&self) -> &'static str {
+        UNUSED_SUPPRESSION
+    }
+
+    fn description(&self) -> &'static str {
+        "Suppression comment or attribute which matches no finding"
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SuppressionKind {
+    /// `%% ironclad:disable-next-line rule_id`
+    NextLine,
+    /// `%% ironclad:disable rule_id` until `%% ironclad:enable rule_id` or the end of file
+    Range,
+    /// `-ironclad_ignore([rule_id]).`
+    Module,
+}
+
+#[derive(Debug)]
+struct Suppression {
+    kind: SuppressionKind,
+    /// Rule ids or diagnostic codes; empty list suppresses everything
+    codes: Vec<String>,
+    /// Where the comment or attribute is written, for the unused suppression warning
+    line: usize,
+    /// Lines covered by the suppression, inclusive
+    first_line: usize,
+    last_line: usize,
+    used: bool,
+}
+
+impl Suppression {
+    /// `rule_id` is the rule reporting the diagnostic when its code is not the rule id itself
+    fn matches(&self, diagnostic: &Diagnostic, rule_id: Option<&str>) -> bool {
+        let code_matches = self.codes.is_empty()
+            || self.codes.iter().any(|c| *c == diagnostic.code || Some(c.as_str()) == rule_id);
+        code_matches && (self.first_line..=self.last_line).contains(&diagnostic.span.start_line)
+    }
+}
+
+/// Suppression comments and attributes of one module file
+#[derive(Debug)]
+pub struct SuppressionSet {
+    file: PathBuf,
+    items: Vec<Suppression>,
+}
+
+impl SuppressionSet {
+    pub fn collect(unit: &CompileUnit) -> Self {
+        let mut set = SuppressionSet { file: unit.path.clone(), items: Vec::new() };
+        set.collect_comments(unit);
+
+        for value in syntax::wild_attributes(&unit.forms, "ironclad_ignore") {
+            let codes = syntax::attribute_values(value).into_iter()
+                .filter_map(syntax::expr_atom)
+                .map(|atom| atom.to_string())
+                .collect();
+            set.items.push(Suppression {
+                kind: SuppressionKind::Module,
+                codes,
+                line: value.start_position().line(),
+                first_line: 1,
+                last_line: usize::MAX,
+                used: false,
+            });
+        }
+        set
+    }
+
+    fn collect_comments(&mut self, unit: &CompileUnit) {
+        // disable-next-line waiting for the next line which has code on it
+        let mut pending_next_line: Vec<Suppression> = Vec::new();
+
+        for token in Tokenizer::new(unit.source_text.as_str()).map_while(Result::ok) {
+            let line = token.start_position().line();
+            let comment = match &token {
+                Token::Whitespace(_) => continue,
+                Token::Comment(comment) => comment.text().trim_start_matches('%').trim(),
+                _ => {
+                    for mut suppression in pending_next_line.drain(..) {
+                        suppression.first_line = line;
+                        suppression.last_line = line;
+                        self.items.push(suppression);
+                    }
+                    continue;
+                }
+            };
+            let Some(directive) = comment.strip_prefix(COMMENT_PREFIX) else { continue };
+            let mut words = directive.split(|c: char| c.is_whitespace() || c == ',').filter(|w| !w.is_empty());
+            let command = words.next().unwrap_or_default();
+            let codes: Vec<String> = words.map(|w| w.to_string()).collect();
+
+            match command {
+                "disable-next-line" => pending_next_line.push(Suppression {
+                    kind: SuppressionKind::NextLine,
+                    codes,
+                    line,
+                    first_line: line,
+                    last_line: line,
+                    used: false,
+                }),
+                "disable" => self.items.push(Suppression {
+                    kind: SuppressionKind::Range,
+                    codes,
+                    line,
+                    first_line: line,
+                    last_line: usize::MAX,
+                    used: false,
+                }),
+                "enable" => self.close_ranges(&codes, line),
+                _ => {}
+            }
+        }
+    }
+
+    /// End open `disable` ranges for the given codes, or all of them if no codes are given.
+    /// A targeted `enable` does not end a `disable` of all rules.
+    fn close_ranges(&mut self, codes: &[String], line: usize) {
+        for item in self.items.iter_mut() {
+            let open = item.kind == SuppressionKind::Range && item.last_line == usize::MAX;
+            let covered = !item.codes.is_empty() && item.codes.iter().all(|c| codes.contains(c));
+            if open && (codes.is_empty() || covered) {
+                item.last_line = line;
+            }
+        }
+    }
+
+    /// Whether a suppression covers the diagnostic; marks the suppression as used
+    pub fn suppresses(&mut self, diagnostic: &Diagnostic, rule_id: Option<&str>) -> bool {
+        if diagnostic.span.file != self.file || diagnostic.code == UNUSED_SUPPRESSION {
+            return false;
+        }
+        let mut suppressed = false;
+        for item in self.items.iter_mut().filter(|item| item.matches(diagnostic, rule_id)) {
+            item.used = true;
+            suppressed = true;
+        }
+        suppressed
+    }
+
+    /// Findings for suppressions which did not match any diagnostic
+    pub fn unused(&self, severity: Severity) -> Vec<Diagnostic> {
+        self.items.iter()
+            .filter(|item| !item.used)
+            .map(|item| {
+                let what = if item.codes.is_empty() { "all rules".to_string() } else { item.codes.join(", ") };
+                Diagnostic::new(severity, UNUSED_SUPPRESSION,
+                                format!("Suppression of {} does not match any finding", what),
+                                SourceSpan::line(&self.file, item.line))
+            })
+            .collect()
+    }
+}
+
+/// Drop diagnostics covered by suppressions in their module, then report unused suppressions with
+/// `unused_severity`, or not at all when it is `None`. `rule_ids` maps diagnostic codes of project analyses to
+/// the rule reporting them, so suppressing the rule suppresses all its codes.
+pub fn apply_suppressions(units: &[&CompileUnit], diagnostics: Vec<Diagnostic>, rule_ids: &HashMap<&str, &str>,
+                          unused_severity: Option<Severity>) -> Vec<Diagnostic> {
+    let mut sets: HashMap<PathBuf, SuppressionSet> = units.iter()
+        .map(|unit| (unit.path.clone(), SuppressionSet::collect(unit)))
+        .collect();
+
+    let mut result: Vec<Diagnostic> = diagnostics.into_iter()
+        .filter(|diagnostic| match sets.get_mut(&diagnostic.span.file) {
+            Some(set) => !set.suppresses(diagnostic, rule_ids.get(diagnostic.code.as_str()).copied()),
+            None => true,
+        })
+        .collect();
+    if let Some(severity) = unused_severity {
+        for set in sets.values() {
+            result.extend(set.unused(severity));
+        }
+    }
+    result
+}
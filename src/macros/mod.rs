@@ -0,0 +1,136 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::PathBuf;
+use crate::diagnostic::{Diagnostic, Severity, SourceSpan};
+use crate::project::ErlProjectImpl;
+use crate::project::preprocessor_info::{MacroConditionKind, MacroDefinition};
+
+/// Macros defined by the compiler itself, redefining them is an error in erlc
+pub const PREDEFINED_MACROS: &[&str] = &[
+    "MODULE", "MODULE_STRING", "FILE", "LINE", "MACHINE", "FUNCTION_NAME", "FUNCTION_ARITY",
+    "OTP_RELEASE", "FEATURE_AVAILABLE", "FEATURE_ENABLED",
+];
+
+/// Flags which build tools define on the command line (rebar3 and erlang.mk test profiles)
+const BUILD_TOOL_MACROS: &[&str] = &["TEST", "EUNIT", "COMMON_TEST"];
+
+/// A `-define` site; headers included by several modules produce one site
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct DefinitionSite {
+    file: PathBuf,
+    line: usize,
+    arity: Option<usize>,
+    body: String,
+}
+
+impl DefinitionSite {
+    fn new(def: &MacroDefinition) -> Self {
+        Self { file: def.file.clone(), line: def.line, arity: def.arity, body: def.body.clone() }
+    }
+
+    fn location(&self) -> String {
+        format!("{}:{}", self.file.to_string_lossy(), self.line)
+    }
+}
+
+/// Report unused macros, macros with different definitions depending on include order, macros shadowing
+/// predefined ones, and `-ifdef`/`-ifndef` on names which are never defined
+pub fn analyze(project: &ErlProjectImpl) -> Vec<Diagnostic> {
+    let modules = project.modules.read().unwrap();
+    let mut diagnostics = Vec::new();
+
+    // Project wide: every definition site by macro name, and definition sites which are referred to
+    let mut sites: BTreeMap<String, BTreeSet<DefinitionSite>> = BTreeMap::new();
+    let mut used_sites: HashSet<DefinitionSite> = HashSet::new();
+    let mut referenced_names: HashSet<String> = HashSet::new();
+
+    for unit in modules.values() {
+        let info = &unit.pp_info;
+        let names_used: HashSet<&str> = info.macro_uses.iter().map(|m| m.name.as_str())
+            .chain(info.macro_conditions.iter().map(|c| c.name.as_str()))
+            .collect();
+        for def in info.macro_defs.iter() {
+            let site = DefinitionSite::new(def);
+            if names_used.contains(def.name.as_str()) {
+                used_sites.insert(site.clone());
+            }
+            sites.entry(def.name.clone()).or_default().insert(site);
+        }
+        referenced_names.extend(names_used.iter().map(|n| n.to_string()));
+    }
+
+    let config_defines: Vec<&String> = project.project_conf.compiler_options.defines.iter()
+        .flat_map(|table| table.keys())
+        .collect();
+
+    for (name, name_sites) in sites.iter() {
+        for site in name_sites.iter() {
+            let span = SourceSpan::line(&site.file, site.line);
+            if PREDEFINED_MACROS.contains(&name.as_str()) {
+                diagnostics.push(Diagnostic::new(Severity::Warning, "macro_shadows_predefined",
+                                                 format!("Macro ?{} redefines a predefined macro", name),
+                                                 span.clone()));
+            }
+            if !used_sites.contains(site) {
+                diagnostics.push(Diagnostic::new(Severity::Warning, "unused_macro",
+                                                 format!("Macro ?{} is never used", name), span));
+            }
+        }
+    }
+
+    for name in config_defines.iter() {
+        if !referenced_names.contains(name.as_str()) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning, "unused_macro",
+                format!("Macro ?{} from compiler_options.defines is never used", name),
+                SourceSpan::file(&project.config_path)));
+        }
+    }
+
+    // One module sees more than one definition of a name and arity: which one wins depends on the include
+    // order. `-define(F, ...)` and `-define(F(X), ...)` are different macros.
+    let mut reported: HashSet<Vec<DefinitionSite>> = HashSet::new();
+    for unit in modules.values() {
+        let files = unit.pp_info.source_files(&unit.path);
+        for (name, name_sites) in sites.iter() {
+            let mut by_arity: BTreeMap<Option<usize>, Vec<DefinitionSite>> = BTreeMap::new();
+            for site in name_sites.iter().filter(|s| files.contains(&s.file)) {
+                by_arity.entry(site.arity).or_default().push(site.clone());
+            }
+            for (arity, visible) in by_arity {
+                let distinct: HashSet<&str> = visible.iter().map(|s| s.body.as_str()).collect();
+                if distinct.len() < 2 || !reported.insert(visible.clone()) {
+                    continue;
+                }
+                let locations: Vec<String> = visible.iter().map(|s| s.location()).collect();
+                let macro_name = match arity {
+                    Some(arity) => format!("{}/{}", name, arity),
+                    None => name.clone(),
+                };
+                diagnostics.push(Diagnostic::new(
+                    Severity::Warning, "macro_redefined",
+                    format!("Macro ?{} has different definitions in {}, module {} gets the one included first",
+                            macro_name, locations.join(", "), unit.name),
+                    SourceSpan::line(&visible[0].file, visible[0].line)));
+            }
+        }
+    }
+
+    // -ifdef/-ifndef on a name which nothing defines, probably a typo
+    let mut reported_conditions = HashSet::new();
+    for unit in modules.values() {
+        for cond in unit.pp_info.macro_conditions.iter().filter(|c| c.kind != MacroConditionKind::Undef) {
+            let known = sites.contains_key(&cond.name)
+                || PREDEFINED_MACROS.contains(&cond.name.as_str())
+                || BUILD_TOOL_MACROS.contains(&cond.name.as_str())
+                || config_defines.iter().any(|d| **d == cond.name);
+            if known || !reported_conditions.insert((cond.file.clone(), cond.line)) {
+                continue;
+            }
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning, "undefined_macro_condition",
+                format!("Macro ?{} is checked here but never defined in the project", cond.name),
+                SourceSpan::line(&cond.file, cond.line)));
+        }
+    }
+    diagnostics
+}
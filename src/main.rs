@@ -1,5 +1,18 @@
+mod baseline;
+mod behaviours;
+mod callgraph;
+mod cli;
+mod deprecations;
+mod diagnostic;
 mod error;
+mod include_graph;
+mod lint;
+mod macros;
+mod metrics;
 mod project;
+mod records;
+mod scope;
+mod syntax;
 
 use std::process::exit;
 use crate::error::IroncladResult;
@@ -7,44 +20,29 @@ use crate::project::{ErlProjectImpl};
 
 fn main() {
     match main_do() {
-        Ok(_) => {
-            println!("Ironclad finished.");
-            exit(0);
+        Ok(exit_code) => {
+            eprintln!("Ironclad finished.");
+            exit(exit_code);
         }
         Err(e) => {
-            println!("{}", e);
+            eprintln!("{}", e);
             exit(e.get_process_exit_code())
         }
     }
 }
 
-fn main_do() -> IroncladResult<()> {
-    // let matches = clap::Command::new("parse")
-    //     .arg(Arg::with_name("ERLANG_FILE").index(1).required(true))
-    //     .arg(
-    //         Arg::with_name("CURRENT_DIR")
-    //             .long("current-dir")
-    //             .takes_value(true),
-    //     )
-    //     .arg(
-    //         Arg::with_name("ERL_LIBS")
-    //             .long("libs")
-    //             .takes_value(true)
-    //             .multiple(true),
-    //     )
-    //     .get_matches();
+fn main_do() -> IroncladResult<i32> {
+    let matches = cli::build_command().get_matches();
+    let config_file = matches.get_one::<String>("config").unwrap();
 
     let mut project = ErlProjectImpl::new();
-    project.load_project_config("ironclad.toml")?;
+    project.load_project_config(config_file)?;
 
     project.input_files = project.build_file_list()?;
-    println!("{}", project);
+    eprintln!("{}", project);
 
+    // Parse all ERL files and their included includes
     project.parse_inputs()?;
 
-    // Parse all ERL files and their included includes
-    // if let Err(e) = ErlParseStage::run_parse_stage(&project) {
-    //     erl_fatal_icerror(e);
-    // }
-    Ok(())
-}
\ No newline at end of file
+    cli::run_command(&matches, &project)
+}
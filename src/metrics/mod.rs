@@ -0,0 +1,247 @@
+use std::fmt::Write;
+use std::str::FromStr;
+use erl_parse::cst::forms::FunDecl;
+use erl_parse::cst::{Expr, Form, GuardTest};
+use erl_tokenize::PositionRange;
+use serde::Serialize;
+use crate::error::{IroncladError, IroncladResult};
+use crate::project::ErlProjectImpl;
+use crate::project::compile_unit::CompileUnit;
+use crate::syntax;
+use crate::syntax::walk::{walk_fun_clause, Visitor};
+
+/// Size and complexity of one function
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionMetrics {
+    /// `name/arity`
+    pub function: String,
+    pub line: usize,
+    /// Number of independent paths: 1 plus every extra clause, guard alternative and `andalso`/`orelse`
+    pub complexity: usize,
+    /// Deepest nesting of `case`, `if`, `receive` and `try`
+    pub nesting: usize,
+    pub clauses: usize,
+    /// Lines which are not blank and not only a comment
+    pub loc: usize,
+    pub arguments: usize,
+}
+
+impl FunctionMetrics {
+    pub fn compute(decl: &FunDecl, source_text: &str) -> Option<Self> {
+        let fun = syntax::fun_decl_name(decl)?;
+        let mut counter = ComplexityCounter::default();
+        let mut clauses = 0;
+        for clause in decl.clauses.iter() {
+            clauses += 1;
+            if let Some(guard) = &clause.guard {
+                counter.complexity += guard.seq.iter().count().saturating_sub(1);
+            }
+            walk_fun_clause(&mut counter, clause);
+        }
+
+        let (first_line, last_line) = (decl.start_position().line(), decl.end_position().line());
+        let loc = source_text.lines()
+            .skip(first_line.saturating_sub(1))
+            .take(last_line + 1 - first_line)
+            .filter(|line| {
+                let line = line.trim();
+                !line.is_empty() && !line.starts_with('%')
+            })
+            .count();
+
+        Some(Self {
+            function: fun.to_string(),
+            line: first_line,
+            complexity: 1 + clauses.saturating_sub(1) + counter.complexity,
+            nesting: counter.max_nesting(),
+            clauses,
+            loc,
+            arguments: fun.arity,
+        })
+    }
+}
+
+type Position = (usize, usize);
+
+/// Counts decision points and remembers where the branching expressions are, nesting is derived from
+/// how their source ranges contain each other
+#[derive(Default)]
+struct ComplexityCounter {
+    complexity: usize,
+    branches: Vec<(Position, Position)>,
+}
+
+impl ComplexityCounter {
+    fn branch<T: PositionRange>(&mut self, node: &T, extra_paths: usize) {
+        self.complexity += extra_paths;
+        let (start, end) = (node.start_position(), node.end_position());
+        self.branches.push(((start.line(), start.column()), (end.line(), end.column())));
+    }
+
+    fn max_nesting(&self) -> usize {
+        self.branches.iter()
+            .map(|inner| {
+                self.branches.iter().filter(|outer| outer.0 <= inner.0 && inner.1 <= outer.1).count()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn is_short_circuit(op: &str) -> bool {
+    op == "andalso" || op == "orelse"
+}
+
+impl Visitor for ComplexityCounter {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Case(case) => self.branch(expr, case.clauses.iter().count().saturating_sub(1)),
+            Expr::If(if_expr) => self.branch(expr, if_expr.clauses.iter().count().saturating_sub(1)),
+            Expr::Receive(receive) => {
+                let paths = receive.clauses.iter().count() + usize::from(receive.timeout.is_some());
+                self.branch(expr, paths.saturating_sub(1));
+            }
+            Expr::Try(try_expr) => {
+                let of_paths = try_expr.branch.as_ref()
+                    .map_or(0, |of| of.clauses.iter().count().saturating_sub(1));
+                let catch_paths = try_expr.catch.as_ref().map_or(0, |catch| catch.clauses.iter().count());
+                self.branch(expr, of_paths + catch_paths);
+            }
+            Expr::BinaryOpCall(call) if is_short_circuit(syntax::binary_op_text(&call.op)) => self.complexity += 1,
+            _ => {}
+        }
+    }
+
+    fn visit_guard_test(&mut self, test: &GuardTest) {
+        if let GuardTest::BinaryOpCall(call) = test {
+            if is_short_circuit(syntax::binary_op_text(&call.op)) {
+                self.complexity += 1;
+            }
+        }
+    }
+}
+
+/// Module totals and the metrics of each function
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleMetrics {
+    pub module: String,
+    pub path: String,
+    pub functions: usize,
+    pub exports: usize,
+    pub function_metrics: Vec<FunctionMetrics>,
+}
+
+impl ModuleMetrics {
+    pub fn compute(unit: &CompileUnit) -> Self {
+        let exports = if syntax::has_export_all(&unit.forms) {
+            syntax::defined_functions(&unit.forms).len()
+        } else {
+            syntax::exported_functions(&unit.forms).len()
+        };
+        let function_metrics: Vec<FunctionMetrics> = unit.forms.iter()
+            .filter_map(|form| match form {
+                Form::FunDecl(decl) => FunctionMetrics::compute(decl, &unit.source_text),
+                _ => None,
+            })
+            .collect();
+        Self {
+            module: unit.name.clone(),
+            path: unit.path.to_string_lossy().to_string(),
+            functions: function_metrics.len(),
+            exports,
+            function_metrics,
+        }
+    }
+}
+
+/// Metrics of every module in the project, sorted by module name
+pub fn collect(project: &ErlProjectImpl) -> Vec<ModuleMetrics> {
+    let modules = project.modules.read().unwrap();
+    let mut result: Vec<ModuleMetrics> = modules.values().map(ModuleMetrics::compute).collect();
+    result.sort_by(|a, b| a.module.cmp(&b.module));
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for MetricsFormat {
+    type Err = IroncladError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(MetricsFormat::Table),
+            "csv" => Ok(MetricsFormat::Csv),
+            "json" => Ok(MetricsFormat::Json),
+            other => Err(IroncladError::CommandLine(format!("Unknown format '{}', expected table, csv or json",
+                                                            other))),
+        }
+    }
+}
+
+const FUNCTION_COLUMNS: [&str; 8] = ["module", "function", "line", "complexity", "nesting", "clauses", "loc",
+    "arguments"];
+const MODULE_COLUMNS: [&str; 4] = ["module", "path", "functions", "exports"];
+
+/// Rows of the function or module level table, as text cells
+fn rows(modules: &[ModuleMetrics], module_level: bool) -> (Vec<&'static str>, Vec<Vec<String>>) {
+    if module_level {
+        let rows = modules.iter()
+            .map(|m| vec![m.module.clone(), m.path.clone(), m.functions.to_string(), m.exports.to_string()])
+            .collect();
+        return (MODULE_COLUMNS.to_vec(), rows);
+    }
+    let rows = modules.iter()
+        .flat_map(|m| m.function_metrics.iter().map(move |f| vec![
+            m.module.clone(), f.function.clone(), f.line.to_string(), f.complexity.to_string(),
+            f.nesting.to_string(), f.clauses.to_string(), f.loc.to_string(), f.arguments.to_string(),
+        ]))
+        .collect();
+    (FUNCTION_COLUMNS.to_vec(), rows)
+}
+
+/// Render metrics in the requested format. Table and CSV list functions, or modules with `module_level`;
+/// JSON always contains both.
+pub fn render(modules: &[ModuleMetrics], format: MetricsFormat, module_level: bool) -> IroncladResult<String> {
+    let mut out = String::new();
+    match format {
+        MetricsFormat::Json => return Ok(serde_json::to_string_pretty(modules)?),
+        MetricsFormat::Csv => {
+            let (header, rows) = rows(modules, module_level);
+            writeln!(out, "{}", header.join(",")).unwrap();
+            for row in rows {
+                let cells: Vec<String> = row.iter().map(|cell| csv_escape(cell)).collect();
+                writeln!(out, "{}", cells.join(",")).unwrap();
+            }
+        }
+        MetricsFormat::Table => {
+            let (header, rows) = rows(modules, module_level);
+            let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+            for row in rows.iter() {
+                for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+            let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+            for row in std::iter::once(&header).chain(rows.iter()) {
+                let cells: Vec<String> = row.iter().zip(widths.iter())
+                    .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                    .collect();
+                writeln!(out, "{}", cells.join("  ").trim_end()).unwrap();
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
@@ -1,5 +1,34 @@
+use std::path::PathBuf;
+use erl_parse::cst::Form;
+use crate::project::preprocessor_info::PreprocessorInfo;
+
 /// An Erlang module with module stuff attached and the syntax tree
 #[derive(Debug)]
 pub struct CompileUnit {
     pub name: String,
+    /// Source file the module was loaded from
+    pub path: PathBuf,
+    /// Contents of the module file, before preprocessing
+    pub source_text: String,
+    /// Module forms after preprocessing, in the order they appear in the source
+    pub forms: Vec<Form>,
+    /// Includes and macros seen by the preprocessor
+    pub pp_info: PreprocessorInfo,
+}
+
+impl CompileUnit {
+    /// OTP application the module belongs to, from the directory layout: `<app>/src/<module>.erl`
+    /// or `<app>-<vsn>/src/<module>.erl`. Modules in nested directories under `src` count too.
+    pub fn otp_app(&self) -> Option<String> {
+        let components: Vec<String> = self.path.components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let src_index = components.iter().rposition(|c| c == "src" || c == "test")?;
+        let app_dir = components.get(src_index.checked_sub(1)?)?;
+        let app = match app_dir.split_once('-') {
+            Some((name, _vsn)) => name,
+            None => app_dir.as_str(),
+        };
+        Some(app.to_string())
+    }
 }
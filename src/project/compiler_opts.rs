@@ -6,6 +6,9 @@ use serde::Deserialize;
 #[derive(Default, Deserialize, Debug)]
 pub struct IroncladProjectFile {
     pub compiler_options: CompilerOptions,
+    /// Rule settings, one table per rule id or diagnostic code: `[lints.unused_record]`.
+    /// Each table can contain `enabled`, `severity` and rule specific parameters.
+    pub lints: Option<toml::Table>,
 }
 
 #[derive(Default, Deserialize, Debug)]
@@ -20,6 +23,8 @@ pub struct CompilerOptions {
     pub exclude_suffixes: Option<Vec<String>>,
     /// Defaults to empty list. Preprocessor defs in form of "NAME" or "NAME=VALUE"
     pub defines: Option<toml::Table>,
+    /// OTP release the project runs on or is moving to, like 26. Calls to functions removed in it are errors.
+    pub target_otp: Option<u32>,
 }
 
 // impl CompilerOpts {
@@ -9,9 +9,12 @@ use trackable::track_try_unwrap;
 use crate::error::{IroncladError, IroncladResult};
 use crate::project::compile_unit::CompileUnit;
 use crate::project::compiler_opts::IroncladProjectFile;
+use crate::project::preprocessor_info::PreprocessorInfo;
+use crate::syntax;
 
 pub mod compile_unit;
 pub mod compiler_opts;
+pub mod preprocessor_info;
 
 #[derive(Default, Debug)]
 pub struct ErlProjectImpl {
@@ -23,6 +26,8 @@ pub struct ErlProjectImpl {
 
     // As loaded from ironclad.toml
     pub project_conf: IroncladProjectFile,
+    /// Path to the project file which was loaded
+    pub config_path: PathBuf,
 
     /// Collection of loaded modules
     pub modules: RwLock<HashMap<String, CompileUnit>>,
@@ -44,6 +49,7 @@ impl ErlProjectImpl {
             //input_masks: VecDeque::new(),
             // input_directories: VecDeque::new(),
             project_conf: Default::default(),
+            config_path: PathBuf::default(),
             modules: RwLock::new(HashMap::new()),
             input_files: Vec::new(),
             exclude_prefixes: Vec::default(),
@@ -57,30 +63,41 @@ impl ErlProjectImpl {
     /// Traverse directories starting from each of the inputs.directories; Add files from inputs if not duplicate.
     /// Assign the result of this function to 'self.input_paths'.
     pub fn build_file_list(&self) -> IroncladResult<Vec<PathBuf>> {
-        let mut file_set: HashSet<PathBuf> = HashSet::with_capacity(ErlProjectImpl::DEFAULT_CAPACITY);
-        let mut file_list = Vec::new();
-        let input_paths = self.project_conf.compiler_options.input_paths.clone().unwrap_or_default();
-
         let m_input_masks = self.project_conf.compiler_options.input_masks.as_ref();
         // println!("Building list of input files... input_paths={:?} input_masks={:?}", input_paths, m_input_masks);
 
-        if let Some(input_masks) = m_input_masks {
-            for file_mask in input_masks {
-                for dir in input_paths.iter() {
-                    let file_glob = PathBuf::from(dir).join("**").join(file_mask);
-                    // println!("Dir {:?} Glob: {:?}", dir, file_glob);
-
-                    let g_result = glob::glob(file_glob.to_str().unwrap());
-
-                    for entry in g_result.map_err(IroncladError::from)? {
-                        match entry {
-                            Ok(path) => self.maybe_add_path(&mut file_set, &mut file_list, path)?,
-                            Err(err) => return Err(IroncladError::from(err).into()),
-                        }
-                    } // for glob search results
-                } // for input dirs
-            } // for input file masks
+        match m_input_masks {
+            Some(input_masks) => self.scan_input_paths(input_masks),
+            None => Ok(Vec::new()),
         }
+    }
+
+    /// Find all header files in the input directories, including those which no module includes
+    pub fn find_header_files(&self) -> IroncladResult<Vec<PathBuf>> {
+        self.scan_input_paths(&["*.hrl".to_string()])
+    }
+
+    /// Glob every input directory recursively with each of the file masks; exclusions and duplicates are skipped
+    fn scan_input_paths(&self, file_masks: &[String]) -> IroncladResult<Vec<PathBuf>> {
+        let mut file_set: HashSet<PathBuf> = HashSet::with_capacity(ErlProjectImpl::DEFAULT_CAPACITY);
+        let mut file_list = Vec::new();
+        let input_paths = self.project_conf.compiler_options.input_paths.clone().unwrap_or_default();
+
+        for file_mask in file_masks {
+            for dir in input_paths.iter() {
+                let file_glob = PathBuf::from(dir).join("**").join(file_mask);
+                // println!("Dir {:?} Glob: {:?}", dir, file_glob);
+
+                let g_result = glob::glob(file_glob.to_str().unwrap());
+
+                for entry in g_result.map_err(IroncladError::from)? {
+                    match entry {
+                        Ok(path) => self.maybe_add_path(&mut file_set, &mut file_list, path)?,
+                        Err(err) => return Err(IroncladError::from(err).into()),
+                    }
+                } // for glob search results
+            } // for input dirs
+        } // for input file masks
         Ok(file_list)
     }
 
@@ -126,6 +143,7 @@ impl ErlProjectImpl {
         let config_contents = std::fs::read_to_string(filename).map_err(IroncladError::from)?;
         // Load TOML config as a generic map (TODO: Load as serde structured TOML)
         self.project_conf = toml::from_str(config_contents.as_str())?;
+        self.config_path = PathBuf::from(filename);
         self.exclude_prefixes = self.project_conf.compiler_options.exclude_prefixes
             .as_ref().unwrap_or(&Vec::new()).clone();
         self.exclude_suffixes = self.project_conf.compiler_options.exclude_suffixes
@@ -133,30 +151,57 @@ impl ErlProjectImpl {
         Ok(())
     }
 
+    /// Directory of the project file, relative paths in lint settings are relative to it
+    pub fn root_dir(&self) -> PathBuf {
+        let dir = self.config_path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf())
+    }
+
+    /// Input directories as canonical paths, files outside of them come from OTP or dependencies
+    pub fn input_dirs(&self) -> Vec<PathBuf> {
+        self.project_conf.compiler_options.input_paths.iter().flatten()
+            .filter_map(|dir| std::fs::canonicalize(dir).ok())
+            .collect()
+    }
+
     /// Convert input files constructed in build_file_list() into source trees stored in `CompileUnit`s
     pub(crate) fn parse_inputs(&self) -> IroncladResult<()> {
         // println!("Parsing input files... {:?}", self.input_files);
         for path in self.input_files.iter() {
             let file_contents = std::fs::read_to_string(path.as_path()).map_err(IroncladError::from)?;
-            self.parse_module_text(path, file_contents.as_str());
+            let unit = self.parse_module_text(path, file_contents.as_str());
+            self.modules.write().unwrap().insert(unit.name.clone(), unit);
         }
         Ok(())
     }
 
-    fn parse_module_text(&self, filename: &Path, text: &str) {
-        println!("* Parsing {}", filename.to_string_lossy());
+    fn parse_module_text(&self, filename: &Path, text: &str) -> CompileUnit {
+        eprintln!("* Parsing {}", filename.to_string_lossy());
 
         // let mut parser = Parser::new(TokenReader::new(Preprocessor::new(Lexer::new(text))));
         let mut pp = Preprocessor::new(Lexer::new(text));
         // Add current file directory to include search path
         if let Some(parent) = filename.parent() {
-            println!("    adding include dir {:?}", parent);
+            eprintln!("    adding include dir {:?}", parent);
             pp.code_paths_mut().push_back(parent.into()); // add include dirs
         }
-        let reader = &mut TokenReader::new(&mut pp);
-        let _module = track_try_unwrap!(erl_parse::builtin::parse_module(reader));
+        let module = {
+            let reader = &mut TokenReader::new(&mut pp);
+            track_try_unwrap!(erl_parse::builtin::parse_module(reader))
+        };
 
         // let value: Form = track_try_unwrap!(parser.parse(), "text={:?}", text);
+        // Module name comes from -module() attribute, or the file name if the attribute is missing
+        let name = syntax::module_name(&module.forms).unwrap_or_else(|| {
+            filename.file_stem().unwrap_or_default().to_string_lossy().to_string()
+        });
+        CompileUnit {
+            name,
+            path: filename.to_path_buf(),
+            source_text: text.to_string(),
+            forms: module.forms,
+            pp_info: PreprocessorInfo::collect(&pp, filename),
+        }
     }
 }
 
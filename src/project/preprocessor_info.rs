@@ -0,0 +1,182 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use erl_pp::{Directive, Preprocessor};
+use erl_tokenize::{Lexer, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncludeKind {
+    Include,
+    IncludeLib,
+}
+
+impl IncludeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IncludeKind::Include => "include",
+            IncludeKind::IncludeLib => "include_lib",
+        }
+    }
+}
+
+/// An `-include` or `-include_lib` directive met while preprocessing the module or one of its headers
+#[derive(Debug, Clone)]
+pub struct IncludeDirective {
+    pub kind: IncludeKind,
+    /// Path as written in the directive
+    pub path: String,
+    /// File which contains the directive: the module itself or a header
+    pub included_from: PathBuf,
+    pub line: usize,
+    /// The header file found on disk, `None` if it was not found in the include search path
+    pub resolved: Option<PathBuf>,
+}
+
+/// A `-define` directive
+#[derive(Debug, Clone)]
+pub struct MacroDefinition {
+    pub name: String,
+    /// Number of macro arguments, `None` for macros defined without parentheses
+    pub arity: Option<usize>,
+    /// Replacement tokens joined with spaces, to compare definitions
+    pub body: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroConditionKind {
+    Ifdef,
+    Ifndef,
+    Undef,
+}
+
+/// `-ifdef`, `-ifndef` or `-undef` directive, these refer to a macro name without expanding it
+#[derive(Debug, Clone)]
+pub struct MacroCondition {
+    pub kind: MacroConditionKind,
+    pub name: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// A `?NAME` macro invocation
+#[derive(Debug, Clone)]
+pub struct MacroUse {
+    pub name: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// What the preprocessor has seen while reading a module and the headers it includes.
+/// The parse tree only contains the result of preprocessing, this keeps the directives.
+#[derive(Debug, Default)]
+pub struct PreprocessorInfo {
+    pub includes: Vec<IncludeDirective>,
+    pub macro_defs: Vec<MacroDefinition>,
+    pub macro_uses: Vec<MacroUse>,
+    pub macro_conditions: Vec<MacroCondition>,
+}
+
+impl PreprocessorInfo {
+    pub fn collect(pp: &Preprocessor<Lexer<&str>>, module_path: &Path) -> Self {
+        let mut info = PreprocessorInfo::default();
+
+        for (position, directive) in pp.directives().iter() {
+            let file = position_file(position, module_path);
+            match directive {
+                Directive::Include(d) => info.add_include(IncludeKind::Include, d.path.value(), file, position,
+                                                          pp.code_paths()),
+                Directive::IncludeLib(d) => info.add_include(IncludeKind::IncludeLib, d.path.value(), file, position,
+                                                             pp.code_paths()),
+                Directive::Define(d) => info.macro_defs.push(MacroDefinition {
+                    name: d.name.value().to_string(),
+                    arity: d.variables.as_ref().map(|vars| vars.iter().count()),
+                    body: d.replacement.iter().map(|t| t.text()).collect::<Vec<_>>().join(" "),
+                    file,
+                    line: position.line(),
+                }),
+                Directive::Ifdef(d) => info.add_condition(MacroConditionKind::Ifdef, d.name.value(), file, position),
+                Directive::Ifndef(d) => info.add_condition(MacroConditionKind::Ifndef, d.name.value(), file, position),
+                Directive::Undef(d) => info.add_condition(MacroConditionKind::Undef, d.name.value(), file, position),
+                _ => {}
+            }
+        }
+
+        for (position, call) in pp.macro_calls().iter() {
+            info.macro_uses.push(MacroUse {
+                name: call.name.value().to_string(),
+                file: position_file(position, module_path),
+                line: position.line(),
+            });
+        }
+        info
+    }
+
+    fn add_condition(&mut self, kind: MacroConditionKind, name: &str, file: PathBuf, position: &Position) {
+        self.macro_conditions.push(MacroCondition { kind, name: name.to_string(), file, line: position.line() });
+    }
+
+    /// The module file and all headers it has included
+    pub fn source_files(&self, module_path: &Path) -> HashSet<PathBuf> {
+        let mut files: HashSet<PathBuf> = self.includes.iter().filter_map(|d| d.resolved.clone()).collect();
+        files.insert(module_path.to_path_buf());
+        files
+    }
+
+    fn add_include(&mut self, kind: IncludeKind, path: &str, included_from: PathBuf, position: &Position,
+                   code_paths: &VecDeque<PathBuf>) {
+        let resolved = resolve_include(kind, path, &included_from, code_paths);
+        self.includes.push(IncludeDirective {
+            kind,
+            path: path.to_string(),
+            included_from,
+            line: position.line(),
+            resolved,
+        });
+    }
+}
+
+thread_local! {
+    /// Header paths as written by the preprocessor mapped to their canonical form, one lookup per file
+    static CANONICAL_PATHS: RefCell<HashMap<PathBuf, PathBuf>> = RefCell::new(HashMap::new());
+}
+
+/// Positions inside the module file itself have no file path set. Header paths are canonicalized to compare
+/// equal with the resolved include paths.
+pub fn position_file(position: &Position, module_path: &Path) -> PathBuf {
+    match position.filepath() {
+        Some(path) => CANONICAL_PATHS.with(|cache| {
+            cache.borrow_mut()
+                .entry(path.to_path_buf())
+                .or_insert_with(|| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()))
+                .clone()
+        }),
+        None => module_path.to_path_buf(),
+    }
+}
+
+/// Find the header on disk similar to how the preprocessor does it: relative to the including file first,
+/// then in the include search path. For `-include_lib("app/include/x.hrl")` the application directory
+/// is also looked up in the parent and grandparent of each search path directory, like a lib dir.
+fn resolve_include(kind: IncludeKind, path: &str, included_from: &Path,
+                   code_paths: &VecDeque<PathBuf>) -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Some(dir) = included_from.parent() {
+        candidates.push(dir.join(path));
+    }
+    candidates.extend(code_paths.iter().map(|dir| dir.join(path)));
+
+    if kind == IncludeKind::IncludeLib {
+        if let Some((_app, rest)) = path.split_once('/') {
+            for dir in code_paths.iter() {
+                candidates.extend(dir.ancestors().skip(1).take(2).map(|ancestor| ancestor.join(path)));
+                candidates.push(dir.join(rest));
+            }
+        }
+    }
+
+    candidates.into_iter()
+        .find(|candidate| candidate.is_file())
+        .and_then(|found| std::fs::canonicalize(found).ok())
+}
@@ -0,0 +1,354 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use erl_parse::cst::{Expr, Form, GuardTest, Pattern, Type};
+use erl_tokenize::PositionRange;
+use crate::diagnostic::{Diagnostic, Severity, SourceSpan};
+use crate::project::ErlProjectImpl;
+use crate::project::compile_unit::CompileUnit;
+use crate::project::preprocessor_info::position_file;
+use crate::syntax;
+use crate::syntax::walk::{walk_forms, Visitor};
+
+#[derive(Debug, Clone)]
+pub struct RecordFieldDef {
+    pub name: String,
+    /// Default value if it is a simple literal
+    pub default: Option<String>,
+}
+
+/// A `-record()` declaration from a module or a header
+#[derive(Debug, Clone)]
+pub struct RecordDefinition {
+    pub name: String,
+    pub fields: Vec<RecordFieldDef>,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+impl RecordDefinition {
+    pub fn field_names(&self) -> Vec<&str> {
+        self.fields.iter().map(|f| f.name.as_str()).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordUsageKind {
+    /// `#rec{...}` creating a new record
+    Construct,
+    /// `R#rec{...}`
+    Update,
+    /// `R#rec.field`
+    Access,
+    /// `#rec.field`
+    Index,
+    /// `#rec{...}` in a pattern or a guard
+    Match,
+    /// `record_info(fields, rec)` and `#rec{_ = V}`, which refer to all fields
+    AllFields,
+    /// `#rec{}` in a `-type`, `-spec` or `-callback`, which uses the record but none of its fields
+    Type,
+}
+
+/// One place where a record, and possibly one of its fields, is mentioned
+#[derive(Debug, Clone)]
+pub struct RecordUsage {
+    pub record: String,
+    pub field: Option<String>,
+    /// Literal value assigned to the field in a `Construct`
+    pub value: Option<String>,
+    pub kind: RecordUsageKind,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// All record definitions in the project, and which modules see which of them
+#[derive(Debug, Default)]
+pub struct RecordIndex {
+    /// Each definition is stored once even when its header is included in many modules
+    pub definitions: Vec<RecordDefinition>,
+    /// Per definition index, usages from all modules which see that definition
+    pub usages: HashMap<usize, Vec<RecordUsage>>,
+    /// Module name mapped to the definitions visible in it, in source order
+    pub module_definitions: HashMap<String, Vec<usize>>,
+}
+
+impl RecordIndex {
+    pub fn build(project: &ErlProjectImpl) -> Self {
+        let mut index = RecordIndex::default();
+        let modules = project.modules.read().unwrap();
+        for unit in modules.values() {
+            index.add_unit(unit);
+        }
+        index
+    }
+
+    fn add_unit(&mut self, unit: &CompileUnit) {
+        // Usages refer to the latest definition of a name, all of them count for conflicts
+        let mut visible: HashMap<String, usize> = HashMap::new();
+        let mut defined: Vec<usize> = Vec::new();
+        for form in unit.forms.iter() {
+            let Form::RecordDecl(decl) = form else { continue };
+            let definition = RecordDefinition {
+                name: decl.record_name.value().to_string(),
+                fields: decl.fields.iter().map(|field| RecordFieldDef {
+                    name: field.field_name.value().to_string(),
+                    default: field.field_default.as_ref().and_then(|d| syntax::literal_text(&d.value)),
+                }).collect(),
+                file: position_file(&form.start_position(), &unit.path),
+                line: form.start_position().line(),
+            };
+            let def_index = self.intern(definition);
+            visible.insert(decl.record_name.value().to_string(), def_index);
+            if !defined.contains(&def_index) {
+                defined.push(def_index);
+            }
+        }
+        self.module_definitions.insert(unit.name.clone(), defined);
+
+        let mut collector = RecordUsageCollector { unit, usages: Vec::new() };
+        walk_forms(&mut collector, &unit.forms);
+        for usage in collector.usages {
+            if let Some(def_index) = visible.get(&usage.record) {
+                self.usages.entry(*def_index).or_default().push(usage);
+            }
+        }
+    }
+
+    /// Find an already stored definition from the same file and line, or store a new one
+    fn intern(&mut self, definition: RecordDefinition) -> usize {
+        let existing = self.definitions.iter()
+            .position(|d| d.file == definition.file && d.line == definition.line);
+        existing.unwrap_or_else(|| {
+            self.definitions.push(definition);
+            self.definitions.len() - 1
+        })
+    }
+
+    fn usages_of(&self, def_index: usize) -> &[RecordUsage] {
+        self.usages.get(&def_index).map(|u| u.as_slice()).unwrap_or_default()
+    }
+}
+
+/// Report unused records and fields, fields which always get the same value, and conflicting definitions
+pub fn analyze(project: &ErlProjectImpl) -> Vec<Diagnostic> {
+    let index = RecordIndex::build(project);
+    let mut diagnostics = Vec::new();
+
+    for (def_index, definition) in index.definitions.iter().enumerate() {
+        let usages = index.usages_of(def_index);
+        let span = SourceSpan::line(&definition.file, definition.line);
+        if usages.is_empty() {
+            diagnostics.push(Diagnostic::new(Severity::Warning, "unused_record",
+                                             format!("Record #{} is never used", definition.name), span));
+            continue;
+        }
+        check_fields(definition, usages, span, &mut diagnostics);
+    }
+    check_conflicts(&index, &mut diagnostics);
+    diagnostics
+}
+
+fn check_fields(definition: &RecordDefinition, usages: &[RecordUsage], span: SourceSpan,
+                diagnostics: &mut Vec<Diagnostic>) {
+    let all_fields_used = usages.iter().any(|u| u.kind == RecordUsageKind::AllFields);
+    let constructs: Vec<&RecordUsage> = usages.iter()
+        .filter(|u| u.kind == RecordUsageKind::Construct && u.field.is_none())
+        .collect();
+    let construct_sites: HashSet<(&PathBuf, usize)> = constructs.iter().map(|u| (&u.file, u.line)).collect();
+
+    for field in definition.fields.iter() {
+        let field_usages: Vec<&RecordUsage> = usages.iter()
+            .filter(|u| u.field.as_deref() == Some(field.name.as_str()))
+            .collect();
+        if field_usages.is_empty() && !all_fields_used {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning, "unused_record_field",
+                format!("Field '{}' of record #{} is never used", field.name, definition.name), span.clone()));
+            continue;
+        }
+
+        // Every construction of the record sets this field, always to the same literal value
+        let assigned: Vec<&RecordUsage> = field_usages.iter().cloned()
+            .filter(|u| u.kind == RecordUsageKind::Construct)
+            .collect();
+        let values: HashSet<Option<&str>> = assigned.iter().map(|u| u.value.as_deref()).collect();
+        if construct_sites.len() < 2 || assigned.len() != constructs.len() || values.len() != 1 {
+            continue;
+        }
+        if let Some(Some(value)) = values.into_iter().next() {
+            let message = if field.default.as_deref() == Some(value) {
+                format!("Field '{}' of record #{} is always set to {}, which is already its default",
+                        field.name, definition.name, value)
+            } else {
+                format!("Field '{}' of record #{} is always set to {}, consider making it the default",
+                        field.name, definition.name, value)
+            };
+            diagnostics.push(Diagnostic::new(Severity::Info, "record_field_same_value", message, span.clone()));
+        }
+    }
+}
+
+fn is_header(definition: &RecordDefinition) -> bool {
+    definition.file.extension().is_some_and(|ext| ext == "hrl")
+}
+
+/// Same record name with different fields. When one module can see both definitions it is an error.
+/// Differing definitions in headers are a warning, because records are often sent between modules in messages.
+/// Records defined in a module file are private to it, these only conflict inside the module.
+fn check_conflicts(index: &RecordIndex, diagnostics: &mut Vec<Diagnostic>) {
+    let mut reported: HashSet<&str> = HashSet::new();
+    let mut modules: Vec<&String> = index.module_definitions.keys().collect();
+    modules.sort();
+    for module in modules {
+        let by_name = group_by_name(index, index.module_definitions[module].iter().cloned());
+        for (name, def_indices) in by_name {
+            if reported.contains(name) {
+                continue;
+            }
+            if let Some(diagnostic) = conflict(index, name, &def_indices, Severity::Error,
+                                               "both definitions are visible in one module") {
+                reported.insert(name);
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    let headers = (0..index.definitions.len()).filter(|i| is_header(&index.definitions[*i]));
+    for (name, def_indices) in group_by_name(index, headers) {
+        if reported.contains(name) {
+            continue;
+        }
+        if let Some(diagnostic) = conflict(index, name, &def_indices, Severity::Warning,
+                                           "records with this name may be passed between modules") {
+            diagnostics.push(diagnostic);
+        }
+    }
+}
+
+fn group_by_name(index: &RecordIndex, def_indices: impl Iterator<Item=usize>) -> BTreeMap<&str, Vec<usize>> {
+    let mut by_name: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for def_index in def_indices {
+        by_name.entry(index.definitions[def_index].name.as_str()).or_default().push(def_index);
+    }
+    by_name
+}
+
+/// A `record_conflict` if any of the definitions has other fields than the first one
+fn conflict(index: &RecordIndex, name: &str, def_indices: &[usize], severity: Severity,
+            context: &str) -> Option<Diagnostic> {
+    let first = &index.definitions[def_indices[0]];
+    let locations: Vec<String> = def_indices.iter()
+        .map(|i| &index.definitions[*i])
+        .filter(|d| d.field_names() != first.field_names())
+        .map(|d| format!("{}:{}", d.file.to_string_lossy(), d.line))
+        .collect();
+    if locations.is_empty() {
+        return None;
+    }
+    Some(Diagnostic::new(
+        severity, "record_conflict",
+        format!("Record #{} is defined with different fields in {}; {}", name, locations.join(", "), context),
+        SourceSpan::line(&first.file, first.line)))
+}
+
+struct RecordUsageCollector<'a> {
+    unit: &'a CompileUnit,
+    usages: Vec<RecordUsage>,
+}
+
+impl<'a> RecordUsageCollector<'a> {
+    fn add<T: PositionRange>(&mut self, node: &T, record: &str, field: Option<&str>, value: Option<String>,
+                             kind: RecordUsageKind) {
+        self.usages.push(RecordUsage {
+            record: record.to_string(),
+            field: field.map(|f| f.to_string()),
+            value,
+            kind,
+            file: position_file(&node.start_position(), &self.unit.path),
+            line: node.start_position().line(),
+        });
+    }
+
+    /// Add a usage for the record and one for each field named in it; `_ = V` refers to all fields
+    fn add_fields<'f, T: PositionRange, I>(&mut self, node: &T, record: &str, kind: RecordUsageKind, fields: I)
+        where I: Iterator<Item=(Option<&'f str>, Option<String>)> {
+        self.add(node, record, None, None, kind);
+        for (field, value) in fields {
+            match field {
+                Some(field) => self.add(node, record, Some(field), value, kind),
+                None => self.add(node, record, None, None, RecordUsageKind::AllFields),
+            }
+        }
+    }
+}
+
+impl<'a> Visitor for RecordUsageCollector<'a> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Record(record) => {
+                let fields = record.fields.iter()
+                    .map(|f| (syntax::record_field_name(f), syntax::literal_text(&f.value)));
+                self.add_fields(expr, record.name.value(), RecordUsageKind::Construct, fields);
+            }
+            Expr::RecordUpdate(update) => {
+                let fields = update.update.fields.iter().map(|f| (syntax::record_field_name(f), None));
+                self.add_fields(expr, update.update.name.value(), RecordUsageKind::Update, fields);
+            }
+            Expr::RecordFieldAccess(access) => {
+                self.add(expr, access.index.record_name.value(), Some(access.index.field_name.value()), None,
+                         RecordUsageKind::Access);
+            }
+            Expr::RecordFieldIndex(index) => {
+                self.add(expr, index.record_name.value(), Some(index.field_name.value()), None,
+                         RecordUsageKind::Index);
+            }
+            Expr::LocalCall(call) if syntax::expr_atom(&call.func) == Some("record_info") => {
+                let args: Vec<&Expr> = call.args.iter().collect();
+                if let [_, name] = args.as_slice() {
+                    if let Some(record) = syntax::expr_atom(name) {
+                        self.add(expr, record, None, None, RecordUsageKind::AllFields);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Record(record) => {
+                let fields = record.fields.iter().map(|f| (syntax::record_field_name(f), None));
+                self.add_fields(pattern, record.name.value(), RecordUsageKind::Match, fields);
+            }
+            Pattern::RecordFieldIndex(index) => {
+                self.add(pattern, index.record_name.value(), Some(index.field_name.value()), None,
+                         RecordUsageKind::Index);
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_guard_test(&mut self, test: &GuardTest) {
+        match test {
+            GuardTest::Record(record) => {
+                let fields = record.fields.iter().map(|f| (syntax::record_field_name(f), None));
+                self.add_fields(test, record.name.value(), RecordUsageKind::Match, fields);
+            }
+            GuardTest::RecordFieldAccess(access) => {
+                self.add(test, access.index.record_name.value(), Some(access.index.field_name.value()), None,
+                         RecordUsageKind::Access);
+            }
+            GuardTest::RecordFieldIndex(index) => {
+                self.add(test, index.record_name.value(), Some(index.field_name.value()), None,
+                         RecordUsageKind::Index);
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_type(&mut self, ty: &Type) {
+        if let Type::Record(record) = ty {
+            self.add(ty, record.name.value(), None, None, RecordUsageKind::Type);
+        }
+    }
+}
@@ -0,0 +1,488 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use erl_parse::cst::building_blocks::{Body, Clauses, Guard, Sequence};
+use erl_parse::cst::clauses::FunClause;
+use erl_parse::cst::exprs::Qualifier;
+use erl_parse::cst::forms::FunDecl;
+use erl_parse::cst::{Expr, GuardTest, Pattern};
+use crate::diagnostic::SourceSpan;
+use crate::syntax;
+use crate::syntax::walk::{walk_guard, walk_guard_seq, walk_pattern, Visitor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeEventKind {
+    /// Variable is bound and never used
+    Unused,
+    /// `_Var` is used after it was bound
+    UnderscoreUsed,
+    /// Same variable appears twice in one pattern, which makes it an equality check
+    RepeatedInPattern,
+    /// Fun head or comprehension generator binds a new variable with the name of an outer one
+    Shadowed,
+    /// Variable bound in only some branches of a `case`/`if`/`receive`/`try`/`catch` is used after it
+    Unsafe,
+}
+
+/// A finding of the variable scope analysis
+#[derive(Debug, Clone)]
+pub struct ScopeEvent {
+    pub kind: ScopeEventKind,
+    pub name: String,
+    pub span: SourceSpan,
+    /// For unsafe variables, the kind of expression where the variable was bound
+    pub construct: Option<&'static str>,
+}
+
+/// How variables in a pattern are treated when a variable with the same name is already bound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatternMode {
+    /// Regular pattern: a bound variable is matched against its value
+    Match,
+    /// Fun heads and comprehension generators: all variables are new and hide the outer ones
+    Fresh,
+}
+
+struct Binding {
+    name: String,
+    span: SourceSpan,
+    used: bool,
+}
+
+/// A variable name visible at a point in the code. A name can have several bindings when it was bound in
+/// different branches of a `case`, all of them are considered used by a later use.
+#[derive(Debug, Clone, Default)]
+struct VarState {
+    ids: Vec<usize>,
+    /// Set when the variable was bound only in some branches, it can not be used after the branching expression
+    unsafe_in: Option<&'static str>,
+}
+
+impl VarState {
+    fn bound(id: usize) -> Self {
+        Self { ids: vec![id], unsafe_in: None }
+    }
+}
+
+type Env = HashMap<String, VarState>;
+
+/// Follows Erlang variable scoping through one function: clause heads, sequential bindings in bodies,
+/// branches of `case`/`if`/`receive`/`try`, and the separate scopes of funs and comprehensions.
+pub struct ScopeAnalyzer<'a> {
+    module_path: &'a Path,
+    bindings: Vec<Binding>,
+    events: Vec<ScopeEvent>,
+}
+
+/// Run the scope analysis for a function declaration
+pub fn analyze_function(decl: &FunDecl, module_path: &Path) -> Vec<ScopeEvent> {
+    let mut analyzer = ScopeAnalyzer { module_path, bindings: Vec::new(), events: Vec::new() };
+    for clause in decl.clauses.iter() {
+        let mut env = Env::new();
+        analyzer.fun_clause(&mut env, clause, PatternMode::Match);
+    }
+    analyzer.finish()
+}
+
+impl<'a> ScopeAnalyzer<'a> {
+    fn finish(mut self) -> Vec<ScopeEvent> {
+        for binding in self.bindings.iter().filter(|b| !b.used && !b.name.starts_with('_')) {
+            self.events.push(ScopeEvent {
+                kind: ScopeEventKind::Unused,
+                name: binding.name.clone(),
+                span: binding.span.clone(),
+                construct: None,
+            });
+        }
+        self.events
+    }
+
+    fn event(&mut self, kind: ScopeEventKind, name: &str, span: SourceSpan) {
+        self.events.push(ScopeEvent { kind, name: name.to_string(), span, construct: None });
+    }
+
+    fn fun_clause<N>(&mut self, env: &mut Env, clause: &FunClause<N>, mode: PatternMode) {
+        let patterns: Vec<&Pattern> = clause.patterns.iter().collect();
+        self.bind_patterns(env, &patterns, mode);
+        if let Some(guard) = &clause.guard {
+            self.guard(env, guard);
+        }
+        self.body(env, &clause.body);
+    }
+
+    /// Bind variables of one or more patterns which are matched together, like the arguments of a clause
+    fn bind_patterns(&mut self, env: &mut Env, patterns: &[&Pattern], mode: PatternMode) {
+        let mut collector = VarCollector::new(self.module_path);
+        for pattern in patterns {
+            walk_pattern(&mut collector, pattern);
+        }
+
+        let mut bound_here: HashMap<String, usize> = HashMap::new();
+        for (name, span) in collector.vars {
+            if name == "_" {
+                continue;
+            }
+            if let Some(id) = bound_here.get(&name) {
+                self.bindings[*id].used = true;
+                self.event(ScopeEventKind::RepeatedInPattern, &name, span);
+                continue;
+            }
+            if env.contains_key(&name) {
+                if mode == PatternMode::Match {
+                    self.use_var(env, &name, span);
+                    continue;
+                }
+                self.event(ScopeEventKind::Shadowed, &name, span.clone());
+            }
+            self.bindings.push(Binding { name: name.clone(), span, used: false });
+            bound_here.insert(name, self.bindings.len() - 1);
+        }
+        for (name, id) in bound_here {
+            env.insert(name, VarState::bound(id));
+        }
+        // Sizes can refer to variables bound earlier in the same pattern
+        for (name, span) in collector.uses {
+            self.use_var(env, &name, span);
+        }
+    }
+
+    fn use_var(&mut self, env: &Env, name: &str, span: SourceSpan) {
+        if name == "_" {
+            return;
+        }
+        if let Some(state) = env.get(name) {
+            for id in state.ids.iter() {
+                self.bindings[*id].used = true;
+            }
+            if let Some(construct) = state.unsafe_in {
+                self.events.push(ScopeEvent {
+                    kind: ScopeEventKind::Unsafe,
+                    name: name.to_string(),
+                    span: span.clone(),
+                    construct: Some(construct),
+                });
+            }
+            if name.starts_with('_') {
+                self.event(ScopeEventKind::UnderscoreUsed, name, span);
+            }
+        }
+    }
+
+    fn guard(&mut self, env: &Env, guard: &Guard) {
+        let mut collector = VarCollector::new(self.module_path);
+        walk_guard(&mut collector, guard);
+        for (name, span) in collector.vars {
+            self.use_var(env, &name, span);
+        }
+    }
+
+    fn guard_seq(&mut self, env: &Env, seq: &Clauses<Sequence<GuardTest>>) {
+        let mut collector = VarCollector::new(self.module_path);
+        walk_guard_seq(&mut collector, seq);
+        for (name, span) in collector.vars {
+            self.use_var(env, &name, span);
+        }
+    }
+
+    fn body(&mut self, env: &mut Env, body: &Body) {
+        for expr in body.exprs.iter() {
+            self.expr(env, expr);
+        }
+    }
+
+    /// Variables bound in any of the branches are visible after the branching expression.
+    /// Those not bound in every branch are unsafe to use after it.
+    fn merge_branches(&mut self, env: &mut Env, branches: Vec<Env>, construct: &'static str) {
+        let before: Vec<String> = env.keys().cloned().collect();
+        let branch_count = branches.len();
+        let mut seen_in: HashMap<String, usize> = HashMap::new();
+
+        for branch in branches {
+            for (name, state) in branch {
+                if before.contains(&name) {
+                    continue;
+                }
+                *seen_in.entry(name.clone()).or_default() += 1;
+                let merged = env.entry(name).or_default();
+                merged.unsafe_in = merged.unsafe_in.or(state.unsafe_in);
+                for id in state.ids {
+                    if !merged.ids.contains(&id) {
+                        merged.ids.push(id);
+                    }
+                }
+            }
+        }
+        for (name, count) in seen_in {
+            if count < branch_count {
+                if let Some(state) = env.get_mut(&name) {
+                    state.unsafe_in = Some(construct);
+                }
+            }
+        }
+    }
+
+    fn exprs<'e, I: Iterator<Item=&'e Expr>>(&mut self, env: &mut Env, exprs: I) {
+        for expr in exprs {
+            self.expr(env, expr);
+        }
+    }
+
+    fn expr(&mut self, env: &mut Env, expr: &Expr) {
+        match expr {
+            Expr::Var(var) => self.use_var(env, var.value(), SourceSpan::from_range(var, self.module_path)),
+            Expr::Literal(_) | Expr::LocalFun(_) | Expr::RemoteFun(_) | Expr::RecordFieldIndex(_) => {}
+            Expr::Tuple(tuple) => self.exprs(env, tuple.iter()),
+            Expr::Map(map) => {
+                for field in map.iter() {
+                    self.expr(env, &field.key);
+                    self.expr(env, &field.value);
+                }
+            }
+            Expr::MapUpdate(update) => {
+                self.expr(env, &update.map);
+                for field in update.update.iter() {
+                    self.expr(env, &field.key);
+                    self.expr(env, &field.value);
+                }
+            }
+            Expr::Record(record) => self.exprs(env, record.fields.iter().map(|f| &f.value)),
+            Expr::RecordUpdate(update) => {
+                self.expr(env, &update.record);
+                self.exprs(env, update.update.fields.iter().map(|f| &f.value));
+            }
+            Expr::RecordFieldAccess(access) => self.expr(env, &access.record),
+            Expr::List(list) => {
+                self.exprs(env, syntax::list_elements(list));
+                if let Some(tail) = syntax::list_tail(list) {
+                    self.expr(env, tail);
+                }
+            }
+            Expr::Bits(bits) => {
+                for elem in bits.iter() {
+                    self.expr(env, &elem.element);
+                    if let Some(size) = &elem.size {
+                        self.expr(env, &size.size);
+                    }
+                }
+            }
+            Expr::Parenthesized(inner) => self.expr(env, &inner.item),
+            Expr::LocalCall(call) => {
+                self.expr(env, &call.func);
+                self.exprs(env, call.args.iter());
+            }
+            Expr::RemoteCall(call) => {
+                self.expr(env, &call.module_name);
+                self.expr(env, &call.func);
+                self.exprs(env, call.args.iter());
+            }
+            Expr::UnaryOpCall(call) => self.expr(env, &call.operand),
+            Expr::BinaryOpCall(call) => {
+                self.expr(env, &call.left);
+                self.expr(env, &call.right);
+            }
+            Expr::Match(m) => {
+                self.expr(env, &m.right);
+                self.bind_patterns(env, &[&m.left], PatternMode::Match);
+            }
+            Expr::Block(block) => self.body(env, &block.body),
+            Expr::Catch(catch) => {
+                // An exception skips the rest of the expression, so nothing bound inside is safe after it
+                let mut branch = env.clone();
+                self.expr(&mut branch, &catch.expr);
+                let skipped = env.clone();
+                self.merge_branches(env, vec![branch, skipped], "catch");
+            }
+            Expr::If(if_expr) => {
+                let mut branches = Vec::new();
+                for clause in if_expr.clauses.iter() {
+                    let mut branch = env.clone();
+                    self.guard_seq(&branch, &clause.cond);
+                    self.body(&mut branch, &clause.body);
+                    branches.push(branch);
+                }
+                self.merge_branches(env, branches, "if");
+            }
+            Expr::Case(case) => {
+                self.expr(env, &case.expr);
+                let mut branches = Vec::new();
+                for clause in case.clauses.iter() {
+                    let mut branch = env.clone();
+                    self.bind_patterns(&mut branch, &[&clause.pattern], PatternMode::Match);
+                    if let Some(guard) = &clause.guard {
+                        self.guard(&branch, guard);
+                    }
+                    self.body(&mut branch, &clause.body);
+                    branches.push(branch);
+                }
+                self.merge_branches(env, branches, "case");
+            }
+            Expr::Receive(receive) => {
+                let mut branches = Vec::new();
+                for clause in receive.clauses.iter() {
+                    let mut branch = env.clone();
+                    self.bind_patterns(&mut branch, &[&clause.pattern], PatternMode::Match);
+                    if let Some(guard) = &clause.guard {
+                        self.guard(&branch, guard);
+                    }
+                    self.body(&mut branch, &clause.body);
+                    branches.push(branch);
+                }
+                if let Some(timeout) = &receive.timeout {
+                    let mut branch = env.clone();
+                    self.expr(&mut branch, &timeout.duration);
+                    self.body(&mut branch, &timeout.body);
+                    branches.push(branch);
+                }
+                self.merge_branches(env, branches, "receive");
+            }
+            Expr::Try(try_expr) => {
+                let mut branches = Vec::new();
+                let outer_names: HashSet<String> = env.keys().cloned().collect();
+                let mut body_env = env.clone();
+                self.body(&mut body_env, &try_expr.body);
+                match &try_expr.branch {
+                    Some(of) => {
+                        for clause in of.clauses.iter() {
+                            let mut branch = body_env.clone();
+                            self.bind_patterns(&mut branch, &[&clause.pattern], PatternMode::Match);
+                            if let Some(guard) = &clause.guard {
+                                self.guard(&branch, guard);
+                            }
+                            self.body(&mut branch, &clause.body);
+                            branches.push(branch);
+                        }
+                    }
+                    None => branches.push(body_env),
+                }
+                if let Some(catch) = &try_expr.catch {
+                    for clause in catch.clauses.iter() {
+                        let mut branch = env.clone();
+                        self.bind_patterns(&mut branch, &[&clause.pattern], PatternMode::Match);
+                        if let Some(guard) = &clause.guard {
+                            self.guard(&branch, guard);
+                        }
+                        self.body(&mut branch, &clause.body);
+                        branches.push(branch);
+                    }
+                }
+                if let Some(after) = &try_expr.after {
+                    // Bindings in `after` are not visible outside of it
+                    let mut after_env = env.clone();
+                    self.body(&mut after_env, &after.body);
+                }
+                self.merge_branches(env, branches, "try");
+                // Like erlc, every variable the try binds is unsafe after it, even when each of the of and catch
+                // clauses binds it: the body may have been left by an exception at any point
+                for (name, state) in env.iter_mut() {
+                    if !outer_names.contains(name) {
+                        state.unsafe_in = Some("try");
+                    }
+                }
+            }
+            Expr::AnonymousFun(fun) => {
+                for clause in fun.clauses.iter() {
+                    let mut clause_env = env.clone();
+                    self.fun_clause(&mut clause_env, clause, PatternMode::Fresh);
+                }
+            }
+            Expr::NamedFun(fun) => {
+                for clause in fun.clauses.iter() {
+                    let mut clause_env = env.clone();
+                    let name_span = SourceSpan::from_range(&clause.name, self.module_path);
+                    self.bind_name(&mut clause_env, clause.name.value(), name_span);
+                    self.fun_clause(&mut clause_env, clause, PatternMode::Fresh);
+                }
+            }
+            Expr::ListComprehension(comp) => {
+                let mut comp_env = env.clone();
+                self.qualifiers(&mut comp_env, &comp.qualifiers);
+                self.expr(&mut comp_env, &comp.element);
+            }
+            Expr::BitsComprehension(comp) => {
+                let mut comp_env = env.clone();
+                self.qualifiers(&mut comp_env, &comp.qualifiers);
+                for elem in comp.element.iter() {
+                    self.expr(&mut comp_env, &elem.element);
+                    if let Some(size) = &elem.size {
+                        self.expr(&mut comp_env, &size.size);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Name of a named fun, visible inside its own body. Recursion through it counts as a use.
+    fn bind_name(&mut self, env: &mut Env, name: &str, span: SourceSpan) {
+        self.bindings.push(Binding { name: name.to_string(), span, used: false });
+        env.insert(name.to_string(), VarState::bound(self.bindings.len() - 1));
+    }
+
+    fn qualifiers(&mut self, env: &mut Env, qualifiers: &Sequence<Qualifier>) {
+        for qualifier in qualifiers.iter() {
+            match qualifier {
+                Qualifier::Generator(gen) => {
+                    self.expr(env, &gen.expr);
+                    self.bind_patterns(env, &[&gen.pattern], PatternMode::Fresh);
+                }
+                Qualifier::BitsGenerator(gen) => {
+                    self.expr(env, &gen.expr);
+                    let patterns: Vec<&Pattern> = gen.pattern.iter().map(|elem| &elem.element).collect();
+                    self.bind_patterns(env, &patterns, PatternMode::Fresh);
+                    let sizes: Vec<&Pattern> = gen.pattern.iter().filter_map(|elem| elem.size.as_ref())
+                        .map(|size| &size.size)
+                        .collect();
+                    let mut collector = VarCollector::new(self.module_path);
+                    sizes.into_iter().for_each(|size| walk_pattern(&mut collector, size));
+                    for (name, span) in collector.vars.into_iter().chain(collector.uses) {
+                        self.use_var(env, &name, span);
+                    }
+                }
+                Qualifier::Filter(expr) => self.expr(env, expr),
+            }
+        }
+    }
+}
+
+/// Collects variable occurrences from patterns and guards, which cannot bind in nested scopes
+struct VarCollector<'a> {
+    module_path: &'a Path,
+    vars: Vec<(String, SourceSpan)>,
+    /// Variables in binary segment sizes and map keys of a pattern, these use a bound variable
+    uses: Vec<(String, SourceSpan)>,
+}
+
+impl<'a> VarCollector<'a> {
+    fn new(module_path: &'a Path) -> Self {
+        Self { module_path, vars: Vec::new(), uses: Vec::new() }
+    }
+
+    /// Move the variables of a size or key to `uses`, the walker visits them later as pattern variables
+    fn collect_uses(&mut self, pattern: &Pattern) {
+        let mut inner = VarCollector::new(self.module_path);
+        walk_pattern(&mut inner, pattern);
+        self.uses.extend(inner.vars.into_iter().chain(inner.uses));
+    }
+}
+
+impl<'a> Visitor for VarCollector<'a> {
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Var(var) => {
+                let span = SourceSpan::from_range(var, self.module_path);
+                if !self.uses.iter().any(|(_, used)| *used == span) {
+                    self.vars.push((var.value().to_string(), span));
+                }
+            }
+            Pattern::Bits(bits) => {
+                bits.iter().filter_map(|elem| elem.size.as_ref()).for_each(|size| self.collect_uses(&size.size));
+            }
+            Pattern::Map(map) => map.iter().for_each(|field| self.collect_uses(&field.key)),
+            _ => {}
+        }
+    }
+
+    fn visit_guard_test(&mut self, test: &GuardTest) {
+        if let GuardTest::Var(var) = test {
+            self.vars.push((var.value().to_string(), SourceSpan::from_range(var, self.module_path)));
+        }
+    }
+}
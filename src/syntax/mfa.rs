@@ -0,0 +1,63 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::error::IroncladError;
+
+/// Function name and arity, as written in `-export` and `-import` lists: `name/arity`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FunArity {
+    pub name: String,
+    pub arity: usize,
+}
+
+impl FunArity {
+    pub fn new(name: &str, arity: usize) -> Self {
+        Self { name: name.to_string(), arity }
+    }
+}
+
+impl Display for FunArity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.name, self.arity)
+    }
+}
+
+/// Fully qualified function reference: `module:name/arity`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MFArity {
+    pub module: String,
+    pub name: String,
+    pub arity: usize,
+}
+
+impl MFArity {
+    pub fn new(module: &str, name: &str, arity: usize) -> Self {
+        Self { module: module.to_string(), name: name.to_string(), arity }
+    }
+
+    /// Strip the module part
+    pub fn fun_arity(&self) -> FunArity {
+        FunArity::new(&self.name, self.arity)
+    }
+}
+
+impl Display for MFArity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}/{}", self.module, self.name, self.arity)
+    }
+}
+
+impl FromStr for MFArity {
+    type Err = IroncladError;
+
+    /// Parse a command line argument in form of `module:name/arity`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_input = || IroncladError::CommandLine(format!("Expected module:function/arity, got '{}'", s));
+        let (module, fun_arity) = s.split_once(':').ok_or_else(bad_input)?;
+        let (name, arity) = fun_arity.rsplit_once('/').ok_or_else(bad_input)?;
+        let arity = arity.parse::<usize>().map_err(|_| bad_input())?;
+        if module.is_empty() || name.is_empty() {
+            return Err(bad_input());
+        }
+        Ok(MFArity::new(module, name, arity))
+    }
+}
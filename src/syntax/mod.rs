@@ -0,0 +1,225 @@
+use std::collections::{HashMap, HashSet};
+use erl_parse::cst::commons::{BinaryOp, List, RecordField, UnaryOp};
+use erl_parse::cst::forms::{FunDecl, FunSpec};
+use erl_parse::cst::{Expr, Form, GuardTest, Literal};
+use erl_tokenize::tokens::IntegerToken;
+use crate::syntax::mfa::FunArity;
+
+pub mod mfa;
+pub mod usage;
+pub mod walk;
+
+/// Skip any number of `( )` around an expression
+pub fn unparenthesize(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Parenthesized(inner) => unparenthesize(&inner.item),
+        other => other,
+    }
+}
+
+/// Return the atom text if the expression is an atom literal
+pub fn expr_atom(expr: &Expr) -> Option<&str> {
+    match unparenthesize(expr) {
+        Expr::Literal(Literal::Atom(atom)) => Some(atom.value()),
+        _ => None,
+    }
+}
+
+/// Return the atom text if the guard expression is an atom literal
+pub fn guard_atom(test: &GuardTest) -> Option<&str> {
+    match test {
+        GuardTest::Literal(Literal::Atom(atom)) => Some(atom.value()),
+        GuardTest::Parenthesized(inner) => guard_atom(&inner.item),
+        _ => None,
+    }
+}
+
+/// Return the string contents if the expression is a string literal (adjacent strings are joined)
+pub fn expr_string(expr: &Expr) -> Option<String> {
+    match unparenthesize(expr) {
+        Expr::Literal(Literal::String(s)) => {
+            let mut result = s.head.value().to_string();
+            for piece in s.tail.iter() {
+                result.push_str(piece.value());
+            }
+            Some(result)
+        }
+        _ => None,
+    }
+}
+
+/// Source text of a literal value (atom, number, char, string, `[]`), used to compare values between expressions
+pub fn literal_text(expr: &Expr) -> Option<String> {
+    match unparenthesize(expr) {
+        Expr::Literal(Literal::Atom(token)) => Some(token.text().to_string()),
+        Expr::Literal(Literal::Integer(token)) => Some(token.text().to_string()),
+        Expr::Literal(Literal::Float(token)) => Some(token.text().to_string()),
+        Expr::Literal(Literal::Char(token)) => Some(token.text().to_string()),
+        Expr::Literal(Literal::String(_)) => expr_string(expr).map(|s| format!("{:?}", s)),
+        Expr::List(list) if list_tail(list).is_none() && list_elements(list).next().is_none() => {
+            Some("[]".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Integer literals which fit a `usize`, i.e. arities
+pub fn integer_value(token: &IntegerToken) -> Option<usize> {
+    token.text().parse::<usize>().ok()
+}
+
+/// Elements of a tuple expression
+pub fn tuple_elements(expr: &Expr) -> Option<Vec<&Expr>> {
+    match unparenthesize(expr) {
+        Expr::Tuple(tuple) => Some(tuple.iter().collect()),
+        _ => None,
+    }
+}
+
+/// Elements of a list before the `|` tail, if any
+pub fn list_elements<T>(list: &List<T>) -> impl Iterator<Item=&T> {
+    list.elements.iter().flat_map(|seq| seq.iter())
+}
+
+/// The tail of an improper list, after the `|`
+pub fn list_tail<T>(list: &List<T>) -> Option<&T> {
+    list.tail.as_ref().map(|tail| &tail.element)
+}
+
+/// Number of elements if the expression is a proper list literal, used to derive arity from `apply` args
+pub fn proper_list_len(expr: &Expr) -> Option<usize> {
+    match unparenthesize(expr) {
+        Expr::List(list) if list_tail(list).is_none() => Some(list_elements(list).count()),
+        _ => None,
+    }
+}
+
+/// Field name in `#rec{field = Value}`, `None` for the `_ = Value` catch-all
+pub fn record_field_name<T>(field: &RecordField<T>) -> Option<&str> {
+    field.key.as_atom().map(|atom| atom.value())
+}
+
+/// Operator as written in the source, like `"=:="` or `"andalso"`
+pub fn binary_op_text(op: &BinaryOp) -> &str {
+    op.text()
+}
+
+/// Unary operator as written in the source, like `"not"` or `"-"`
+pub fn unary_op_text(op: &UnaryOp) -> &str {
+    op.text()
+}
+
+/// A `name/arity` expression, as found in `-optional_callbacks` and `-deprecated` attribute values
+pub fn expr_fun_arity(expr: &Expr) -> Option<FunArity> {
+    match unparenthesize(expr) {
+        Expr::BinaryOpCall(call) if binary_op_text(&call.op) == "/" => {
+            let name = expr_atom(&call.left)?;
+            match unparenthesize(&call.right) {
+                Expr::Literal(Literal::Integer(arity)) => Some(FunArity::new(name, integer_value(arity)?)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Name and arity of a function declaration, taken from its first clause
+pub fn fun_decl_name(decl: &FunDecl) -> Option<FunArity> {
+    decl.clauses.iter().next()
+        .map(|clause| FunArity::new(clause.name.value(), clause.patterns.iter().count()))
+}
+
+/// Name and arity a `-spec` is written for, taken from its first clause. `-spec mod:name(...)` counts only
+/// when `mod` is the given module.
+pub fn fun_spec_name(spec: &FunSpec, module: &str) -> Option<FunArity> {
+    if let Some(prefix) = &spec.module {
+        if prefix.name.value() != module {
+            return None;
+        }
+    }
+    let arity = spec.clauses.iter().next()?.args.iter().count();
+    Some(FunArity::new(spec.fun_name.value(), arity))
+}
+
+/// Module name from the `-module()` attribute
+pub fn module_name(forms: &[Form]) -> Option<String> {
+    forms.iter().find_map(|form| match form {
+        Form::ModuleAttr(attr) => Some(attr.module_name.value().to_string()),
+        _ => None,
+    })
+}
+
+/// All functions declared in the module
+pub fn defined_functions(forms: &[Form]) -> HashSet<FunArity> {
+    forms.iter()
+        .filter_map(|form| match form {
+            Form::FunDecl(decl) => fun_decl_name(decl),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Functions listed in `-export()` attributes. Does not account for `-compile(export_all)`
+pub fn exported_functions(forms: &[Form]) -> HashSet<FunArity> {
+    let mut result = HashSet::new();
+    for form in forms {
+        if let Form::ExportAttr(attr) = form {
+            for export in attr.exports.iter() {
+                if let Some(arity) = integer_value(&export.arity) {
+                    result.insert(FunArity::new(export.name.value(), arity));
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Values of a custom attribute like `-behaviour(gen_server)` (also written as `-behavior`)
+pub fn wild_attributes<'a>(forms: &'a [Form], name: &'a str) -> impl Iterator<Item=&'a Expr> {
+    forms.iter().filter_map(move |form| match form {
+        Form::WildAttr(attr) if attr.attr_name.value() == name => Some(&attr.attr_value),
+        _ => None,
+    })
+}
+
+/// Elements of a list attribute value, or the value itself if it is not a list: `-compile(export_all)` and
+/// `-compile([export_all])` mean the same
+pub fn attribute_values(value: &Expr) -> Vec<&Expr> {
+    match unparenthesize(value) {
+        Expr::List(list) => list_elements(list).collect(),
+        other => vec![other],
+    }
+}
+
+/// Values of `-behaviour` and `-behavior` attributes
+pub fn behaviour_attributes(forms: &[Form]) -> impl Iterator<Item=&Expr> {
+    wild_attributes(forms, "behaviour").chain(wild_attributes(forms, "behavior"))
+}
+
+/// Behaviours named in `-behaviour` and `-behavior` attributes
+pub fn implemented_behaviours(forms: &[Form]) -> Vec<&str> {
+    behaviour_attributes(forms).filter_map(expr_atom).collect()
+}
+
+/// Whether the module has `-compile(export_all)`, which exports every function
+pub fn has_export_all(forms: &[Form]) -> bool {
+    wild_attributes(forms, "compile")
+        .flat_map(attribute_values)
+        .any(|value| expr_atom(value) == Some("export_all"))
+}
+
+/// Functions listed in `-import(Module, [...])` attributes, mapped to the module they are imported from
+pub fn imported_functions(forms: &[Form]) -> HashMap<FunArity, String> {
+    let mut result = HashMap::new();
+    for form in forms {
+        if let Form::ImportAttr(attr) = form {
+            for import in attr.imports.iter() {
+                if let Some(arity) = integer_value(&import.arity) {
+                    result.insert(FunArity::new(import.name.value(), arity),
+                                  attr.module_name.value().to_string());
+                }
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+use erl_parse::cst::{Expr, GuardTest, Pattern, Type};
+use erl_tokenize::PositionRange;
+use crate::project::compile_unit::CompileUnit;
+use crate::project::preprocessor_info::position_file;
+use crate::syntax::mfa::FunArity;
+use crate::syntax::walk::{walk_forms, Visitor};
+
+/// Records and types referenced by a module, with the file where each reference is written
+/// (the module or one of its headers).
+#[derive(Debug, Default)]
+pub struct NameUsage {
+    pub records: Vec<(String, PathBuf)>,
+    pub types: Vec<(FunArity, PathBuf)>,
+}
+
+impl NameUsage {
+    pub fn collect(unit: &CompileUnit) -> Self {
+        let mut collector = UsageCollector { unit, usage: NameUsage::default() };
+        walk_forms(&mut collector, &unit.forms);
+        collector.usage
+    }
+}
+
+struct UsageCollector<'a> {
+    unit: &'a CompileUnit,
+    usage: NameUsage,
+}
+
+impl<'a> UsageCollector<'a> {
+    fn add_record<T: PositionRange>(&mut self, name: &str, node: &T) {
+        let file = position_file(&node.start_position(), &self.unit.path);
+        self.usage.records.push((name.to_string(), file));
+    }
+}
+
+impl<'a> Visitor for UsageCollector<'a> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Record(record) => self.add_record(record.name.value(), expr),
+            Expr::RecordUpdate(update) => self.add_record(update.update.name.value(), expr),
+            Expr::RecordFieldAccess(access) => self.add_record(access.index.record_name.value(), expr),
+            Expr::RecordFieldIndex(index) => self.add_record(index.record_name.value(), expr),
+            _ => {}
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Record(record) => self.add_record(record.name.value(), pattern),
+            Pattern::RecordFieldIndex(index) => self.add_record(index.record_name.value(), pattern),
+            _ => {}
+        }
+    }
+
+    fn visit_guard_test(&mut self, test: &GuardTest) {
+        match test {
+            GuardTest::Record(record) => self.add_record(record.name.value(), test),
+            GuardTest::RecordFieldAccess(access) => self.add_record(access.index.record_name.value(), test),
+            GuardTest::RecordFieldIndex(index) => self.add_record(index.record_name.value(), test),
+            _ => {}
+        }
+    }
+
+    fn visit_type(&mut self, ty: &Type) {
+        match ty {
+            Type::Record(record) => self.add_record(record.name.value(), ty),
+            Type::LocalCall(call) => {
+                let file = position_file(&ty.start_position(), &self.unit.path);
+                let fun_arity = FunArity::new(call.name.value(), call.args.iter().count());
+                self.usage.types.push((fun_arity, file));
+            }
+            _ => {}
+        }
+    }
+}
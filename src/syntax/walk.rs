@@ -0,0 +1,333 @@
+use erl_parse::cst::building_blocks::{Body, Clauses, Guard, Sequence};
+use erl_parse::cst::clauses::{CaseClause, CatchClause, FunClause, SpecClause};
+use erl_parse::cst::exprs::Qualifier;
+use erl_parse::cst::{Expr, Form, GuardTest, Pattern, Type};
+use crate::syntax;
+
+/// Callbacks invoked by the `walk_*` functions for every node, parents before children.
+/// Implement only the hooks you need, the others default to doing nothing.
+pub trait Visitor {
+    fn visit_form(&mut self, _form: &Form) {}
+    fn visit_expr(&mut self, _expr: &Expr) {}
+    fn visit_pattern(&mut self, _pattern: &Pattern) {}
+    fn visit_guard_test(&mut self, _test: &GuardTest) {}
+    fn visit_type(&mut self, _ty: &Type) {}
+}
+
+pub fn walk_forms<V: Visitor + ?Sized>(v: &mut V, forms: &[Form]) {
+    for form in forms {
+        walk_form(v, form);
+    }
+}
+
+pub fn walk_form<V: Visitor + ?Sized>(v: &mut V, form: &Form) {
+    v.visit_form(form);
+    match form {
+        Form::FunDecl(decl) => {
+            for clause in decl.clauses.iter() {
+                walk_fun_clause(v, clause);
+            }
+        }
+        Form::FunSpec(spec) => {
+            for clause in spec.clauses.iter() {
+                walk_spec_clause(v, clause);
+            }
+        }
+        Form::CallbackSpec(spec) => {
+            for clause in spec.clauses.iter() {
+                walk_spec_clause(v, clause);
+            }
+        }
+        Form::RecordDecl(decl) => {
+            for field in decl.fields.iter() {
+                if let Some(default) = &field.field_default {
+                    walk_expr(v, &default.value);
+                }
+                if let Some(field_type) = &field.field_type {
+                    walk_type(v, &field_type.field_type);
+                }
+            }
+        }
+        Form::TypeDecl(decl) => walk_type(v, &decl.ty),
+        Form::WildAttr(attr) => walk_expr(v, &attr.attr_value),
+        _ => {}
+    }
+}
+
+pub fn walk_fun_clause<V: Visitor + ?Sized, N>(v: &mut V, clause: &FunClause<N>) {
+    for pattern in clause.patterns.iter() {
+        walk_pattern(v, pattern);
+    }
+    if let Some(guard) = &clause.guard {
+        walk_guard(v, guard);
+    }
+    walk_body(v, &clause.body);
+}
+
+pub fn walk_case_clause<V: Visitor + ?Sized>(v: &mut V, clause: &CaseClause) {
+    walk_pattern(v, &clause.pattern);
+    if let Some(guard) = &clause.guard {
+        walk_guard(v, guard);
+    }
+    walk_body(v, &clause.body);
+}
+
+pub fn walk_catch_clause<V: Visitor + ?Sized>(v: &mut V, clause: &CatchClause) {
+    walk_pattern(v, &clause.pattern);
+    if let Some(guard) = &clause.guard {
+        walk_guard(v, guard);
+    }
+    walk_body(v, &clause.body);
+}
+
+pub fn walk_spec_clause<V: Visitor + ?Sized>(v: &mut V, clause: &SpecClause) {
+    for arg in clause.args.iter() {
+        walk_type(v, arg);
+    }
+    walk_type(v, &clause.return_type);
+}
+
+pub fn walk_body<V: Visitor + ?Sized>(v: &mut V, body: &Body) {
+    for expr in body.exprs.iter() {
+        walk_expr(v, expr);
+    }
+}
+
+pub fn walk_guard<V: Visitor + ?Sized>(v: &mut V, guard: &Guard) {
+    walk_guard_seq(v, &guard.seq);
+}
+
+/// Guard sequence: alternatives separated by `;`, each is a list of tests separated by `,`
+pub fn walk_guard_seq<V: Visitor + ?Sized>(v: &mut V, seq: &Clauses<Sequence<GuardTest>>) {
+    for tests in seq.iter() {
+        for test in tests.iter() {
+            walk_guard_test(v, test);
+        }
+    }
+}
+
+pub fn walk_qualifiers<V: Visitor + ?Sized>(v: &mut V, qualifiers: &Sequence<Qualifier>) {
+    for qualifier in qualifiers.iter() {
+        match qualifier {
+            Qualifier::Generator(gen) => {
+                walk_pattern(v, &gen.pattern);
+                walk_expr(v, &gen.expr);
+            }
+            Qualifier::BitsGenerator(gen) => {
+                for elem in gen.pattern.iter() {
+                    walk_pattern(v, &elem.element);
+                }
+                walk_expr(v, &gen.expr);
+            }
+            Qualifier::Filter(expr) => walk_expr(v, expr),
+        }
+    }
+}
+
+pub fn walk_expr<V: Visitor + ?Sized>(v: &mut V, expr: &Expr) {
+    v.visit_expr(expr);
+    match expr {
+        Expr::Literal(_) | Expr::Var(_) | Expr::RecordFieldIndex(_) => {}
+        Expr::LocalFun(_) | Expr::RemoteFun(_) => {}
+        Expr::Tuple(tuple) => tuple.iter().for_each(|e| walk_expr(v, e)),
+        Expr::Map(map) => {
+            for field in map.iter() {
+                walk_expr(v, &field.key);
+                walk_expr(v, &field.value);
+            }
+        }
+        Expr::MapUpdate(update) => {
+            walk_expr(v, &update.map);
+            for field in update.update.iter() {
+                walk_expr(v, &field.key);
+                walk_expr(v, &field.value);
+            }
+        }
+        Expr::Record(record) => record.fields.iter().for_each(|f| walk_expr(v, &f.value)),
+        Expr::RecordUpdate(update) => {
+            walk_expr(v, &update.record);
+            update.update.fields.iter().for_each(|f| walk_expr(v, &f.value));
+        }
+        Expr::RecordFieldAccess(access) => walk_expr(v, &access.record),
+        Expr::List(list) => {
+            syntax::list_elements(list).for_each(|e| walk_expr(v, e));
+            if let Some(tail) = syntax::list_tail(list) {
+                walk_expr(v, tail);
+            }
+        }
+        Expr::ListComprehension(comp) => {
+            walk_expr(v, &comp.element);
+            walk_qualifiers(v, &comp.qualifiers);
+        }
+        Expr::Bits(bits) => {
+            for elem in bits.iter() {
+                walk_expr(v, &elem.element);
+                if let Some(size) = &elem.size {
+                    walk_expr(v, &size.size);
+                }
+            }
+        }
+        Expr::BitsComprehension(comp) => {
+            for elem in comp.element.iter() {
+                walk_expr(v, &elem.element);
+                if let Some(size) = &elem.size {
+                    walk_expr(v, &size.size);
+                }
+            }
+            walk_qualifiers(v, &comp.qualifiers);
+        }
+        Expr::Parenthesized(inner) => walk_expr(v, &inner.item),
+        Expr::LocalCall(call) => {
+            walk_expr(v, &call.func);
+            call.args.iter().for_each(|arg| walk_expr(v, arg));
+        }
+        Expr::RemoteCall(call) => {
+            walk_expr(v, &call.module_name);
+            walk_expr(v, &call.func);
+            call.args.iter().for_each(|arg| walk_expr(v, arg));
+        }
+        Expr::AnonymousFun(fun) => fun.clauses.iter().for_each(|c| walk_fun_clause(v, c)),
+        Expr::NamedFun(fun) => fun.clauses.iter().for_each(|c| walk_fun_clause(v, c)),
+        Expr::UnaryOpCall(call) => walk_expr(v, &call.operand),
+        Expr::BinaryOpCall(call) => {
+            walk_expr(v, &call.left);
+            walk_expr(v, &call.right);
+        }
+        Expr::Match(m) => {
+            walk_pattern(v, &m.left);
+            walk_expr(v, &m.right);
+        }
+        Expr::Block(block) => walk_body(v, &block.body),
+        Expr::Catch(catch) => walk_expr(v, &catch.expr),
+        Expr::If(if_expr) => {
+            for clause in if_expr.clauses.iter() {
+                walk_guard_seq(v, &clause.cond);
+                walk_body(v, &clause.body);
+            }
+        }
+        Expr::Case(case) => {
+            walk_expr(v, &case.expr);
+            case.clauses.iter().for_each(|c| walk_case_clause(v, c));
+        }
+        Expr::Receive(receive) => {
+            receive.clauses.iter().for_each(|c| walk_case_clause(v, c));
+            if let Some(timeout) = &receive.timeout {
+                walk_expr(v, &timeout.duration);
+                walk_body(v, &timeout.body);
+            }
+        }
+        Expr::Try(try_expr) => {
+            walk_body(v, &try_expr.body);
+            if let Some(branch) = &try_expr.branch {
+                branch.clauses.iter().for_each(|c| walk_case_clause(v, c));
+            }
+            if let Some(catch) = &try_expr.catch {
+                catch.clauses.iter().for_each(|c| walk_catch_clause(v, c));
+            }
+            if let Some(after) = &try_expr.after {
+                walk_body(v, &after.body);
+            }
+        }
+    }
+}
+
+pub fn walk_pattern<V: Visitor + ?Sized>(v: &mut V, pattern: &Pattern) {
+    v.visit_pattern(pattern);
+    match pattern {
+        Pattern::Tuple(tuple) => tuple.iter().for_each(|p| walk_pattern(v, p)),
+        Pattern::Map(map) => {
+            for field in map.iter() {
+                walk_pattern(v, &field.key);
+                walk_pattern(v, &field.value);
+            }
+        }
+        Pattern::Record(record) => record.fields.iter().for_each(|f| walk_pattern(v, &f.value)),
+        Pattern::List(list) => {
+            syntax::list_elements(list).for_each(|p| walk_pattern(v, p));
+            if let Some(tail) = syntax::list_tail(list) {
+                walk_pattern(v, tail);
+            }
+        }
+        Pattern::Bits(bits) => {
+            for elem in bits.iter() {
+                walk_pattern(v, &elem.element);
+                if let Some(size) = &elem.size {
+                    walk_pattern(v, &size.size);
+                }
+            }
+        }
+        Pattern::Parenthesized(inner) => walk_pattern(v, &inner.item),
+        Pattern::UnaryOpCall(call) => walk_pattern(v, &call.operand),
+        Pattern::BinaryOpCall(call) => {
+            walk_pattern(v, &call.left);
+            walk_pattern(v, &call.right);
+        }
+        Pattern::Match(m) => {
+            walk_pattern(v, &m.left);
+            walk_pattern(v, &m.right);
+        }
+        _ => {}
+    }
+}
+
+pub fn walk_guard_test<V: Visitor + ?Sized>(v: &mut V, test: &GuardTest) {
+    v.visit_guard_test(test);
+    match test {
+        GuardTest::Tuple(tuple) => tuple.iter().for_each(|t| walk_guard_test(v, t)),
+        GuardTest::Map(map) => {
+            for field in map.iter() {
+                walk_guard_test(v, &field.key);
+                walk_guard_test(v, &field.value);
+            }
+        }
+        GuardTest::Record(record) => record.fields.iter().for_each(|f| walk_guard_test(v, &f.value)),
+        GuardTest::RecordFieldAccess(access) => walk_guard_test(v, &access.record),
+        GuardTest::List(list) => {
+            syntax::list_elements(list).for_each(|t| walk_guard_test(v, t));
+            if let Some(tail) = syntax::list_tail(list) {
+                walk_guard_test(v, tail);
+            }
+        }
+        GuardTest::Bits(bits) => {
+            for elem in bits.iter() {
+                walk_guard_test(v, &elem.element);
+                if let Some(size) = &elem.size {
+                    walk_guard_test(v, &size.size);
+                }
+            }
+        }
+        GuardTest::Parenthesized(inner) => walk_guard_test(v, &inner.item),
+        GuardTest::LocalCall(call) => call.args.iter().for_each(|arg| walk_guard_test(v, arg)),
+        GuardTest::RemoteCall(call) => call.args.iter().for_each(|arg| walk_guard_test(v, arg)),
+        GuardTest::UnaryOpCall(call) => walk_guard_test(v, &call.operand),
+        GuardTest::BinaryOpCall(call) => {
+            walk_guard_test(v, &call.left);
+            walk_guard_test(v, &call.right);
+        }
+        _ => {}
+    }
+}
+
+pub fn walk_type<V: Visitor + ?Sized>(v: &mut V, ty: &Type) {
+    v.visit_type(ty);
+    match ty {
+        Type::Tuple(tuple) => tuple.iter().for_each(|t| walk_type(v, t)),
+        Type::List(list) => syntax::list_elements(list).for_each(|t| walk_type(v, t)),
+        Type::Parenthesized(inner) => walk_type(v, &inner.item),
+        Type::Annotated(annotated) => walk_type(v, &annotated.ty),
+        Type::LocalCall(call) => call.args.iter().for_each(|arg| walk_type(v, arg)),
+        Type::RemoteCall(call) => call.args.iter().for_each(|arg| walk_type(v, arg)),
+        Type::Union(union) => {
+            walk_type(v, &union.left);
+            walk_type(v, &union.right);
+        }
+        Type::Record(record) => record.fields.iter().for_each(|f| walk_type(v, &f.value)),
+        Type::Map(map) => {
+            for field in map.iter() {
+                walk_type(v, &field.key);
+                walk_type(v, &field.value);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([f/0]).
+-import(os, [cmd/1]).
+-define(LOG(X), io:format(X)).
+
+f() ->
+    ?LOG(\"hi\"),
+    cmd(\"ls\"),
+    io:fwrite(\"ok\").
+";
+
+const SCRIPT: &str = "-module(tool).
+-export([main/0]).
+
+main() ->
+    io:format(\"done\").
+";
+
+const ENTRIES: &str = "[[lints.banned_calls.entries]]
+calls = [\"io:format/1\"]
+except_paths = [\"scripts/**\"]
+message = \"Use logger\"
+[[lints.banned_calls.entries]]
+call = \"os:cmd\"
+";
+
+fn project(name: &str) -> TestProject {
+    TestProject::new(name)
+        .file("src/a.erl", MODULE)
+        .file("scripts/tool.erl", SCRIPT)
+        .input_paths(&["src", "scripts"])
+}
+
+#[test]
+fn nothing_is_banned_by_default() {
+    let output = project("banned-default").check();
+    assert!(output.findings("banned_calls").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn banned_calls_are_found_through_macros_and_imports() {
+    let output = project("banned").config(ENTRIES).check();
+    let findings = output.findings("banned_calls");
+    assert_eq!(findings.len(), 2, "{}", output.stdout);
+    assert!(findings.iter().any(|l| l.contains(": error [banned_calls] Call to io:format/1 is banned here: Use \
+                                               logger")), "{}", output.stdout);
+    assert!(findings.iter().any(|l| l.contains("src/a.erl:8:") && l.ends_with("Call to os:cmd/1 is banned here")),
+            "{}", output.stdout);
+    assert!(output.findings_in("banned_calls", "scripts/tool.erl").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn paths_limit_the_ban_to_matching_files() {
+    let output = project("banned-paths")
+        .config("[[lints.banned_calls.entries]]\ncall = \"io:format\"\npaths = [\"scripts/*.erl\"]\n")
+        .check();
+    let findings = output.findings("banned_calls");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("scripts/tool.erl:5:"), "{}", findings[0]);
+}
+
+#[test]
+fn apps_limit_the_ban_to_their_modules() {
+    let output = TestProject::new("banned-apps")
+        .file("billing/src/billing_api.erl", "-module(billing_api).\n-export([f/0]).\n\nf() ->\n    \
+                                              erlang:halt().\n")
+        .file("shop/src/shop_api.erl", "-module(shop_api).\n-export([f/0]).\n\nf() ->\n    erlang:halt().\n")
+        .input_paths(&["billing/src", "shop/src"])
+        .config("[[lints.banned_calls.entries]]\ncall = \"erlang:halt/0\"\napps = [\"billing\"]\n")
+        .check();
+    let findings = output.findings("banned_calls");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("billing/src/billing_api.erl:5:"), "{}", findings[0]);
+}
+
+#[test]
+fn bad_call_target_is_a_configuration_error() {
+    let output = project("banned-bad")
+        .config("[[lints.banned_calls.entries]]\ncall = \"io:format/x\"\n")
+        .check();
+    assert_eq!(output.status, 2);
+    assert!(output.stderr.contains("'io:format/x' must be module, module:function or module:function/arity"),
+            "{}", output.stderr);
+}
+
+#[test]
+fn call_and_message_of_the_wrong_type_are_configuration_errors() {
+    let output = project("banned-call-type")
+        .config("[[lints.banned_calls.entries]]\ncall = [\"io:format\"]\n")
+        .check();
+    assert_eq!(output.status, 2);
+    assert!(output.stderr.contains("lints.banned_calls.call must be a string"), "{}", output.stderr);
+
+    let output = project("banned-message-type")
+        .config("[[lints.banned_calls.entries]]\ncall = \"io:format\"\nmessage = 1\n")
+        .check();
+    assert_eq!(output.status, 2);
+    assert!(output.stderr.contains("lints.banned_calls.message must be a string"), "{}", output.stderr);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([f/1]).
+
+f(X) ->
+    catch X.
+";
+
+#[test]
+fn check_with_findings_exits_with_failure() {
+    let output = TestProject::new("exit-code").file("src/a.erl", MODULE).check();
+    assert_eq!(output.findings("old_style_catch").len(), 1, "{}", output.stdout);
+    assert_eq!(output.status, 1);
+}
+
+#[test]
+fn baseline_hides_accepted_findings() {
+    let project = TestProject::new("baseline").file("src/a.erl", MODULE);
+    let written = project.run(&["baseline", "write"]);
+    assert!(written.stdout.starts_with("Baseline with "), "{}", written.stdout);
+    assert!(written.stdout.trim_end().ends_with("findings written to ironclad-baseline.toml"), "{}", written.stdout);
+    assert!(project.read("ironclad-baseline.toml").contains("rule = \"old_style_catch\""));
+
+    let output = project.run(&["check", "--baseline"]);
+    assert_eq!(output.stdout, "");
+    assert_eq!(output.status, 0);
+}
+
+#[test]
+fn baseline_survives_moved_code_and_reports_new_findings() {
+    let project = TestProject::new("baseline-new").file("src/a.erl", MODULE);
+    project.run(&["baseline", "write", "--file", "accepted.toml"]);
+
+    let project = project.file("src/a.erl", &format!("%% Comment which moves the code down\n{}\ng(Y) ->\n    \
+                                                       catch Y.\n", MODULE.replace("[f/1]", "[f/1, g/1]")));
+    let output = project.run(&["check", "--baseline", "accepted.toml"]);
+    assert_eq!(output.lines_of("old_style_catch"), vec![9], "{}", output.stdout);
+    assert!(!output.stdout.contains("Fixed since the baseline"), "{}", output.stdout);
+    assert_eq!(output.status, 1);
+}
+
+#[test]
+fn fixed_baseline_entries_are_listed() {
+    let project = TestProject::new("baseline-fixed").file("src/a.erl", MODULE);
+    project.run(&["baseline", "write"]);
+
+    let project = project.file("src/a.erl", &MODULE.replace("catch X.", "X."));
+    let output = project.run(&["check", "--baseline"]);
+    assert!(output.stdout.contains("Fixed since the baseline was written (1):\n  a:f/1 [old_style_catch] "),
+            "{}", output.stdout);
+    assert_eq!(output.status, 0);
+}
+
+#[test]
+fn missing_baseline_file_is_an_error() {
+    let output = TestProject::new("baseline-missing").file("src/a.erl", MODULE)
+        .run(&["check", "--baseline", "nothing.toml"]);
+    assert_eq!(output.status, 2);
+}
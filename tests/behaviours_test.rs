@@ -0,0 +1,123 @@
+mod common;
+
+use common::TestProject;
+
+const SERVER: &str = "-module(server).
+-behaviour(gen_server).
+-export([init/1, handle_call/3, handle_cats/2, terminate/3]).
+
+init(Args) ->
+    {ok, Args}.
+
+handle_call(_Request, _From, State) ->
+    {reply, ok, State}.
+
+handle_cats(_Msg, State) ->
+    {noreply, State}.
+
+terminate(_Reason, _State, _Extra) ->
+    ok.
+";
+
+const COMPLETE: &str = "-module(complete).
+-behavior(supervisor).
+-export([init/1]).
+
+init(_Args) ->
+    {ok, {#{}, []}}.
+";
+
+const PLUGIN: &str = "-module(plugin).
+-callback handle(Event :: term()) -> ok.
+-callback describe() -> string().
+-optional_callbacks([describe/0]).
+";
+
+#[test]
+fn missing_required_callback_is_an_error() {
+    let output = TestProject::new("missing-callback").file("src/server.erl", SERVER).check();
+    let findings = output.findings("missing_callback");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/server.erl:2:"), "{}", findings[0]);
+    assert!(findings[0].contains(": error [missing_callback] Callback handle_cast/2 of behaviour gen_server is not \
+                                  exported by server"), "{}", findings[0]);
+}
+
+#[test]
+fn misspelled_callback_is_reported() {
+    let output = TestProject::new("misspelled-callback").file("src/server.erl", SERVER).check();
+    let findings = output.findings("misspelled_callback");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].ends_with("Exported handle_cats/2 looks like a misspelled callback handle_cast/2 of \
+                                   behaviour gen_server"), "{}", findings[0]);
+}
+
+#[test]
+fn optional_callbacks_are_reported_only_when_exported_with_other_arity() {
+    let output = TestProject::new("optional-callback").file("src/server.erl", SERVER).check();
+    let findings = output.findings("optional_callback");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains(": warning [optional_callback] Optional callback terminate/2 "), "{}", findings[0]);
+    assert!(findings[0].ends_with("(exported as terminate/3 instead)"), "{}", findings[0]);
+}
+
+#[test]
+fn one_arity_of_an_alternative_callback_is_enough() {
+    let output = TestProject::new("alternative-callback")
+        .file("src/server.erl", "-module(server).
+-behaviour(gen_server).
+-export([init/1, handle_call/3, handle_cast/2, format_status/2]).
+
+init(Args) ->
+    {ok, Args}.
+
+handle_call(_Request, _From, State) ->
+    {reply, ok, State}.
+
+handle_cast(_Msg, State) ->
+    {noreply, State}.
+
+format_status(_Opt, [_PDict, State]) ->
+    State.
+")
+        .check();
+    for code in ["missing_callback", "optional_callback", "misspelled_callback", "unknown_behaviour"] {
+        assert!(output.findings(code).is_empty(), "{}", output.stdout);
+    }
+}
+
+#[test]
+fn complete_implementation_has_no_findings() {
+    let output = TestProject::new("complete-behaviour").file("src/complete.erl", COMPLETE).check();
+    for code in ["missing_callback", "optional_callback", "misspelled_callback", "unknown_behaviour"] {
+        assert!(output.findings(code).is_empty(), "{}", output.stdout);
+    }
+}
+
+#[test]
+fn project_behaviour_callbacks_are_checked() {
+    let output = TestProject::new("project-behaviour")
+        .file("src/plugin.erl", PLUGIN)
+        .file("src/impl.erl", "-module(impl).\n-behaviour(plugin).\n-export([describe/0]).\n\n\
+                               describe() ->\n    \"impl\".\n")
+        .file("src/full.erl", "-module(full).\n-behaviour(plugin).\n-export([handle/1]).\n\n\
+                               handle(_Event) ->\n    ok.\n")
+        .check();
+    let missing = output.findings("missing_callback");
+    assert_eq!(missing.len(), 1, "{}", output.stdout);
+    assert!(missing[0].ends_with("Callback handle/1 of behaviour plugin is not exported by impl"), "{}", missing[0]);
+
+    assert!(output.findings("optional_callback").is_empty(), "{}", output.stdout);
+    assert!(output.findings("unknown_behaviour").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn unknown_behaviour_is_reported() {
+    let output = TestProject::new("unknown-behaviour")
+        .file("src/a.erl", "-module(a).\n-behaviour(gen_srever).\n")
+        .check();
+    let findings = output.findings("unknown_behaviour");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].ends_with("Behaviour gen_srever is not an OTP behaviour and not found in the project"),
+            "{}", findings[0]);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::TestProject;
+
+const CALLER: &str = "-module(a).
+-export([start/0]).
+-import(b, [helper/1]).
+-define(B, b).
+
+start() ->
+    run(1),
+    helper(2),
+    ?B:other(),
+    F = fun b:other/0,
+    F(),
+    spawn(b, worker, [3]),
+    apply(b, helper, [4]).
+
+run(X) ->
+    b:helper(X).
+";
+
+const CALLEE: &str = "-module(b).
+-export([helper/1, other/0, worker/1]).
+
+helper(X) ->
+    other(),
+    X.
+
+other() ->
+    ok.
+
+worker(X) ->
+    X.
+";
+
+fn project(name: &str) -> TestProject {
+    TestProject::new(name).file("src/a.erl", CALLER).file("src/b.erl", CALLEE)
+}
+
+#[test]
+fn callers_include_imports_and_apply() {
+    let output = project("callers").run(&["callers", "b:helper/1"]);
+    let lines: Vec<&str> = output.stdout.lines().collect();
+    assert!(lines.iter().any(|l| l.starts_with("a:start/0 -> b:helper/1 (imported, ") && l.ends_with("a.erl:8")),
+            "{}", output.stdout);
+    assert!(lines.iter().any(|l| l.starts_with("a:start/0 -> b:helper/1 (apply, ")), "{}", output.stdout);
+    assert!(lines.iter().any(|l| l.starts_with("a:run/1 -> b:helper/1 (remote, ")), "{}", output.stdout);
+    assert_eq!(lines.len(), 3, "{}", output.stdout);
+}
+
+#[test]
+fn callers_of_uncalled_function_are_empty() {
+    let output = project("callers-none").run(&["callers", "a:start/0"]);
+    assert_eq!(output.stdout, "");
+    assert_eq!(output.status, 0);
+}
+
+#[test]
+fn callees_include_locals_macros_funs_and_spawn() {
+    let output = project("callees").run(&["callees", "a:start/0"]);
+    for expected in ["-> a:run/1 (local, ", "-> b:other/0 (remote, ", "-> b:other/0 (fun, ", "-> b:worker/1 (spawn, "] {
+        assert!(output.stdout.contains(expected), "missing '{}' in:\n{}", expected, output.stdout);
+    }
+    // Calling a variable has no known callee
+    assert!(!output.stdout.contains("-> a:F/0"), "{}", output.stdout);
+}
+
+#[test]
+fn path_follows_calls_across_modules() {
+    let output = project("path").run(&["path", "a:start/0", "b:other/0"]);
+    assert_eq!(output.stdout.lines().count(), 1, "{}", output.stdout);
+    assert!(output.stdout.starts_with("a:start/0 -> b:other/0 "), "{}", output.stdout);
+
+    let output = project("path-hops").run(&["path", "a:run/1", "b:other/0"]);
+    let lines: Vec<&str> = output.stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", output.stdout);
+    assert!(lines[0].starts_with("a:run/1 -> b:helper/1 (remote, "), "{}", output.stdout);
+    assert!(lines[1].starts_with("b:helper/1 -> b:other/0 (local, "), "{}", output.stdout);
+}
+
+#[test]
+fn path_against_call_direction_is_not_found() {
+    let output = project("path-none").run(&["path", "b:other/0", "a:start/0"]);
+    assert_eq!(output.stdout.trim(), "No call path from b:other/0 to a:start/0");
+}
+
+#[test]
+fn bad_mfa_argument_fails() {
+    let output = project("bad-mfa").run(&["callers", "b:helper"]);
+    assert_ne!(output.status, 0);
+    assert_eq!(output.stdout, "");
+}
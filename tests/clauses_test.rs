@@ -0,0 +1,75 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([f/1, g/2, h/2, k/1, m/1, n/1, p/1, q/1]).
+
+f(X) -> X;
+f(1) -> one.
+
+g(X, _) when X > 0 -> first;
+g(_, X) when X > 0 -> second;
+g(X, _) when X > 0 -> third.
+
+h(A, B) ->
+    case A of
+        {ok, V} -> V;
+        {ok, V} -> V;
+        B -> B;
+        _ -> other;
+        error -> error
+    end.
+
+k(X) ->
+    try X() of
+        ok -> ok;
+        Other -> Other
+    catch
+        Class:Reason -> {Class, Reason};
+        error:badarg -> badarg
+    end.
+
+m(L) ->
+    lists:map(fun(_) -> a; (x) -> b end, L).
+
+n([H | _]) -> H;
+n([H, _]) -> H;
+n([]) -> none.
+
+p(1) -> one.
+
+q(X) -> p(X).
+
+p(2) -> two.
+";
+
+#[test]
+fn shadowed_clauses_are_unreachable() {
+    let output = TestProject::new("clauses-unreachable").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("unreachable_clause"), vec![5, 9, 17, 26, 30, 33], "{}", output.stdout);
+    let unreachable = output.findings("unreachable_clause");
+    assert!(unreachable[0].contains("This function clause can never match, the clause on line 4 already matches"),
+            "{}", output.stdout);
+    assert!(unreachable[1].contains("the clause on line 7"), "{}", output.stdout);
+    assert!(unreachable[2].contains("This case clause can never match, the clause on line 16"), "{}", output.stdout);
+    assert!(unreachable[3].contains("This catch clause can never match, the clause on line 25"), "{}", output.stdout);
+    assert!(unreachable[4].contains("This fun clause can never match"), "{}", output.stdout);
+    assert!(unreachable[5].contains("the clause on line 32"), "{}", output.stdout);
+}
+
+#[test]
+fn repeated_clauses_are_duplicates() {
+    let output = TestProject::new("clauses-duplicate").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("duplicate_clause"), vec![14], "{}", output.stdout);
+    assert!(output.findings("duplicate_clause")[0].contains("This case clause duplicates the clause on line 13"),
+            "{}", output.stdout);
+}
+
+#[test]
+fn clauses_split_by_other_forms_are_reported() {
+    let output = TestProject::new("clauses-separated").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("separated_clauses"), vec![40], "{}", output.stdout);
+    assert!(output.findings("separated_clauses")[0].contains("Clauses of p/1 are separated by other forms, the first \
+                                                              ones start on line 36"), "{}", output.stdout);
+}
@@ -0,0 +1,105 @@
+//! Runs the `ironclad` binary on small projects written to a temporary directory
+
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// A project directory with an `ironclad.toml` scanning `src` for modules
+pub struct TestProject {
+    pub root: PathBuf,
+    input_paths: Vec<String>,
+    lints: String,
+}
+
+/// Result of one `ironclad` run
+pub struct RunOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub status: i32,
+}
+
+impl TestProject {
+    /// Empty project, `name` must be unique among the tests as they run in parallel
+    pub fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("ironclad-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        Self { root, input_paths: vec!["src".to_string()], lints: String::new() }
+    }
+
+    /// Write a file, `path` is relative to the project root
+    pub fn file(self, path: &str, contents: &str) -> Self {
+        let path = self.root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+        self
+    }
+
+    /// Directories to scan instead of `src`, relative to the project root
+    pub fn input_paths(mut self, paths: &[&str]) -> Self {
+        self.input_paths = paths.iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    /// Append TOML to the project file, for `[lints.*]` tables
+    pub fn config(mut self, toml: &str) -> Self {
+        self.lints.push_str(toml);
+        self.lints.push('\n');
+        self
+    }
+
+    pub fn read(&self, path: &str) -> String {
+        std::fs::read_to_string(self.root.join(path)).unwrap()
+    }
+
+    /// Run `ironclad --config ironclad.toml <args>` in the project directory
+    pub fn run(&self, args: &[&str]) -> RunOutput {
+        let input_paths: Vec<String> = self.input_paths.iter().map(|p| format!("{:?}", p)).collect();
+        let project_file = format!("[compiler_options]\ninput_paths = [{}]\ninput_masks = [\"*.erl\"]\n\n{}",
+                                   input_paths.join(", "), self.lints);
+        std::fs::write(self.root.join("ironclad.toml"), project_file).unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_ironclad"))
+            .current_dir(&self.root)
+            .args(["--config", "ironclad.toml"])
+            .args(args)
+            .output()
+            .unwrap();
+        RunOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            status: output.status.code().unwrap_or(-1),
+        }
+    }
+
+    pub fn check(&self) -> RunOutput {
+        self.run(&["check"])
+    }
+}
+
+impl Drop for TestProject {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+impl RunOutput {
+    /// Diagnostic lines with the given code
+    pub fn findings(&self, code: &str) -> Vec<&str> {
+        let tag = format!("[{}]", code);
+        self.stdout.lines().filter(|line| line.contains(&tag)).collect()
+    }
+
+    /// Diagnostic lines with the given code in a file, `file` is the path ending like `src/a.erl`
+    pub fn findings_in(&self, code: &str, file: &str) -> Vec<&str> {
+        self.findings(code).into_iter().filter(|line| line.contains(&format!("{}:", file))).collect()
+    }
+
+    /// Line numbers of the findings with the given code
+    pub fn lines_of(&self, code: &str) -> Vec<usize> {
+        self.findings(code).iter()
+            .filter_map(|line| line.split(':').nth(1))
+            .filter_map(|n| n.parse().ok())
+            .collect()
+    }
+}
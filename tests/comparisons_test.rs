@@ -0,0 +1,122 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([f/2, g/2, h/1, i/1, j/2, k/1, l/1]).
+
+f(A, B) ->
+    A and B.
+
+g(A, B) when A or B ->
+    A and B =:= false.
+
+h(X) ->
+    X == 1.0.
+
+i(X) when X /= (2.5) ->
+    X.
+
+j(X, L) ->
+    {X =:= ok, length(L) == undefined, 1 =:= 1.0, [] < a}.
+
+k(X) ->
+    case X of true -> true; false -> false end.
+
+l(X) ->
+    case X > 0 of
+        true -> false;
+        false -> true
+    end.
+";
+
+const FIXED: &str = "-module(a).
+-export([f/2, g/2, h/1, i/1, j/2, k/1, l/1]).
+
+f(A, B) ->
+    A andalso B.
+
+g(A, B) when A orelse B ->
+    (A andalso B) =:= false.
+
+h(X) ->
+    X == 1.0.
+
+i(X) when X /= (2.5) ->
+    X.
+
+j(X, L) ->
+    {X =:= ok, length(L) == undefined, false, false}.
+
+k(X) ->
+    X.
+
+l(X) ->
+    not (X > 0).
+";
+
+const FINE: &str = "-module(a).
+-export([f/2, g/1, h/1]).
+
+f(A, B) when A andalso B ->
+    A orelse B.
+
+g(X) ->
+    {X =:= 1.0, X == 1}.
+
+h(X) ->
+    case X of
+        true -> yes;
+        false -> no
+    end.
+";
+
+#[test]
+fn comparison_problems_are_reported() {
+    let output = TestProject::new("comparisons").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("strict_boolean_operator"), vec![5, 7, 8], "{}", output.stdout);
+    assert!(output.findings("strict_boolean_operator")[0].contains("`and` evaluates both operands, use `andalso`"),
+            "{}", output.stdout);
+    assert!(output.findings("strict_boolean_operator")[1].contains("`or` evaluates both operands, use `orelse`"),
+            "{}", output.stdout);
+
+    assert_eq!(output.lines_of("float_equality"), vec![11, 13], "{}", output.stdout);
+    let floats = output.findings("float_equality");
+    assert!(floats[0].contains("== compares a float with numeric conversion, so 1 == 1.0. Use =:= for an exact \
+                                comparison"), "{}", output.stdout);
+    assert!(floats[1].contains("Use =/= for an exact comparison"), "{}", output.stdout);
+
+    assert_eq!(output.lines_of("constant_comparison"), vec![17, 17, 17], "{}", output.stdout);
+    let constants = output.findings("constant_comparison");
+    assert!(constants[0].contains("Comparing an integer with an atom using == is always false"), "{}", output.stdout);
+    assert!(constants[1].contains("Comparing an integer with a float using =:= is always false"), "{}", output.stdout);
+    assert!(constants[2].contains("Comparing [] with an atom using < is always false"), "{}", output.stdout);
+
+    assert_eq!(output.lines_of("redundant_boolean_case"), vec![20, 23], "{}", output.stdout);
+    let cases = output.findings("redundant_boolean_case");
+    assert!(cases[0].contains("This case returns the boolean it matches on"), "{}", output.stdout);
+    assert!(cases[1].contains("This case negates a boolean, use `not`"), "{}", output.stdout);
+}
+
+#[test]
+fn fix_rewrites_operators_constants_and_boolean_cases() {
+    let project = TestProject::new("comparisons-fix").file("src/a.erl", MODULE);
+    let output = project.run(&["check", "--fix"]);
+    assert!(output.stdout.contains("Applied 7 fixes to 1 files"), "{}", output.stdout);
+    assert_eq!(project.read("src/a.erl"), FIXED);
+
+    // Neither the float comparisons nor the comparison dropping the length/1 call are fixed
+    let output = project.check();
+    assert_eq!(output.lines_of("float_equality"), vec![11, 13], "{}", output.stdout);
+    assert_eq!(output.lines_of("constant_comparison"), vec![17], "{}", output.stdout);
+    assert!(output.findings("strict_boolean_operator").is_empty(), "{}", output.stdout);
+    assert!(output.findings("redundant_boolean_case").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn exact_comparisons_and_short_circuit_operators_have_no_findings() {
+    let output = TestProject::new("comparisons-fine").file("src/a.erl", FINE).check();
+    for code in ["strict_boolean_operator", "float_equality", "constant_comparison", "redundant_boolean_case"] {
+        assert!(output.findings(code).is_empty(), "{}", output.stdout);
+    }
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::TestProject;
+
+const CALLER: &str = "-module(a).
+-export([f/0]).
+
+f() ->
+    T = erlang:now(),
+    S = erlang:get_stacktrace(),
+    R = random:uniform(),
+    P = pg2:create(g),
+    old:api(1),
+    old:other(),
+    {T, S, R, P}.
+";
+
+const OLD: &str = "-module(old).
+-export([api/1, other/0, internal/0]).
+-deprecated([{api, 1, \"use new:api/1\"}]).
+
+api(X) ->
+    X.
+
+other() ->
+    ok.
+
+internal() ->
+    api(2).
+";
+
+fn project(name: &str) -> TestProject {
+    TestProject::new(name).file("src/a.erl", CALLER).file("src/old.erl", OLD)
+}
+
+#[test]
+fn deprecated_otp_and_project_functions_are_reported() {
+    let output = project("deprecated").check();
+    let findings = output.findings("deprecated_function");
+    assert_eq!(output.lines_of("deprecated_function"), vec![5, 6, 7, 8, 9], "{}", output.stdout);
+    assert!(findings[0].ends_with("erlang:now/0 is deprecated since OTP 18, use erlang:monotonic_time/0, \
+                                   erlang:system_time/0 or erlang:timestamp/0"), "{}", findings[0]);
+    assert!(findings[1].ends_with("erlang:get_stacktrace/0 is deprecated since OTP 21 and removed in OTP 24, use \
+                                   the Class:Reason:Stacktrace pattern in catch clauses"), "{}", findings[1]);
+    assert!(findings[2].ends_with("random:uniform/0 is deprecated since OTP 19, use the rand module"),
+            "{}", findings[2]);
+    assert!(findings[4].ends_with("old:api/1 is deprecated: use new:api/1"), "{}", findings[4]);
+    assert!(output.findings("removed_function").is_empty(), "{}", output.stdout);
+    // Calls inside the deprecated module are fine
+    assert!(output.findings_in("deprecated_function", "src/old.erl").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn functions_removed_in_the_target_release_are_errors() {
+    let output = project("removed").config("target_otp = 24").check();
+    let removed = output.findings("removed_function");
+    assert_eq!(output.lines_of("removed_function"), vec![6, 8], "{}", output.stdout);
+    assert!(removed[0].contains(": error [removed_function] erlang:get_stacktrace/0 was removed in OTP 24, use "),
+            "{}", removed[0]);
+    assert!(removed[1].contains("pg2:create/1 was removed in OTP 24, use the pg module"), "{}", removed[1]);
+    assert_eq!(output.lines_of("deprecated_function"), vec![5, 7, 9], "{}", output.stdout);
+}
+
+#[test]
+fn functions_deprecated_after_the_target_release_are_not_reported() {
+    let output = project("older-target").config("target_otp = 18").check();
+    assert_eq!(output.lines_of("deprecated_function"), vec![5, 9], "{}", output.stdout);
+    assert!(output.findings("removed_function").is_empty(), "{}", output.stdout);
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([f/1, g/1, h/1, i/1, j/1, k/1]).
+
+f(X) ->
+    try X()
+    catch
+        _:_ -> ok
+    end.
+
+g(X) ->
+    try X()
+    catch
+        _ -> ok
+    end.
+
+h(X) ->
+    try X()
+    catch
+        _:_:_ -> logger:error(\"failed\")
+    end.
+
+i(X) ->
+    try X() of
+        {ok, V} -> V
+    catch
+        error:badarg -> erlang:error(bad_input);
+        Class:Reason -> erlang:raise(Class, Reason, [])
+    end.
+
+j(X) ->
+    case catch X() of
+        {'EXIT', _} -> erlang:get_stacktrace();
+        Result -> Result
+    end.
+
+k(X) ->
+    try X() of
+        ok -> ok
+    catch
+        throw:Reason -> Reason
+    end.
+";
+
+#[test]
+fn ignored_exceptions_are_swallowed() {
+    let output = TestProject::new("exceptions-swallowed").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("swallowed_exception"), vec![7, 13], "{}", output.stdout);
+    let swallowed = output.findings("swallowed_exception");
+    assert!(swallowed[0].contains("`catch _:_ ->` discards the exception without logging or rethrowing it"),
+            "{}", output.stdout);
+    assert!(swallowed[1].contains("`catch _ ->` discards the exception"), "{}", output.stdout);
+}
+
+#[test]
+fn catch_all_next_to_of_is_reported() {
+    let output = TestProject::new("exceptions-try-of").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("try_of_catch_all"), vec![27], "{}", output.stdout);
+    assert!(output.findings("try_of_catch_all")[0].contains("exceptions raised in the of clauses propagate"),
+            "{}", output.stdout);
+}
+
+#[test]
+fn old_catch_and_get_stacktrace_are_reported() {
+    let output = TestProject::new("exceptions-old").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("old_style_catch"), vec![31], "{}", output.stdout);
+    assert_eq!(output.lines_of("get_stacktrace"), vec![32], "{}", output.stdout);
+    assert!(output.findings("get_stacktrace")[0].contains("returns [] since OTP 23 and was removed in OTP 24"),
+            "{}", output.stdout);
+}
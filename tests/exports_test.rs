@@ -0,0 +1,76 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([f/1, f/3, g/0]).
+-export([f/1, h/1, member/2, reverse/1]).
+-export_type([t/0, u/1, u/0]).
+-import(lists, [member/2, reverse/1]).
+
+-type t() :: integer().
+-type u(T) :: [T].
+
+f(X) -> X.
+
+g() -> ok.
+
+reverse(L) -> L.
+";
+
+const EXPORT_ALL: &str = "-compile([export_all, nowarn_export_all]).
+-export([f/0]).
+
+f() -> ok.
+";
+
+fn export_all_project(name: &str) -> TestProject {
+    TestProject::new(name)
+        .file("src/b.erl", &format!("-module(b).\n{}", EXPORT_ALL))
+        .file("src/b_SUITE.erl", &format!("-module(b_SUITE).\n{}", EXPORT_ALL))
+        .file("src/b_tests.erl", &format!("-module(b_tests).\n{}", EXPORT_ALL))
+        .file("src/legacy_b.erl", &format!("-module(legacy_b).\n{}", EXPORT_ALL))
+}
+
+#[test]
+fn export_lists_are_checked_against_the_module() {
+    let output = TestProject::new("exports").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("undefined_export"), vec![2, 3], "{}", output.stdout);
+    let undefined = output.findings("undefined_export");
+    assert!(undefined[0].contains("Exported function f/3 is not defined, the module defines f/1"),
+            "{}", output.stdout);
+    assert!(undefined[1].ends_with("Exported function h/1 is not defined"), "{}", output.stdout);
+
+    assert_eq!(output.lines_of("duplicate_export"), vec![3], "{}", output.stdout);
+    assert!(output.findings("duplicate_export")[0].contains("Function f/1 is already exported on line 2"),
+            "{}", output.stdout);
+
+    assert_eq!(output.lines_of("export_imported"), vec![3, 3], "{}", output.stdout);
+    let imported = output.findings("export_imported");
+    assert!(imported[0].contains("Exported function member/2 is also imported from lists; a module can only export \
+                                  its own functions"), "{}", output.stdout);
+    assert!(imported[1].contains("Exported function reverse/1 is also imported from lists; the local definition \
+                                  conflicts with the import"), "{}", output.stdout);
+
+    assert_eq!(output.lines_of("undefined_export_type"), vec![4], "{}", output.stdout);
+    assert!(output.findings("undefined_export_type")[0].contains("Exported type u/0 is not defined, the module \
+                                                                  defines u/1"), "{}", output.stdout);
+}
+
+#[test]
+fn export_all_is_allowed_in_test_modules_only() {
+    let output = export_all_project("export-all").check();
+    assert_eq!(output.findings("export_all").len(), 2, "{}", output.stdout);
+    assert_eq!(output.findings_in("export_all", "src/b.erl").len(), 1, "{}", output.stdout);
+    assert_eq!(output.findings_in("export_all", "src/legacy_b.erl").len(), 1, "{}", output.stdout);
+    assert!(output.findings("export_all")[0].contains("List the exports explicitly"), "{}", output.stdout);
+}
+
+#[test]
+fn export_all_except_replaces_the_default_patterns() {
+    let output = export_all_project("export-all-except")
+        .config("[lints.export_all]\nexcept = [\"legacy_*\"]\n")
+        .check();
+    assert_eq!(output.findings("export_all").len(), 3, "{}", output.stdout);
+    assert!(output.findings_in("export_all", "src/legacy_b.erl").is_empty(), "{}", output.stdout);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([f/2]).
+
+f(Name, Count) ->
+    io:format(\"~q~n\", [Name]),
+    io:format(\"~s: ~b~n\", [Name]),
+    io_lib:format(\"~p ~p~n\", [Name, Count, extra]),
+    logger:info(\"~s has ~b items\", [{Name}, Count]),
+    lager:warning(\"~s~n\", [42]),
+    error_logger:error_msg(\"done~\").
+";
+
+const FINE: &str = "-module(a).
+-export([f/2]).
+
+f(Name, Args) ->
+    io:format(\"~s: ~-10.2.0f~n\", [Name, 1.5]),
+    io:format(user, \"~*w ~tp~n\", [5, Name, Name]),
+    io:format(\"~c~c~n\", \"ab\"),
+    io:format(\"~p~n\", Args),
+    logger:info(#{what => Name}),
+    logger:log(info, \"~ts ~~ ~n\", [Name]),
+    lager:info(\"done\").
+";
+
+#[test]
+fn bad_format_strings_are_reported() {
+    let output = TestProject::new("format-strings").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("invalid_format_directive"), vec![5, 10], "{}", output.stdout);
+    let invalid = output.findings("invalid_format_directive");
+    assert!(invalid[0].contains("Bad format string for io:format/2: ~q is not a valid control sequence"),
+            "{}", output.stdout);
+    assert!(invalid[1].contains("the format string ends inside a control sequence"), "{}", output.stdout);
+
+    assert_eq!(output.lines_of("format_args_mismatch"), vec![6, 7], "{}", output.stdout);
+    let mismatch = output.findings("format_args_mismatch");
+    assert!(mismatch[0].contains("io:format/2 expects 2 argument(s), but 1 are given"), "{}", output.stdout);
+    assert!(mismatch[1].contains("io_lib:format/2 expects 2 argument(s), but 3 are given"), "{}", output.stdout);
+
+    assert_eq!(output.lines_of("format_string_arg"), vec![8, 9], "{}", output.stdout);
+    let string_arg = output.findings("format_string_arg");
+    assert!(string_arg[0].contains("~s is given a tuple"), "{}", output.stdout);
+    assert!(string_arg[1].contains("~s is given an integer"), "{}", output.stdout);
+}
+
+#[test]
+fn valid_format_strings_have_no_findings() {
+    let output = TestProject::new("format-strings-fine").file("src/a.erl", FINE).check();
+    for code in ["invalid_format_directive", "format_args_mismatch", "format_string_arg"] {
+        assert!(output.findings(code).is_empty(), "{}", output.stdout);
+    }
+}
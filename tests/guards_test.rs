@@ -0,0 +1,71 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([f/2, p/1, q/1]).
+
+valid(X) -> X > 0.
+
+f(X, L) when valid(X) -> L;
+f(X, L) when atom_to_list(X) =:= L -> L;
+f(X, L) when lists:member(X, L) -> L;
+f(X, L) when is_integer(X), erlang:is_list(L), length(L) > X -> L.
+
+p(<<X:foo>>) -> X;
+p(A ++ B) -> {A, B};
+p([$a | T] ++ Rest) -> {T, Rest};
+p(X + 1) -> X.
+
+q(<<N:8, X:(N * 8)>>) -> X;
+q(\"ab\" ++ T) -> T;
+q([$a, $b] ++ T) -> T;
+q(1 + 2) -> three.
+";
+
+const UPDATES: &str = "-module(u).
+-export([g/1, h/1, i/1, j/1]).
+-record(r, {a, b}).
+
+-spec g(R) -> R when R :: #r{}.
+-define(SET_A(R), R#r{a = 1}).
+
+g(R) when R#r{a = 1} =:= R -> ?SET_A(R).
+
+h(M) when M#{a => 1} =:= M -> M.
+
+i(R) when R#r.a =:= 1, R#r{b = 2} =:= R -> R.
+
+j(M) when is_map(M), M =:= #{a => 1} -> M#{a := 2}.
+";
+
+#[test]
+fn non_guard_calls_are_reported() {
+    let output = TestProject::new("guards-calls").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("illegal_guard_call"), vec![6, 7, 8], "{}", output.stdout);
+    let calls = output.findings("illegal_guard_call");
+    assert!(calls[0].contains("Local function valid/1 can not be called in a guard"), "{}", output.stdout);
+    assert!(calls[1].contains("atom_to_list/1 is not a guard BIF"), "{}", output.stdout);
+    assert!(calls[2].contains("lists:member/2 is not a guard BIF"), "{}", output.stdout);
+}
+
+#[test]
+fn illegal_patterns_are_reported() {
+    let output = TestProject::new("guards-patterns").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("illegal_pattern"), vec![11, 12, 13, 14], "{}", output.stdout);
+    let patterns = output.findings("illegal_pattern");
+    assert!(patterns[0].contains("Binary segment size in a pattern must be an integer"), "{}", output.stdout);
+    assert!(patterns[1].contains("Operator ++ is not allowed in a pattern"), "{}", output.stdout);
+    assert!(patterns[2].contains("Operator ++ is not allowed in a pattern"), "{}", output.stdout);
+    assert!(patterns[3].contains("Arithmetic in a pattern must only use literal numbers"), "{}", output.stdout);
+}
+
+#[test]
+fn updates_in_guards_are_reported() {
+    let output = TestProject::new("guards-updates").file("src/u.erl", UPDATES).check();
+    assert_eq!(output.lines_of("illegal_guard_update"), vec![8, 10, 12], "{}", output.stdout);
+    let updates = output.findings("illegal_guard_update");
+    assert!(updates[0].contains("Record update is not allowed in a guard"), "{}", output.stdout);
+    assert!(updates[1].contains("Map update is not allowed in a guard"), "{}", output.stdout);
+    assert!(updates[2].contains("Record update is not allowed in a guard"), "{}", output.stdout);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-include(\"used.hrl\").
+-include(\"unused.hrl\").
+-export([new/0]).
+
+new() ->
+    #point{x = 1}.
+";
+
+fn project(name: &str) -> TestProject {
+    TestProject::new(name)
+        .file("src/a.erl", MODULE)
+        .file("src/used.hrl", "-include(\"base.hrl\").\n-record(point, {x, y = ?ORIGIN}).\n")
+        .file("src/base.hrl", "-define(ORIGIN, 0).\n")
+        .file("src/unused.hrl", "-define(UNUSED_LIMIT, 10).\n")
+        .file("src/orphan.hrl", "-define(ORPHAN, true).\n")
+}
+
+#[test]
+fn includes_lists_module_and_header_directives() {
+    let output = project("includes").run(&["includes"]);
+    let lines: Vec<&str> = output.stdout.lines().collect();
+    assert!(lines.iter().any(|l| l.contains("src/a.erl:2 -include ") && l.ends_with("src/used.hrl")),
+            "{}", output.stdout);
+    assert!(lines.iter().any(|l| l.contains("src/a.erl:3 -include ") && l.ends_with("src/unused.hrl")),
+            "{}", output.stdout);
+    assert!(lines.iter().any(|l| l.contains("src/used.hrl:1 -include ") && l.ends_with("src/base.hrl")),
+            "{}", output.stdout);
+    assert!(!output.stdout.contains("orphan.hrl"), "{}", output.stdout);
+}
+
+#[test]
+fn header_nobody_includes_is_reported() {
+    let output = project("unused-header").check();
+    let findings = output.findings("unused_header");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/orphan.hrl:1:1: warning"), "{}", findings[0]);
+}
+
+#[test]
+fn include_without_used_names_is_reported() {
+    let output = project("unused-include").check();
+    let findings = output.findings("unused_include");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:3:"), "{}", findings[0]);
+    assert!(findings[0].contains("'unused.hrl' are used in module a"), "{}", findings[0]);
+}
+
+#[test]
+fn macro_used_only_by_the_header_chain_does_not_count_for_the_module() {
+    // base.hrl is only needed by used.hrl, the module includes used.hrl for the record
+    let output = project("nested-include").check();
+    assert!(output.findings_in("unused_include", "src/used.hrl").is_empty(), "{}", output.stdout);
+    assert!(!output.findings("unused_include").iter().any(|l| l.contains("'used.hrl'")), "{}", output.stdout);
+}
+
+#[test]
+fn include_cycle_is_an_error() {
+    let output = TestProject::new("include-cycle")
+        .file("src/a.erl", "-module(a).\n-include(\"first.hrl\").\n")
+        .file("src/first.hrl", "-ifndef(FIRST).\n-define(FIRST, 1).\n-include(\"second.hrl\").\n-endif.\n")
+        .file("src/second.hrl", "-ifndef(SECOND).\n-define(SECOND, 1).\n-include(\"first.hrl\").\n-endif.\n")
+        .check();
+    let findings = output.findings("include_cycle");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains(": error [include_cycle] Include cycle: "), "{}", findings[0]);
+    assert!(findings[0].contains("first.hrl") && findings[0].contains("second.hrl"), "{}", findings[0]);
+}
+
+#[test]
+fn acyclic_includes_report_no_cycle() {
+    let output = project("no-cycle").check();
+    assert!(output.findings("include_cycle").is_empty(), "{}", output.stdout);
+}
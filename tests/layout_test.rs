@@ -0,0 +1,62 @@
+mod common;
+
+use common::TestProject;
+
+const MESSY: &str = "-module(a).\n-export([f/2]).\n\n\n\nf(X,Y)->\n\tZ=X,   \n\t{Z,Y}.";
+
+const TIDY: &str = "-module(a).\n-export([f/2]).\n\nf(X, Y) ->\n    Z = X,\n    {Z, Y}.\n";
+
+#[test]
+fn layout_problems_are_reported_at_their_positions() {
+    let output = TestProject::new("layout").file("src/a.erl", MESSY).check();
+    assert_eq!(output.lines_of("multiple_blank_lines"), vec![4], "{}", output.stdout);
+    assert!(output.findings("multiple_blank_lines")[0].ends_with("3 blank lines in a row, at most 1 allowed"),
+            "{}", output.stdout);
+    assert_eq!(output.lines_of("comma_spacing"), vec![6, 8], "{}", output.stdout);
+    assert_eq!(output.lines_of("operator_spacing"), vec![6, 7, 7], "{}", output.stdout);
+    assert_eq!(output.lines_of("trailing_whitespace"), vec![7], "{}", output.stdout);
+    assert_eq!(output.lines_of("tab_indentation"), vec![7, 8], "{}", output.stdout);
+    assert_eq!(output.lines_of("missing_final_newline"), vec![8], "{}", output.stdout);
+}
+
+#[test]
+fn fix_rewrites_the_layout() {
+    let project = TestProject::new("layout-fix").file("src/a.erl", MESSY);
+    let output = project.run(&["check", "--fix"]);
+    assert!(output.stdout.contains("Applied 10 fixes to 1 files"), "{}", output.stdout);
+    assert_eq!(project.read("src/a.erl"), TIDY);
+
+    let output = project.check();
+    for code in ["multiple_blank_lines", "comma_spacing", "operator_spacing", "trailing_whitespace",
+                 "tab_indentation", "missing_final_newline"] {
+        assert!(output.findings(code).is_empty(), "{}", output.stdout);
+    }
+}
+
+#[test]
+fn tidy_module_has_no_layout_findings() {
+    let output = TestProject::new("layout-tidy").file("src/a.erl", TIDY).check();
+    for code in ["line_too_long", "multiple_blank_lines", "comma_spacing", "operator_spacing", "trailing_whitespace",
+                 "tab_indentation", "missing_final_newline"] {
+        assert!(output.findings(code).is_empty(), "{}", output.stdout);
+    }
+}
+
+#[test]
+fn line_length_and_operators_are_configurable() {
+    let text = "-module(a).\n-export([long_function_name/1]).\n\nlong_function_name(X) when X==1 ->\n    X.\n";
+    let output = TestProject::new("layout-default").file("src/a.erl", text).check();
+    assert!(output.findings("line_too_long").is_empty(), "{}", output.stdout);
+    assert!(output.findings("operator_spacing").is_empty(), "{}", output.stdout);
+
+    let output = TestProject::new("layout-config")
+        .file("src/a.erl", text)
+        .config("[lints.line_too_long]\nmax_length = 30\n[lints.operator_spacing]\noperators = [\"==\"]\n")
+        .check();
+    let long = output.findings("line_too_long");
+    assert_eq!(long.len(), 2, "{}", output.stdout);
+    assert!(long[0].contains("src/a.erl:2:31:") && long[0].ends_with("Line is 32 characters long, the limit is 30"),
+            "{}", long[0]);
+    assert_eq!(output.lines_of("line_too_long"), vec![2, 4], "{}", output.stdout);
+    assert_eq!(output.lines_of("operator_spacing"), vec![4, 4], "{}", output.stdout);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([f/1]).
+
+-record(unused, {field}).
+
+f(X) ->
+    Unused = X,
+    catch X.
+";
+
+fn project(name: &str) -> TestProject {
+    TestProject::new(name).file("src/a.erl", MODULE)
+}
+
+#[test]
+fn rules_lists_ids_severity_and_codes() {
+    let output = project("rules").config("[lints.old_style_catch]\nenabled = false\n").run(&["rules"]);
+    let line = |id: &str| output.stdout.lines().find(|l| l.starts_with(&format!("{} ", id))).unwrap_or("");
+    assert!(line("records").contains(" warning "), "{}", output.stdout);
+    assert!(line("records").ends_with("(unused_record, unused_record_field, record_field_same_value, \
+                                       record_conflict)"), "{}", output.stdout);
+    assert!(line("old_style_catch").contains(" disabled "), "{}", output.stdout);
+}
+
+#[test]
+fn disabled_rule_reports_nothing() {
+    let enabled = project("rule-enabled").check();
+    assert_eq!(enabled.findings("old_style_catch").len(), 1, "{}", enabled.stdout);
+
+    let disabled = project("rule-disabled").config("[lints.old_style_catch]\nenabled = false\n").check();
+    assert!(disabled.findings("old_style_catch").is_empty(), "{}", disabled.stdout);
+}
+
+#[test]
+fn rule_severity_applies_to_module_and_project_rules() {
+    let output = project("rule-severity")
+        .config("[lints.old_style_catch]\nseverity = \"info\"\n[lints.records]\nseverity = \"error\"\n")
+        .check();
+    assert!(output.findings("old_style_catch")[0].contains(": info [old_style_catch] "), "{}", output.stdout);
+    assert!(output.findings("unused_record")[0].contains(": error [unused_record] "), "{}", output.stdout);
+}
+
+#[test]
+fn code_settings_refine_the_rule_settings() {
+    let output = project("code-severity")
+        .config("[lints.records]\nseverity = \"error\"\n[lints.unused_record]\nseverity = \"info\"\n\
+                 [lints.unused_record_field]\nenabled = false\n")
+        .check();
+    assert!(output.findings("unused_record")[0].contains(": info [unused_record] "), "{}", output.stdout);
+    assert!(output.findings("unused_record_field").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn unknown_lint_id_is_an_error() {
+    let output = project("unknown-lint").config("[lints.old_style_cacth]\nenabled = false\n").check();
+    assert_ne!(output.status, 0);
+    assert!(output.stderr.contains("[lints.old_style_cacth] is not a rule id or diagnostic code"), "{}",
+            output.stderr);
+    assert_eq!(output.stdout, "");
+}
+
+#[test]
+fn bad_setting_values_are_errors() {
+    let output = project("bad-severity").config("[lints.old_style_catch]\nseverity = \"fatal\"\n").check();
+    assert_ne!(output.status, 0);
+    assert!(output.stderr.contains("Unknown severity 'fatal'"), "{}", output.stderr);
+
+    let output = project("bad-param").config("[lints.line_too_long]\nmax_length = \"long\"\n").check();
+    assert_ne!(output.status, 0);
+    assert!(output.stderr.contains("lints.line_too_long.max_length must be a positive integer"), "{}",
+            output.stderr);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::TestProject;
+
+#[test]
+fn unused_macros_are_reported() {
+    let output = TestProject::new("unused-macro")
+        .file("src/a.erl", "-module(a).\n-export([f/0]).\n-define(USED, 1).\n-define(UNUSED, 2).\n\n\
+                            f() ->\n    ?USED.\n")
+        .config("[compiler_options.defines]\nVERBOSE = true\n")
+        .check();
+    let findings = output.findings("unused_macro");
+    assert_eq!(findings.len(), 2, "{}", output.stdout);
+    assert!(findings.iter().any(|l| l.contains("src/a.erl:4:1: warning [unused_macro] Macro ?UNUSED is never used")),
+            "{}", output.stdout);
+    assert!(findings.iter().any(|l| l.ends_with("Macro ?VERBOSE from compiler_options.defines is never used")),
+            "{}", output.stdout);
+    assert!(!output.stdout.contains("?USED is never used"), "{}", output.stdout);
+}
+
+#[test]
+fn macro_used_in_another_module_through_a_header_is_used() {
+    let output = TestProject::new("macro-header")
+        .file("src/a.erl", "-module(a).\n-include(\"limits.hrl\").\n-export([f/0]).\n\nf() ->\n    ?LIMIT.\n")
+        .file("src/b.erl", "-module(b).\n-include(\"limits.hrl\").\n-export([g/0]).\n\ng() ->\n    ok.\n")
+        .file("src/limits.hrl", "-define(LIMIT, 10).\n")
+        .check();
+    assert!(output.findings("unused_macro").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn different_definitions_seen_by_one_module_are_reported() {
+    let output = TestProject::new("macro-redefined")
+        .file("src/a.erl", "-module(a).\n-include(\"one.hrl\").\n-include(\"two.hrl\").\n")
+        .file("src/one.hrl", "-define(TIMEOUT, 100).\n")
+        .file("src/two.hrl", "-define(TIMEOUT, 200).\n")
+        .check();
+    let findings = output.findings("macro_redefined");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("Macro ?TIMEOUT has different definitions in "), "{}", findings[0]);
+    assert!(findings[0].ends_with("module a gets the one included first"), "{}", findings[0]);
+}
+
+#[test]
+fn same_definition_or_other_arity_is_not_a_redefinition() {
+    let output = TestProject::new("macro-arity")
+        .file("src/a.erl", "-module(a).\n-include(\"one.hrl\").\n-include(\"two.hrl\").\n-define(F, 1).\n\
+                            -define(F(X), X).\n")
+        .file("src/one.hrl", "-define(TIMEOUT, 100).\n")
+        .file("src/two.hrl", "-define(TIMEOUT, 100).\n")
+        .check();
+    assert!(output.findings("macro_redefined").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn arity_is_named_in_redefinitions_of_macros_with_arguments() {
+    let output = TestProject::new("macro-arity-redefined")
+        .file("src/a.erl", "-module(a).\n-include(\"one.hrl\").\n-include(\"two.hrl\").\n")
+        .file("src/one.hrl", "-define(WRAP(X), {X}).\n")
+        .file("src/two.hrl", "-define(WRAP(X), [X]).\n")
+        .check();
+    let findings = output.findings("macro_redefined");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("Macro ?WRAP/1 has different definitions"), "{}", findings[0]);
+}
+
+#[test]
+fn redefining_a_predefined_macro_is_reported() {
+    let output = TestProject::new("macro-predefined")
+        .file("src/a.erl", "-module(a).\n-define(MACHINE, 'JAM').\n-define(MACHINE_NAME, 'BEAM').\n")
+        .check();
+    let findings = output.findings("macro_shadows_predefined");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:2:1: warning [macro_shadows_predefined] Macro ?MACHINE redefines"),
+            "{}", findings[0]);
+}
+
+#[test]
+fn condition_on_a_never_defined_macro_is_reported() {
+    let output = TestProject::new("macro-condition")
+        .file("src/a.erl", "-module(a).\n-define(DEBUG, true).\n\n-ifdef(DEBGU).\n-export([f/0]).\n-endif.\n\n\
+                            -ifdef(DEBUG).\n-endif.\n-ifdef(TEST).\n-endif.\n-ifndef(OTP_RELEASE).\n-endif.\n")
+        .check();
+    let findings = output.findings("undefined_macro_condition");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:4:1: warning [undefined_macro_condition] Macro ?DEBGU is checked here"),
+            "{}", findings[0]);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([classify/2, simple/0]).
+
+classify(X, Y) when X > 0; Y > 0 ->
+    case X of
+        1 ->
+            if
+                Y > 1 -> big;
+                true -> small
+            end;
+        _ -> other
+    end;
+classify(_, _) ->
+    %% comment line
+    none.
+
+simple() ->
+    ok.
+";
+
+fn project(name: &str) -> TestProject {
+    TestProject::new(name).file("src/a.erl", MODULE)
+}
+
+#[test]
+fn csv_lists_function_metrics() {
+    let output = project("metrics-csv").run(&["metrics", "--format", "csv"]);
+    assert_eq!(output.stdout, "module,function,line,complexity,nesting,clauses,loc,arguments\n\
+                               a,classify/2,4,5,2,2,11,2\n\
+                               a,simple/0,17,1,0,1,2,0\n");
+}
+
+#[test]
+fn csv_lists_module_totals() {
+    let project = project("metrics-modules");
+    let output = project.run(&["metrics", "--format", "csv", "--modules"]);
+    let lines: Vec<&str> = output.stdout.lines().collect();
+    assert_eq!(lines[0], "module,path,functions,exports");
+    assert!(lines[1].starts_with("a,") && lines[1].ends_with("src/a.erl,2,2"), "{}", output.stdout);
+    assert_eq!(lines.len(), 2, "{}", output.stdout);
+}
+
+#[test]
+fn json_output_is_parseable() {
+    let output = project("metrics-json").run(&["metrics", "--format", "json"]);
+    let modules: serde_json::Value = serde_json::from_str(&output.stdout).expect(&output.stdout);
+    let module = &modules[0];
+    assert_eq!(module["module"], "a");
+    assert_eq!(module["exports"], 2);
+    assert_eq!(module["function_metrics"][0]["function"], "classify/2");
+    assert_eq!(module["function_metrics"][0]["complexity"], 5);
+}
+
+#[test]
+fn table_aligns_columns() {
+    let output = project("metrics-table").run(&["metrics"]);
+    let lines: Vec<&str> = output.stdout.lines().collect();
+    assert_eq!(lines[0], "module  function    line  complexity  nesting  clauses  loc  arguments");
+    assert_eq!(lines[1], "a       classify/2  4     5           2        2        11   2");
+}
+
+#[test]
+fn thresholds_are_not_reached_by_default() {
+    let output = project("metrics-default").check();
+    for code in ["function_complexity", "function_nesting", "function_clauses", "function_length",
+                 "function_arguments", "module_functions", "module_exports"] {
+        assert!(output.findings(code).is_empty(), "{}", output.stdout);
+    }
+}
+
+#[test]
+fn metrics_above_the_configured_limits_are_reported() {
+    let output = project("metrics-limits")
+        .config("[lints.function_complexity]\nmax = 4\n[lints.function_nesting]\nmax = 1\n\
+                 [lints.function_length]\nmax = 11\n[lints.module_exports]\nmax = 1\n")
+        .check();
+    let complexity = output.findings("function_complexity");
+    assert_eq!(complexity.len(), 1, "{}", output.stdout);
+    assert!(complexity[0].contains("src/a.erl:4:1:") && complexity[0].ends_with("Function classify/2 cyclomatic \
+                                    complexity is 5, the limit is 4"), "{}", complexity[0]);
+    assert_eq!(output.lines_of("function_nesting"), vec![4], "{}", output.stdout);
+    assert!(output.findings("function_length").is_empty(), "{}", output.stdout);
+    let exports = output.findings("module_exports");
+    assert_eq!(exports.len(), 1, "{}", output.stdout);
+    assert!(exports[0].ends_with("Module a has 2 exported functions, the limit is 1"), "{}", exports[0]);
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([badName/1, good_name/1]).
+-define(badMacro, 1).
+-define(GOOD_MACRO, 2).
+-record(myRecord, {fieldOne, field_two}).
+-type myType() :: integer().
+
+badName(My_var) ->
+    {?badMacro, ?GOOD_MACRO, My_var, My_var, fooBar, 'Quoted', #myRecord{}}.
+
+good_name(GoodVar) ->
+    {GoodVar, good_atom}.
+";
+
+#[test]
+fn default_styles_are_checked_for_every_kind_of_name() {
+    let output = TestProject::new("naming").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("module_naming"), Vec::<usize>::new(), "{}", output.stdout);
+    assert_eq!(output.lines_of("function_naming"), vec![8], "{}", output.stdout);
+    assert_eq!(output.lines_of("variable_naming"), vec![8], "{}", output.stdout);
+    assert_eq!(output.lines_of("macro_naming"), vec![3], "{}", output.stdout);
+    assert_eq!(output.lines_of("record_naming"), vec![5], "{}", output.stdout);
+    assert_eq!(output.lines_of("record_field_naming"), vec![5], "{}", output.stdout);
+    assert_eq!(output.lines_of("type_naming"), vec![6], "{}", output.stdout);
+    assert_eq!(output.lines_of("atom_naming"), vec![9], "{}", output.stdout);
+    assert!(output.findings("function_naming")[0].ends_with("Function name badName does not follow snake_case"),
+            "{}", output.stdout);
+    assert!(output.findings("variable_naming")[0].ends_with("Variable name My_var does not follow CamelCase"),
+            "{}", output.stdout);
+}
+
+#[test]
+fn style_and_pattern_are_configurable() {
+    let output = TestProject::new("naming-pattern")
+        .file("src/a.erl", MODULE)
+        .config("[lints.function_naming]\npattern = \"^[a-z]+$\"\n[lints.atom_naming]\nstyle = \"CamelCase\"\n")
+        .check();
+    assert_eq!(output.lines_of("function_naming"), vec![8, 11], "{}", output.stdout);
+    assert!(output.findings("function_naming")[1].ends_with("Function name good_name does not follow pattern \
+                                                             ^[a-z]+$"), "{}", output.stdout);
+    assert_eq!(output.lines_of("atom_naming"), vec![9, 12], "{}", output.stdout);
+}
+
+#[test]
+fn unknown_style_is_a_configuration_error() {
+    let output = TestProject::new("naming-bad-style")
+        .file("src/a.erl", MODULE)
+        .config("[lints.function_naming]\nstyle = \"kebab-case\"\n")
+        .check();
+    assert_eq!(output.status, 2);
+    assert!(output.stderr.contains("lints.function_naming.style must be snake_case, CamelCase or SCREAMING_SNAKE"),
+            "{}", output.stderr);
+}
+
+#[test]
+fn macros_defined_outside_the_input_paths_are_not_checked() {
+    let output = TestProject::new("naming-deps")
+        .file("src/a.erl", "-module(a).\n-include(\"../deps/lib.hrl\").\n-define(localMacro, 1).\n")
+        .file("deps/lib.hrl", "-define(depMacro, 1).\n")
+        .check();
+    let findings = output.findings("macro_naming");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:3:1:") && findings[0].contains("Macro name localMacro"),
+            "{}", findings[0]);
+}
+
+#[test]
+fn header_forms_are_checked_once_and_only_in_the_input_paths() {
+    let module = |name: &str| format!("-module({}).\n-include(\"../deps/lib.hrl\").\n-include(\"own.hrl\").\n", name);
+    let output = TestProject::new("naming-header-forms")
+        .file("src/a.erl", &module("a"))
+        .file("src/b.erl", &module("b"))
+        .file("src/own.hrl", "-record(ownRecord, {ownField}).\n")
+        .file("deps/lib.hrl", "-record(xmlElement, {expandedName}).\n-type xmlText() :: term().\n")
+        .check();
+    let records = output.findings("record_naming");
+    assert_eq!(records.len(), 1, "{}", output.stdout);
+    assert!(records[0].contains("src/own.hrl:1:") && records[0].contains("Record name ownRecord"), "{}", records[0]);
+    assert_eq!(output.findings("record_field_naming").len(), 1, "{}", output.stdout);
+    assert!(output.findings("type_naming").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn module_prefix_is_required_for_configured_apps() {
+    let output = TestProject::new("module-prefix")
+        .file("billing/src/invoice.erl", "-module(invoice).\n")
+        .file("billing/src/billing_tax.erl", "-module(billing_tax).\n")
+        .file("billing/src/billing.erl", "-module(billing).\n")
+        .file("shop/src/cart.erl", "-module(cart).\n")
+        .input_paths(&["billing/src", "shop/src"])
+        .config("[lints.module_prefix]\nprefixes = { billing = \"billing_\" }\n")
+        .check();
+    let findings = output.findings("module_prefix");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("billing/src/invoice.erl:1:") && findings[0].ends_with("Module invoice of \
+                                  application billing must start with billing_"), "{}", findings[0]);
+
+    let output = TestProject::new("module-prefix-all")
+        .file("shop/src/cart.erl", "-module(cart).\n")
+        .file("shop/src/shop_order.erl", "-module(shop_order).\n")
+        .input_paths(&["shop/src"])
+        .config("[lints.module_prefix]\nall_apps = true\n")
+        .check();
+    let findings = output.findings("module_prefix");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].ends_with("Module cart of application shop must start with shop_"), "{}", findings[0]);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([empty/1, full/1, twice/1, loop/2, nested/1, sized/1, joined/2]).
+
+empty(L) when length(L) == 0 ->
+    true;
+empty(_) ->
+    false.
+
+full(L) when length(L) >= 1 ->
+    true;
+full(_) ->
+    false.
+
+twice(L) ->
+    lists:reverse(lists:reverse(L)).
+
+loop([], Acc) ->
+    Acc;
+loop([H | T], Acc) ->
+    loop(T, Acc ++ [H]).
+
+nested(Lists) ->
+    [lists:nth(1, L) || L <- Lists].
+
+sized(T) when size(T) > 2 ->
+    size(T).
+
+joined(A, B) ->
+    string:concat(A, B).
+";
+
+const FINE: &str = "-module(a).
+-export([pair/1, once/2, walk/1]).
+
+pair(L) when length(L) == 2 ->
+    lists:reverse(L).
+
+once(Acc, H) ->
+    Acc ++ [H].
+
+walk([H | T]) ->
+    [H | walk(T)];
+walk([]) ->
+    [].
+";
+
+#[test]
+fn performance_anti_patterns_are_reported() {
+    let output = TestProject::new("performance").file("src/a.erl", MODULE).check();
+    assert_eq!(output.lines_of("length_in_guard"), vec![4, 9], "{}", output.stdout);
+    assert!(output.findings("length_in_guard")[1].contains("Match [_ | _] instead"), "{}", output.stdout);
+    assert_eq!(output.lines_of("double_reverse"), vec![15], "{}", output.stdout);
+    assert_eq!(output.lines_of("list_ops_in_loop"), vec![20, 23], "{}", output.stdout);
+    assert!(output.findings("list_ops_in_loop")[0].contains("Acc ++ ... in a recursive function copies the \
+                                                             accumulator"), "{}", output.stdout);
+    assert!(output.findings("list_ops_in_loop")[1].contains("lists:nth/2 inside a loop walks the list"),
+            "{}", output.stdout);
+    assert_eq!(output.lines_of("size_call"), vec![25, 26], "{}", output.stdout);
+    assert_eq!(output.lines_of("string_concat"), vec![29], "{}", output.stdout);
+}
+
+#[test]
+fn fix_replaces_empty_length_tests_and_double_reverse() {
+    let project = TestProject::new("performance-fix").file("src/a.erl", MODULE);
+    let output = project.run(&["check", "--fix"]);
+    assert!(output.stdout.contains("Applied 2 fixes to 1 files"), "{}", output.stdout);
+    let expected = MODULE
+        .replace("length(L) == 0", "L =:= []")
+        .replace("lists:reverse(lists:reverse(L))", "L");
+    assert_eq!(project.read("src/a.erl"), expected);
+
+    // `L =/= []` would also accept terms which are not lists, so the "not empty" test is left as it is
+    let output = project.check();
+    assert_eq!(output.lines_of("length_in_guard"), vec![9], "{}", output.stdout);
+    assert!(output.findings("double_reverse").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn plain_list_code_has_no_findings() {
+    let output = TestProject::new("performance-fine").file("src/a.erl", FINE).check();
+    for code in ["length_in_guard", "double_reverse", "list_ops_in_loop", "size_call", "string_concat"] {
+        assert!(output.findings(code).is_empty(), "{}", output.stdout);
+    }
+}
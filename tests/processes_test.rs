@@ -0,0 +1,144 @@
+mod common;
+
+use common::TestProject;
+
+const CLIENT: &str = "-module(a).
+-export([start/0, start_linked/0, loop/1, ask/1, ask_statem/1, ask_forever/1, ask_patient/1, wait/0,
+         wait_after/0]).
+
+start() ->
+    spawn(fun() -> ok end).
+
+start_linked() ->
+    Pid = spawn(fun() -> ok end),
+    link(Pid),
+    Pid.
+
+loop(State) ->
+    receive
+        stop -> ok;
+        Msg -> loop([Msg | State])
+    end.
+
+ask(Server) ->
+    gen_server:call(Server, ask).
+
+ask_statem(Server) ->
+    gen_statem:call(Server, ask).
+
+ask_forever(Server) ->
+    gen_server:call(Server, ask, infinity).
+
+ask_patient(Server) ->
+    gen_server:call(Server, ask, 60000).
+
+wait() ->
+    receive
+        done -> ok
+    end.
+
+wait_after() ->
+    receive done -> ok after 1000 -> timeout end.
+";
+
+const SERVER: &str = "-module(s).
+-behaviour(gen_server).
+-export([init/1, handle_call/3, handle_cast/2, helper/0]).
+
+init(Args) ->
+    timer:sleep(100),
+    {ok, Args}.
+
+handle_call(_Request, _From, State) ->
+    {reply, ok, State}.
+
+handle_cast(_Msg, State) ->
+    {noreply, State}.
+
+helper() ->
+    timer:sleep(100).
+";
+
+const STATEM: &str = "-module(m).
+-behaviour(gen_statem).
+-export([init/1, callback_mode/0, idle/3]).
+
+init(Args) ->
+    {ok, idle, Args}.
+
+callback_mode() ->
+    state_functions.
+
+idle(_Type, _Event, Data) ->
+    timer:sleep(100),
+    {keep_state, Data}.
+";
+
+const SUPERVISOR: &str = "-module(sup).
+-behaviour(supervisor).
+-export([init/1, start_worker/0]).
+
+init([]) ->
+    {ok, {#{}, []}}.
+
+start_worker() ->
+    spawn(fun() -> ok end).
+";
+
+fn project(name: &str) -> TestProject {
+    TestProject::new(name)
+        .file("src/a.erl", CLIENT)
+        .file("src/s.erl", SERVER)
+        .file("src/m.erl", STATEM)
+        .file("src/sup.erl", SUPERVISOR)
+}
+
+#[test]
+fn spawn_without_link_or_monitor_is_reported() {
+    let output = project("processes-spawn").check();
+    assert_eq!(output.lines_of("unsupervised_spawn"), vec![6], "{}", output.stdout);
+    let spawns = output.findings_in("unsupervised_spawn", "src/a.erl");
+    assert_eq!(spawns.len(), 1, "{}", output.stdout);
+    assert!(spawns[0].contains("erlang:spawn/1 starts a process nobody links to or monitors"), "{}", output.stdout);
+}
+
+#[test]
+fn calls_with_default_or_infinity_timeouts_are_reported() {
+    let output = project("processes-timeout").check();
+    assert_eq!(output.lines_of("call_timeout"), vec![20, 23, 26], "{}", output.stdout);
+    let calls = output.findings("call_timeout");
+    assert!(calls[0].contains("gen_server:call/2 waits 5 seconds by default and then exits the caller"),
+            "{}", output.stdout);
+    assert!(calls[1].contains("gen_statem:call/2 waits forever by default and blocks the caller if the state \
+                               machine hangs"), "{}", output.stdout);
+    assert!(calls[2].contains("gen_server:call/3 with an infinity timeout blocks forever"), "{}", output.stdout);
+}
+
+#[test]
+fn call_timeout_modules_limit_the_checked_modules() {
+    let output = project("processes-timeout-modules")
+        .config("[lints.call_timeout]\nmodules = [\"client_*\"]\n")
+        .check();
+    assert!(output.findings("call_timeout").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn receive_without_after_outside_of_loops_is_reported() {
+    let output = project("processes-receive").check();
+    assert_eq!(output.lines_of("receive_without_after"), vec![32], "{}", output.stdout);
+    assert!(output.findings("receive_without_after")[0].contains("Add an after clause with a timeout"),
+            "{}", output.stdout);
+}
+
+#[test]
+fn sleep_in_server_callbacks_is_reported() {
+    let output = project("processes-sleep").check();
+    let server = output.findings_in("sleep_in_callback", "src/s.erl");
+    assert_eq!(server.len(), 1, "{}", output.stdout);
+    assert!(server[0].contains(":6:"), "{}", output.stdout);
+    assert!(server[0].contains("timer:sleep in callback init/1 blocks the server process"), "{}", output.stdout);
+    let statem = output.findings_in("sleep_in_callback", "src/m.erl");
+    assert_eq!(statem.len(), 1, "{}", output.stdout);
+    assert!(statem[0].contains("timer:sleep in callback idle/3"), "{}", output.stdout);
+    assert_eq!(output.findings("sleep_in_callback").len(), 2, "{}", output.stdout);
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use common::TestProject;
+
+const USAGE: &str = "-module(a).
+-export([new/0, other/0, get_name/1]).
+
+-record(user, {name, age, role = guest}).
+-record(session, {id}).
+
+new() ->
+    #user{name = <<\"a\">>, role = admin}.
+
+other() ->
+    #user{name = <<\"b\">>, role = admin}.
+
+get_name(#user{name = Name}) ->
+    Name.
+";
+
+#[test]
+fn unused_record_and_field_are_reported() {
+    let output = TestProject::new("unused-record").file("src/a.erl", USAGE).check();
+    let records = output.findings("unused_record");
+    assert_eq!(records.len(), 1, "{}", output.stdout);
+    assert!(records[0].contains("src/a.erl:5:1: warning [unused_record] Record #session is never used"),
+            "{}", records[0]);
+
+    let fields = output.findings("unused_record_field");
+    assert_eq!(fields.len(), 1, "{}", output.stdout);
+    assert!(fields[0].ends_with("Field 'age' of record #user is never used"), "{}", fields[0]);
+}
+
+#[test]
+fn records_named_in_types_and_specs_are_used() {
+    let output = TestProject::new("record-in-types")
+        .file("src/a.erl", "-module(a).
+-export([f/1]).
+-export_type([t/0]).
+
+-record(state, {id}).
+-record(config, {name}).
+-record(unused, {id}).
+
+-type t() :: #config{}.
+
+-spec f(#state{}) -> ok.
+f(_State) ->
+    ok.
+")
+        .check();
+    let records = output.findings("unused_record");
+    assert_eq!(records.len(), 1, "{}", output.stdout);
+    assert!(records[0].ends_with("Record #unused is never used"), "{}", records[0]);
+}
+
+#[test]
+fn field_always_set_to_one_value_is_reported() {
+    let output = TestProject::new("same-value").file("src/a.erl", USAGE).check();
+    let findings = output.findings("record_field_same_value");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains(": info [record_field_same_value] Field 'role' of record #user is always set to \
+                                  admin, consider making it the default"), "{}", findings[0]);
+}
+
+#[test]
+fn all_fields_used_by_record_info_are_not_reported() {
+    let output = TestProject::new("record-info")
+        .file("src/a.erl", "-module(a).\n-export([fields/0]).\n\n-record(cfg, {host, port}).\n\n\
+                            fields() ->\n    record_info(fields, cfg).\n")
+        .check();
+    assert!(output.findings("unused_record").is_empty(), "{}", output.stdout);
+    assert!(output.findings("unused_record_field").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn conflicting_headers_in_one_module_are_an_error() {
+    let output = TestProject::new("conflict-module")
+        .file("src/a.erl", "-module(a).\n-include(\"one.hrl\").\n-include(\"two.hrl\").\n-export([f/0]).\n\n\
+                            f() ->\n    #cfg{}.\n")
+        .file("src/one.hrl", "-record(cfg, {host}).\n")
+        .file("src/two.hrl", "-record(cfg, {host, port}).\n")
+        .check();
+    let findings = output.findings("record_conflict");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains(": error [record_conflict] Record #cfg is defined with different fields in "),
+            "{}", findings[0]);
+    assert!(findings[0].ends_with("both definitions are visible in one module"), "{}", findings[0]);
+}
+
+#[test]
+fn conflicting_headers_in_different_modules_are_a_warning() {
+    let output = TestProject::new("conflict-headers")
+        .file("src/a.erl", "-module(a).\n-include(\"one.hrl\").\n-export([f/0]).\n\nf() ->\n    #cfg{}.\n")
+        .file("src/b.erl", "-module(b).\n-include(\"two.hrl\").\n-export([f/0]).\n\nf() ->\n    #cfg{}.\n")
+        .file("src/one.hrl", "-record(cfg, {host}).\n")
+        .file("src/two.hrl", "-record(cfg, {host, port}).\n")
+        .check();
+    let findings = output.findings("record_conflict");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains(": warning [record_conflict] "), "{}", findings[0]);
+    assert!(findings[0].ends_with("records with this name may be passed between modules"), "{}", findings[0]);
+}
+
+#[test]
+fn private_records_of_different_modules_do_not_conflict() {
+    let output = TestProject::new("conflict-private")
+        .file("src/a.erl", "-module(a).\n-export([f/0]).\n\n-record(state, {a}).\n\nf() ->\n    #state{}.\n")
+        .file("src/b.erl", "-module(b).\n-export([f/0]).\n\n-record(state, {b, c}).\n\nf() ->\n    #state{}.\n")
+        .check();
+    assert!(output.findings("record_conflict").is_empty(), "{}", output.stdout);
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([f/2]).
+
+f(Name, Bin) ->
+    A = list_to_atom(Name),
+    B = list_to_atom(\"fixed\"),
+    C = binary_to_term(Bin),
+    D = binary_to_term(Bin, [safe]),
+    os:cmd(\"ls \" ++ Name),
+    os:cmd(\"ls\"),
+    ssl:connect(\"host\", 443, [{verify, verify_none}]),
+    ssl:connect(\"host\", 443, [{verify, verify_peer}]),
+    E = crypto:hash(md5, Bin),
+    F = crypto:hash(sha256, Bin),
+    file:read_file(\"/data/\" ++ Name),
+    file:read_file(filename:join(\"/data\", Name)),
+    Fixed = \"/data/config\",
+    file:read_file(Fixed),
+    open_port({spawn, <<\"echo \", Bin/binary>>}, []),
+    {A, B, C, D, E, F}.
+";
+
+fn check(name: &str) -> common::RunOutput {
+    TestProject::new(name).file("src/a.erl", MODULE).check()
+}
+
+#[test]
+fn atoms_from_runtime_data_are_reported() {
+    let output = check("atom-from-data");
+    assert_eq!(output.lines_of("atom_from_data"), vec![5], "{}", output.stdout);
+    assert!(output.findings("atom_from_data")[0].contains("erlang:list_to_atom/1 on runtime data can exhaust the \
+                                                           atom table"), "{}", output.stdout);
+}
+
+#[test]
+fn binary_to_term_needs_the_safe_option() {
+    let output = check("binary-to-term");
+    assert_eq!(output.lines_of("unsafe_binary_to_term"), vec![7], "{}", output.stdout);
+}
+
+#[test]
+fn assembled_shell_commands_are_reported() {
+    let output = check("command-injection");
+    assert_eq!(output.lines_of("command_injection"), vec![9, 19], "{}", output.stdout);
+    assert!(output.findings("command_injection")[0].contains(": error [command_injection] os:cmd/1 runs a shell \
+                                                              command assembled from parts"), "{}", output.stdout);
+}
+
+#[test]
+fn disabled_certificate_verification_is_reported() {
+    let output = check("verify-none");
+    assert_eq!(output.lines_of("ssl_verify_none"), vec![11], "{}", output.stdout);
+}
+
+#[test]
+fn weak_hashes_are_reported() {
+    let output = check("weak-hash");
+    assert_eq!(output.lines_of("weak_hash"), vec![13], "{}", output.stdout);
+    assert!(output.findings("weak_hash")[0].contains("md5 is broken for security purposes"), "{}", output.stdout);
+}
+
+#[test]
+fn paths_built_from_arguments_are_reported() {
+    let output = check("path-from-arguments");
+    assert_eq!(output.lines_of("path_from_arguments"), vec![15, 16], "{}", output.stdout);
+    assert!(output.findings("path_from_arguments")[0].contains("file:read_file/1 is called with a path built from \
+                                                                argument Name"), "{}", output.stdout);
+}
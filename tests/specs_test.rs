@@ -0,0 +1,86 @@
+mod common;
+
+use common::TestProject;
+
+const MODULE: &str = "-module(a).
+-export([documented/1, undocumented/0, mode/1]).
+
+-spec documented(integer()) -> integer().
+documented(X) ->
+    X.
+
+undocumented() ->
+    ok.
+
+-spec missing(integer()) -> ok.
+-spec undocumented(integer()) -> ok.
+-spec documented(atom()) -> atom().
+
+-spec mode(read) -> ok; (delete) -> ok; (integer(), atom()) -> ok.
+mode(read) ->
+    ok;
+mode(write) ->
+    ok.
+";
+
+const CLEAN: &str = "-module(a).
+-export([mode/1]).
+
+-spec mode(read | write) -> ok.
+mode(read) ->
+    ok;
+mode(write) ->
+    ok.
+
+local() ->
+    ok.
+";
+
+#[test]
+fn exported_function_without_spec_is_reported() {
+    let output = TestProject::new("missing-spec").file("src/a.erl", MODULE).check();
+    let findings = output.findings("missing_spec");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:8:1: info [missing_spec] Exported function undocumented/0 has no -spec"),
+            "{}", findings[0]);
+}
+
+#[test]
+fn spec_without_function_names_other_arities() {
+    let output = TestProject::new("spec-without-function").file("src/a.erl", MODULE).check();
+    let findings = output.findings("spec_without_function");
+    assert_eq!(findings.len(), 2, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:11:") && findings[0].ends_with("Spec for missing/1 has no matching \
+                                  function"), "{}", findings[0]);
+    assert!(findings[1].contains("src/a.erl:12:") && findings[1].ends_with("Spec for undocumented/1 has no \
+                                  matching function, the module defines undocumented/0"), "{}", findings[1]);
+}
+
+#[test]
+fn second_spec_of_a_function_is_reported() {
+    let output = TestProject::new("duplicate-spec").file("src/a.erl", MODULE).check();
+    let findings = output.findings("duplicate_spec");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:13:1: error [duplicate_spec] Function documented/1 already has a -spec"),
+            "{}", findings[0]);
+}
+
+#[test]
+fn spec_clauses_are_compared_with_function_clauses() {
+    let output = TestProject::new("spec-clause-mismatch").file("src/a.erl", MODULE).check();
+    let findings = output.findings("spec_clause_mismatch");
+    assert_eq!(findings.len(), 2, "{}", output.stdout);
+    assert!(findings.iter().any(|l| l.ends_with("Spec of mode/1 allows 'delete' as argument 1, but no clause of \
+                                                 the function matches it")), "{}", output.stdout);
+    assert!(findings.iter().any(|l| l.ends_with("Spec clause for mode/1 has 2 arguments, the first clause has 1")),
+            "{}", output.stdout);
+    assert_eq!(output.lines_of("spec_clause_mismatch"), vec![15, 15], "{}", output.stdout);
+}
+
+#[test]
+fn consistent_specs_have_no_findings() {
+    let output = TestProject::new("specs-clean").file("src/a.erl", CLEAN).check();
+    for code in ["missing_spec", "spec_without_function", "duplicate_spec", "spec_clause_mismatch"] {
+        assert!(output.findings(code).is_empty(), "{}", output.stdout);
+    }
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use common::TestProject;
+
+fn check_module(name: &str, text: &str) -> common::RunOutput {
+    TestProject::new(name).file("src/a.erl", text).check()
+}
+
+#[test]
+fn disable_next_line_covers_only_the_next_code_line() {
+    let output = check_module("next-line", "-module(a).
+-export([f/1]).
+
+f(X) ->
+    %% ironclad:disable-next-line old_style_catch
+
+    A = catch X,
+    B = catch A,
+    B.
+");
+    assert_eq!(output.lines_of("old_style_catch"), vec![8], "{}", output.stdout);
+    assert!(output.findings("unused_suppression").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn disable_and_enable_cover_a_range() {
+    let output = check_module("range", "-module(a).
+-export([f/1]).
+
+f(X) ->
+    %% ironclad:disable old_style_catch
+    A = catch X,
+    B = catch A,
+    %% ironclad:enable old_style_catch
+    catch B.
+");
+    assert_eq!(output.lines_of("old_style_catch"), vec![9], "{}", output.stdout);
+}
+
+#[test]
+fn targeted_enable_does_not_end_disabling_all_rules() {
+    let output = check_module("disable-all", "-module(a).
+-export([f/1]).
+
+f(X) ->
+    %% ironclad:disable
+    A = catch X,
+    %% ironclad:enable old_style_catch
+    catch A.
+");
+    assert!(output.findings("old_style_catch").is_empty(), "{}", output.stdout);
+
+    let output = check_module("enable-all", "-module(a).
+-export([f/1]).
+
+f(X) ->
+    %% ironclad:disable
+    A = catch X,
+    %% ironclad:enable
+    catch A.
+");
+    assert_eq!(output.lines_of("old_style_catch"), vec![8], "{}", output.stdout);
+}
+
+#[test]
+fn module_attribute_suppresses_the_whole_module() {
+    let output = check_module("module-ignore", "-module(a).
+-ironclad_ignore([old_style_catch]).
+-export([f/1]).
+
+f(X) ->
+    A = catch X,
+    catch A.
+");
+    assert!(output.findings("old_style_catch").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn rule_id_suppresses_project_analysis_codes() {
+    let text = "-module(a).
+
+%% ironclad:disable-next-line records
+-record(first, {a}).
+-record(second, {b}).
+";
+    let output = check_module("rule-id", text);
+    assert_eq!(output.lines_of("unused_record"), vec![5], "{}", output.stdout);
+
+    let output = check_module("code", &text.replace("disable-next-line records", "disable-next-line unused_record"));
+    assert_eq!(output.lines_of("unused_record"), vec![5], "{}", output.stdout);
+    assert!(output.findings("unused_suppression").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn suppression_without_finding_is_reported() {
+    let output = check_module("unused-suppression", "-module(a).
+-export([f/1]).
+
+f(X) ->
+    %% ironclad:disable-next-line old_style_catch
+    X.
+");
+    let findings = output.findings("unused_suppression");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:5:1: warning [unused_suppression] Suppression of old_style_catch does \
+                                  not match any finding"), "{}", findings[0]);
+}
+
+#[test]
+fn unused_suppression_is_configured_like_other_rules() {
+    let text = "-module(a).
+-export([f/1]).
+
+f(X) ->
+    %% ironclad:disable-next-line old_style_catch
+    X.
+";
+    let output = TestProject::new("unused-suppression-severity")
+        .file("src/a.erl", text)
+        .config("[lints.unused_suppression]\nseverity = \"info\"\n")
+        .check();
+    let findings = output.findings("unused_suppression");
+    assert_eq!(findings.len(), 1, "{}", output.stderr);
+    assert!(findings[0].contains("info [unused_suppression]"), "{}", findings[0]);
+
+    let output = TestProject::new("unused-suppression-disabled")
+        .file("src/a.erl", text)
+        .config("[lints.unused_suppression]\nenabled = false\n")
+        .check();
+    assert!(output.findings("unused_suppression").is_empty(), "{}", output.stdout);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+}
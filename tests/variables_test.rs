@@ -0,0 +1,197 @@
+mod common;
+
+use common::TestProject;
+
+fn check_module(name: &str, text: &str) -> common::RunOutput {
+    TestProject::new(name).file("src/a.erl", text).check()
+}
+
+#[test]
+fn unused_variable_is_reported() {
+    let output = check_module("unused-variable", "-module(a).
+-export([f/2]).
+
+f(X, Y) ->
+    Z = X + 1,
+    X.
+");
+    let findings = output.findings("unused_variable");
+    assert_eq!(findings.len(), 2, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:4:") && findings[0].ends_with("Variable Y is unused, remove it or \
+                                                                           rename to _Y"), "{}", findings[0]);
+    assert!(findings[1].contains("src/a.erl:5:") && findings[1].contains("Variable Z is unused"), "{}", findings[1]);
+}
+
+#[test]
+fn underscore_and_anonymous_variables_are_not_unused() {
+    let output = check_module("underscore-unused", "-module(a).
+-export([f/2]).
+
+f(_X, _) ->
+    ok.
+");
+    assert!(output.findings("unused_variable").is_empty(), "{}", output.stdout);
+    assert!(output.findings("underscore_variable_used").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn used_underscore_variable_is_reported() {
+    let output = check_module("underscore-used", "-module(a).
+-export([f/1]).
+
+f(_Config) ->
+    _Config.
+");
+    assert_eq!(output.lines_of("underscore_variable_used"), vec![5], "{}", output.stdout);
+}
+
+#[test]
+fn repeated_pattern_variable_is_reported() {
+    let output = check_module("repeated-variable", "-module(a).
+-export([f/1, g/2]).
+
+f({A, A}) ->
+    A.
+
+g(A, B) ->
+    {A, B}.
+");
+    let findings = output.findings("repeated_pattern_variable");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:4:") && findings[0].contains("Variable A appears again"),
+            "{}", findings[0]);
+}
+
+#[test]
+fn binary_sizes_and_map_keys_use_variables() {
+    let output = check_module("size-key-uses", "-module(a).
+-export([header/1, pad/1, lookup/2, gen/2]).
+
+header(<<Size:8, Payload:Size/binary, _/binary>>) ->
+    Payload.
+
+pad(N) ->
+    <<0:N>>.
+
+lookup(Key, #{Key := Value}) ->
+    Value.
+
+gen(Bin, Width) ->
+    [V || <<V:Width>> <= Bin].
+");
+    assert!(output.findings("unused_variable").is_empty(), "{}", output.stdout);
+}
+
+#[test]
+fn anonymous_arguments_are_reported_from_min_count() {
+    let text = "-module(a).
+-export([f/3, g/2]).
+
+f(_, _, X) ->
+    X.
+
+g(_, Y) ->
+    Y.
+";
+    let output = check_module("anonymous", text);
+    let findings = output.findings("anonymous_variable");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:4:3: info [anonymous_variable] 2 arguments of f are ignored"),
+            "{}", findings[0]);
+
+    let output = TestProject::new("anonymous-min").file("src/a.erl", text)
+        .config("[lints.anonymous_variable]\nmin_count = 1\n")
+        .check();
+    assert_eq!(output.lines_of("anonymous_variable"), vec![4, 7], "{}", output.stdout);
+}
+
+#[test]
+fn fun_heads_and_generators_shadow_variables() {
+    let output = check_module("shadowed", "-module(a).
+-export([f/2, g/1]).
+
+f(X, L) ->
+    F = fun(X) -> X + 1 end,
+    [X || X <- L] ++ [F(1)].
+
+g(L) ->
+    F = fun(Y) -> Y + 1 end,
+    [F(Z) || Z <- L].
+");
+    assert_eq!(output.lines_of("shadowed_variable"), vec![5, 6], "{}", output.stdout);
+    assert!(output.findings("shadowed_variable")[0].contains("Variable X shadows a variable of the enclosing scope"),
+            "{}", output.stdout);
+}
+
+#[test]
+fn variable_bound_in_some_branches_is_unsafe() {
+    let output = check_module("unsafe-case", "-module(a).
+-export([f/1, g/1]).
+
+f(A) ->
+    case A of
+        1 -> B = one;
+        _ -> ok
+    end,
+    B.
+
+g(A) ->
+    case A of
+        1 -> B = one;
+        _ -> B = other
+    end,
+    B.
+");
+    let findings = output.findings("unsafe_variable");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:9:") && findings[0].contains(": error [unsafe_variable] Variable B is \
+                                  unsafe in 'case'"), "{}", findings[0]);
+}
+
+#[test]
+fn try_body_bindings_are_unsafe_after_the_try() {
+    let output = check_module("unsafe-try", "-module(a).
+-export([f/1, g/1]).
+
+f(A) ->
+    try
+        B = A + 1
+    of
+        _ -> ok
+    catch
+        _:_ -> B = 0
+    end,
+    B.
+
+g(A) ->
+    C = try
+        A + 1
+    catch
+        _:_ -> 0
+    end,
+    C.
+");
+    let findings = output.findings("unsafe_variable");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:12:") && findings[0].contains("Variable B is unsafe in 'try'"),
+            "{}", findings[0]);
+}
+
+#[test]
+fn clause_bindings_are_unsafe_after_the_try() {
+    let output = check_module("unsafe-try-clauses", "-module(a).
+-export([f/1]).
+
+f(A) ->
+    try A of
+        X -> Y = X
+    catch
+        _:_ -> Y = 0
+    end,
+    Y.
+");
+    let findings = output.findings("unsafe_variable");
+    assert_eq!(findings.len(), 1, "{}", output.stdout);
+    assert!(findings[0].contains("src/a.erl:10:") && findings[0].contains("Variable Y is unsafe in 'try'"),
+            "{}", findings[0]);
+}